@@ -597,6 +597,23 @@ pub extern "C" fn validate_atom(space: *const space_t, atom: *const atom_ref_t)
     hyperon::metta::types::validate_atom(dyn_space.borrow().as_space(), atom)
 }
 
+/// @brief Explains why `atom` is not correctly typed
+/// @ingroup metta_language_group
+/// @param[in]  space  A pointer to the `space_t` representing the space context in which to perform the check
+/// @param[in]  atom  A pointer to the `atom_t` or `atom_ref_t` representing the atom whose Type the function will check
+/// @return The `atom_t` describing the innermost ill-typed expression, or a NULL `atom_t` if the atom is correctly
+///    typed.  The diagnostic atom has the form `(BadArgType <expr> <position> <expected> (<actual>...) (<declarations>...))`
+///    or `(IncorrectNumberOfArguments <expr> <expected> <actual> (<declarations>...))`
+/// @note The caller must take ownership responsibility for the returned `atom_t`, if it is not NULL
+///
+#[no_mangle]
+pub extern "C" fn get_type_error(space: *const space_t, atom: *const atom_ref_t) -> atom_t {
+    let dyn_space = unsafe{ &*space }.borrow();
+    let atom = unsafe{ &*atom }.borrow();
+    hyperon::metta::types::get_type_error(dyn_space.borrow().as_space(), atom)
+        .map(|err| err.to_atom()).into()
+}
+
 /// @brief Provides all types for `atom` in the context of `space`
 /// @ingroup metta_language_group
 /// @param[in]  space  A pointer to the `space_t` representing the space context in which to access the Atom's types
@@ -1377,6 +1394,20 @@ pub extern "C" fn env_builder_set_is_test(builder: *mut env_builder_t, is_test:
     *builder_arg_ref = builder.into();
 }
 
+/// @brief Sets the seed for the random generators of the runners created within the environment
+/// @ingroup environment_group
+/// @param[in]  builder  A pointer to the in-process environment builder state
+/// @param[in]  seed  The seed value
+/// @note The seed can be changed for a specific runner by `!(pragma! random-seed <seed>)`
+///
+#[no_mangle]
+pub extern "C" fn env_builder_set_random_seed(builder: *mut env_builder_t, seed: u64) {
+    let builder_arg_ref = unsafe{ &mut *builder };
+    let builder = core::mem::replace(builder_arg_ref, env_builder_t::null()).into_inner();
+    let builder = builder.set_random_seed(seed);
+    *builder_arg_ref = builder.into();
+}
+
 /// @brief Adds a config directory to search for imports.  The most recently added paths will be searched
 ///     first, continuing in inverse order
 /// @ingroup environment_group
@@ -1395,6 +1426,43 @@ pub extern "C" fn env_builder_add_include_path(builder: *mut env_builder_t, path
     *builder_arg_ref = builder.into();
 }
 
+/// @brief Adds a directory which MeTTa code is allowed to access using file system operations.  When
+///     no directories are added only the working directory is allowed, or the process working
+///     directory if the working directory is not set
+/// @ingroup environment_group
+/// @param[in]  builder  A pointer to the in-process environment builder state
+/// @param[in]  path  A C-style string specifying a path to the allowed directory
+///
+#[no_mangle]
+pub extern "C" fn env_builder_add_allowed_path(builder: *mut env_builder_t, path: *const c_char) {
+    let builder_arg_ref = unsafe{ &mut *builder };
+    let builder = core::mem::replace(builder_arg_ref, env_builder_t::null()).into_inner();
+    let builder = if path.is_null() {
+        panic!("Fatal Error: path cannot be NULL");
+    } else {
+        builder.add_allowed_paths(vec![PathBuf::from(cstr_as_str(path))])
+    };
+    *builder_arg_ref = builder.into();
+}
+
+/// @brief Adds a name of the environment variable which MeTTa code is allowed to read.  When no names
+///     are added no environment variables can be read
+/// @ingroup environment_group
+/// @param[in]  builder  A pointer to the in-process environment builder state
+/// @param[in]  name  A C-style string specifying a name of the allowed environment variable
+///
+#[no_mangle]
+pub extern "C" fn env_builder_add_allowed_env_var(builder: *mut env_builder_t, name: *const c_char) {
+    let builder_arg_ref = unsafe{ &mut *builder };
+    let builder = core::mem::replace(builder_arg_ref, env_builder_t::null()).into_inner();
+    let builder = if name.is_null() {
+        panic!("Fatal Error: name cannot be NULL");
+    } else {
+        builder.add_allowed_env_vars([cstr_as_str(name)])
+    };
+    *builder_arg_ref = builder.into();
+}
+
 /// @brief Access the value of a grounded i64 atom
 /// @ingroup metta_language_group
 /// @param[in]  n  A pointer to an `atom_t` or an `atom_ref_t` to access
@@ -1462,6 +1530,62 @@ pub extern "C" fn grounded_number_get_double(n: *const atom_ref_t, res: *mut c_d
     }
 }
 
+/// @brief Renders the numerator of a grounded exact number into a text buffer
+/// @ingroup metta_language_group
+/// @param[in]  n  A pointer to an `atom_t` or an `atom_ref_t` to access
+/// @param[out]  buf  A buffer into which the text will be written
+/// @param[in]  buf_len  The maximum allocated size of `buf`
+/// @return The length of the decimal string, minus the string terminator character.  Zero is
+/// returned if the atom is not a grounded integer or rational number.  If
+/// `return_value > buf_len + 1`, then the text was not fully written and this function should be
+/// called again with a larger buffer.
+/// @note Integers of any size are accessible via this function, their denominator is 1
+#[no_mangle]
+pub extern "C" fn grounded_number_get_numerator(n: *const atom_ref_t, buf: *mut c_char, buf_len: usize) -> usize {
+    match atom_as_number(unsafe { (*n).borrow() }).and_then(Number::to_rational) {
+        Some(r) => write_into_buf(r.numer(), buf, buf_len),
+        None => 0,
+    }
+}
+
+/// @brief Renders the denominator of a grounded exact number into a text buffer
+/// @ingroup metta_language_group
+/// @param[in]  n  A pointer to an `atom_t` or an `atom_ref_t` to access
+/// @param[out]  buf  A buffer into which the text will be written
+/// @param[in]  buf_len  The maximum allocated size of `buf`
+/// @return The length of the decimal string, minus the string terminator character.  Zero is
+/// returned if the atom is not a grounded integer or rational number.  If
+/// `return_value > buf_len + 1`, then the text was not fully written and this function should be
+/// called again with a larger buffer.
+#[no_mangle]
+pub extern "C" fn grounded_number_get_denominator(n: *const atom_ref_t, buf: *mut c_char, buf_len: usize) -> usize {
+    match atom_as_number(unsafe { (*n).borrow() }).and_then(Number::to_rational) {
+        Some(r) => write_into_buf(r.denom(), buf, buf_len),
+        None => 0,
+    }
+}
+
+fn atom_as_number(atom: &Atom) -> Option<&Number> {
+    match atom {
+        Atom::Grounded(gnd) => gnd.as_any_ref().downcast_ref::<Number>(),
+        _ => None,
+    }
+}
+
+/// @brief Creates a grounded number atom from its text representation
+/// @ingroup metta_language_group
+/// @param[in]  s  A C-style string containing an integer of any size, a rational number
+///     written as `<numerator>/<denominator>` or a floating point number
+/// @return An `atom_t` for the number, or a null atom if the string cannot be parsed
+/// @note The caller must take ownership responsibility for the returned `atom_t`
+#[no_mangle]
+pub extern "C" fn str_into_grounded_number(s: *const c_char) -> atom_t {
+    match cstr_as_str(s).parse::<Number>() {
+        Ok(n) => Atom::gnd(n).into(),
+        Err(_) => atom_t::null(),
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn longlong_into_grounded_number(n: c_longlong) -> atom_t {
     Atom::gnd(Number::Integer(n)).into()
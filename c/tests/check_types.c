@@ -37,8 +37,20 @@ START_TEST (test_validate_atom)
 
     atom_t foo = expr(atom_sym("foo"), atom_sym("a"), atom_ref_null());
     ck_assert(validate_atom(&space, &foo));
+    atom_t no_error = get_type_error(&space, &foo);
+    ck_assert(atom_is_null(&no_error));
     atom_free(foo);
 
+    atom_t bad_foo = expr(atom_sym("foo"), atom_sym("b"), atom_ref_null());
+    ck_assert(!validate_atom(&space, &bad_foo));
+    atom_t error = get_type_error(&space, &bad_foo);
+    ck_assert(!atom_is_null(&error));
+    char* error_str = stratom(&error);
+    ck_assert_str_eq(error_str, "(BadArgType (foo b) 1 A (B) ((: foo (-> A B)) (: b B)))");
+    free(error_str);
+    atom_free(error);
+    atom_free(bad_foo);
+
     space_free(space);
 }
 END_TEST
@@ -342,6 +342,19 @@ impl Bindings {
         }
     }
 
+    /// Replaces the value of the `var` and of all variables which are equal
+    /// to it by `value` without matching the previous value. Returns `false`
+    /// if there is no binding for the `var`.
+    pub(crate) fn replace_var_value(&mut self, var: &VariableAtom, value: Atom) -> bool {
+        match self.binding_by_var.get(var) {
+            Some(&binding_id) => {
+                self.bindings[binding_id].atom = Some(value);
+                true
+            },
+            None => false,
+        }
+    }
+
     /// Tries to insert `value` as a binding for the `var`. If `self` already
     /// has binding for the `var` and it is not matchable with the `value` then
     /// function returns Err. Otherwise it returns updated Bindings.
@@ -104,7 +104,13 @@ macro_rules! expr {
 /// ```
 #[macro_export]
 macro_rules! sym {
-    ($x:literal) => { $crate::Atom::Symbol($crate::SymbolAtom::new($crate::common::collections::ImmutableString::Literal($x))) };
+    ($x:literal) => {{
+        fn id() -> $crate::SymbolId {
+            static ID: ::std::sync::OnceLock<$crate::SymbolId> = ::std::sync::OnceLock::new();
+            *ID.get_or_init(|| $crate::SymbolAtom::intern_static($x))
+        }
+        $crate::Atom::Symbol($crate::SymbolAtom::new($x, id))
+    }};
 }
 
 pub mod matcher;
@@ -116,29 +122,156 @@ pub use iter::*;
 use std::any::Any;
 use std::fmt::{Display, Debug};
 use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 
-use crate::common::collections::ImmutableString;
 use crate::common::ReplacingMapper;
 
 // Symbol atom
 
+/// Unique identifier of the interned symbol name. Two symbols have equal
+/// identifiers if and only if they have equal names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolId(usize);
+
+impl Display for SymbolId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
 /// A symbol atom structure.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Names of the symbols are interned in the global symbol table thus
+/// symbol keeps a reference to the unique copy of the name and an
+/// identifier which makes equality check and hashing cheap. Symbols created
+/// by [sym!] cannot access the symbol table at compile time, each [sym!] call
+/// site keeps a static cell which caches the identifier after the first
+/// lookup, thus the table is accessed only once per call site.
+#[derive(Clone)]
 pub struct SymbolAtom {
-    name: ImmutableString,
+    name: &'static str,
+    id: SymbolIdRef,
+}
+
+#[derive(Clone, Copy)]
+enum SymbolIdRef {
+    Interned(SymbolId),
+    Static(fn() -> SymbolId),
 }
 
 impl SymbolAtom {
-    /// Constructs new symbol from `name`. Not intended to be used directly,
-    /// use [sym!] or [Atom::sym] instead.
+    /// Constructs new symbol from `name` literal. Not intended to be used
+    /// directly, use [sym!] or [Atom::sym] instead.
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, id: fn() -> SymbolId) -> Self {
+        Self{ name, id: SymbolIdRef::Static(id) }
+    }
+
+    /// Returns identifier of the `name` literal. Not intended to be used
+    /// directly, it is called by [sym!] once per call site.
     #[doc(hidden)]
-    pub const fn new(name: ImmutableString) -> Self {
-        Self{ name }
+    pub fn intern_static(name: &'static str) -> SymbolId {
+        symbol_table::intern_static(name)
+    }
+
+    /// Constructs new symbol interning the `name` passed.
+    fn interned(name: &str) -> Self {
+        let (name, id) = symbol_table::intern(name);
+        Self{ name, id: SymbolIdRef::Interned(id) }
     }
 
     /// Returns the name of the symbol.
     pub fn name(&self) -> &str {
-        self.name.as_str()
+        self.name
+    }
+
+    /// Returns the unique identifier of the symbol name.
+    pub fn id(&self) -> SymbolId {
+        match self.id {
+            SymbolIdRef::Interned(id) => id,
+            SymbolIdRef::Static(id) => id(),
+        }
+    }
+}
+
+impl PartialEq for SymbolAtom {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for SymbolAtom {}
+
+impl std::hash::Hash for SymbolAtom {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id().hash(state)
+    }
+}
+
+impl Debug for SymbolAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SymbolAtom").field("name", &self.name).finish()
+    }
+}
+
+mod symbol_table {
+    use super::SymbolId;
+    use std::collections::HashMap;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::sync::{RwLock, OnceLock};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const SHARDS: usize = 16;
+
+    type Shard = RwLock<HashMap<&'static str, SymbolId>>;
+
+    /// Global table of the symbol names. The table is split into shards by
+    /// the hash of the name, each shard is locked separately and names which
+    /// are already in the table are looked up under the read lock only.
+    ///
+    /// Names are never removed from the table, thus memory allocated for a
+    /// name is kept until the end of the process. The memory used is bounded
+    /// by the total length of the distinct symbol names created by the
+    /// process, which is not an issue unless the program constructs an
+    /// unbounded number of different symbols.
+    fn shard(name: &str) -> &'static Shard {
+        static TABLE: OnceLock<[Shard; SHARDS]> = OnceLock::new();
+        let table = TABLE.get_or_init(|| std::array::from_fn(|_| RwLock::new(HashMap::new())));
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        &table[hasher.finish() as usize % SHARDS]
+    }
+
+    fn intern_with<F: FnOnce() -> &'static str>(name: &str, copy: F) -> (&'static str, SymbolId) {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+        let shard = shard(name);
+        if let Some((name, id)) = shard.read().unwrap().get_key_value(name) {
+            return (*name, *id);
+        }
+        let mut shard = shard.write().unwrap();
+        // name could be added by other thread after the read lock is released
+        match shard.get_key_value(name) {
+            Some((name, id)) => (*name, *id),
+            None => {
+                let name = copy();
+                let id = SymbolId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+                shard.insert(name, id);
+                (name, id)
+            },
+        }
+    }
+
+    /// Returns the unique copy of the `name` and its identifier, copies
+    /// `name` into the table if it is not there yet.
+    pub(super) fn intern(name: &str) -> (&'static str, SymbolId) {
+        intern_with(name, || Box::leak(name.to_string().into_boxed_str()))
+    }
+
+    /// Returns the identifier of the `name`. Unlike [intern] doesn't copy
+    /// the `name` which is already static.
+    pub(super) fn intern_static(name: &'static str) -> SymbolId {
+        intern_with(name, || name).1
     }
 }
 
@@ -151,7 +284,7 @@ impl Display for SymbolAtom {
 // Expression atom
 
 /// An expression atom structure.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ExpressionAtom {
     children: Vec<Atom>,
 }
@@ -347,6 +480,8 @@ pub trait GroundedAtom : mopa::Any + Debug + Display {
     fn type_(&self) -> Atom;
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError>;
     fn match_(&self, other: &Atom) -> matcher::MatchResultIter;
+    fn hash_gnd(&self, state: &mut dyn Hasher);
+    fn is_type_level(&self) -> bool;
 }
 
 mopafy!(GroundedAtom);
@@ -415,6 +550,22 @@ pub trait Grounded : Display {
     /// [matcher::Bindings] for the variables of the `other` atom.
     /// See [matcher] for detailed explanation.
     fn match_(&self, other: &Atom) -> matcher::MatchResultIter;
+
+    /// Feeds the value of the grounded atom into the `state` to calculate
+    /// the hash of the atom. Implementation should be consistent with
+    /// `PartialEq`: equal values should feed the same data into the `state`.
+    /// Default implementation feeds nothing, thus the hash of the atom
+    /// depends only on the Rust type of the value. It is correct but makes
+    /// all values of the type collide in hash tables.
+    fn hash_gnd(&self, _state: &mut dyn Hasher) {}
+
+    /// Returns true if the grounded function has no side effects and is
+    /// cheap to compute. Such functions are executed by the type checker to
+    /// reduce type expressions, see [crate::metta::types::reduce_type].
+    /// Default implementation returns false.
+    fn is_type_level(&self) -> bool {
+        false
+    }
 }
 
 /// Returns the name of the Rust type wrapped into [Atom::Symbol]. This is a
@@ -484,6 +635,15 @@ impl<T: AutoGroundedType> GroundedAtom for AutoGroundedAtom<T> {
     fn match_(&self, other: &Atom) -> matcher::MatchResultIter {
         match_by_equality(&self.0, other)
     }
+
+    fn hash_gnd(&self, _state: &mut dyn Hasher) {
+        // AutoGroundedType doesn't require Hash to be implemented, thus
+        // values are distinguished by Rust type only
+    }
+
+    fn is_type_level(&self) -> bool {
+        false
+    }
 }
 
 impl<T: AutoGroundedType> Display for AutoGroundedAtom<T> {
@@ -534,6 +694,14 @@ impl<T: CustomGroundedType> GroundedAtom for CustomGroundedAtom<T> {
     fn match_(&self, other: &Atom) -> matcher::MatchResultIter {
         Grounded::match_(&self.0, other)
     }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        Grounded::hash_gnd(&self.0, state)
+    }
+
+    fn is_type_level(&self) -> bool {
+        Grounded::is_type_level(&self.0)
+    }
 }
 
 impl<T: CustomGroundedType> Display for CustomGroundedAtom<T> {
@@ -582,6 +750,14 @@ impl PartialEq for Box<dyn GroundedAtom> {
 
 impl Eq for Box<dyn GroundedAtom> {}
 
+impl Hash for Box<dyn GroundedAtom> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // eq_gnd() never returns true for the values of different Rust types
+        self.as_any_ref().type_id().hash(state);
+        self.hash_gnd(state);
+    }
+}
+
 impl Clone for Box<dyn GroundedAtom> {
     fn clone(&self) -> Self {
         self.clone_gnd()
@@ -633,10 +809,8 @@ impl Atom {
     /// assert_eq!(a, aa);
     /// assert_ne!(a, b);
     /// ```
-    // TODO: can be rewritten using Into<ImmutableString> to convert &str
-    // literasl into ImmutableString::Literal.
-    pub fn sym<T: Into<String>>(name: T) -> Self {
-        Self::Symbol(SymbolAtom::new(ImmutableString::Allocated(name.into())))
+    pub fn sym<T: AsRef<str>>(name: T) -> Self {
+        Self::Symbol(SymbolAtom::interned(name.as_ref()))
     }
 
     /// Constructs expression out of array of children.
@@ -765,6 +939,18 @@ impl PartialEq for Atom {
     }
 }
 
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Atom::Symbol(sym) => sym.hash(state),
+            Atom::Expression(expr) => expr.hash(state),
+            Atom::Variable(var) => var.hash(state),
+            Atom::Grounded(gnd) => gnd.hash(state),
+        }
+    }
+}
+
 impl TryFrom<Atom> for VariableAtom {
     type Error = &'static str;
     fn try_from(atom: Atom) -> Result<Self, &'static str> {
@@ -898,7 +1084,7 @@ mod test {
 
     #[inline]
     fn symbol(name: &'static str) -> Atom {
-        Atom::Symbol(SymbolAtom::new(ImmutableString::Literal(name)))
+        Atom::sym(name)
     }
 
     #[inline]
@@ -971,6 +1157,43 @@ mod test {
         assert_eq!(expr!("foo"), symbol("foo"));
     }
 
+    #[test]
+    fn test_hash_atom() {
+        use std::collections::HashSet;
+        let atoms = [expr!("A" x {6} ("B" y)), expr!("A" x {6} ("B" y)),
+            expr!("A" x {7} ("B" y)), expr!("A" y {6} ("B" y)),
+            Atom::gnd(TestInteger(6)), Atom::gnd(TestInteger(6)), Atom::value(6)];
+        let set: HashSet<Atom> = atoms.iter().cloned().collect();
+
+        assert_eq!(set.len(), 5);
+        atoms.iter().for_each(|atom| assert!(set.contains(atom)));
+    }
+
+    #[test]
+    fn test_symbol_interning() {
+        fn hash(sym: &SymbolAtom) -> u64 {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            sym.hash(&mut hasher);
+            hasher.finish()
+        }
+        fn literal() -> SymbolAtom {
+            const LITERAL: Atom = sym!("interned-symbol");
+            <SymbolAtom>::try_from(LITERAL).unwrap()
+        }
+        let static_sym = literal();
+        let interned = <SymbolAtom>::try_from(Atom::sym(String::from("interned-symbol"))).unwrap();
+        let other = <SymbolAtom>::try_from(Atom::sym("other-symbol")).unwrap();
+
+        assert_eq!(interned.name(), "interned-symbol");
+        assert_eq!(static_sym, interned);
+        assert_eq!(static_sym.id(), interned.id());
+        assert_eq!(hash(&static_sym), hash(&interned));
+        assert_ne!(interned, other);
+        assert_ne!(interned.id(), other.id());
+        assert_eq!(Atom::sym("interned-symbol"), Atom::sym(String::from("interned-symbol")));
+    }
+
     #[test]
     fn test_expr_variable() {
         assert_eq!(expr!(n), variable("n"));
@@ -1015,8 +1238,8 @@ mod test {
 
     #[test]
     fn test_display_atom() {
-        assert_eq!(format!("{}", Atom::Symbol(SymbolAtom::new(ImmutableString::Literal("test")))), "test");
-        assert_eq!(format!("{}", Atom::Symbol(SymbolAtom::new(ImmutableString::Allocated("test".into())))), "test");
+        assert_eq!(format!("{}", sym!("test")), "test");
+        assert_eq!(format!("{}", Atom::sym(String::from("test"))), "test");
         assert_eq!(format!("{}", Atom::var("x")), "$x");
         assert_eq!(format!("{}", Atom::value(42)), "42");
         assert_eq!(format!("{}", Atom::value([1, 2, 3])), "[1, 2, 3]");
@@ -43,7 +43,7 @@ use std::marker::PhantomData;
 use crate::common::shared::Shared;
 
 /// Single token of [TrieKey]. Each kind of token has its own recognition rules.
-#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
 pub enum TrieToken<T> {
     /// Exact token recognizes another instance of [TrieToken::Exact] which
     /// has the equal value inside.
@@ -231,6 +231,38 @@ where
         self.0.insert(key, value)
     }
 
+    /// Insert a batch of `(key, value)` pairs. Result is the same as inserting
+    /// pairs one by one using [MultiTrie::insert], but the batch is sorted by
+    /// key first, so keys with a common prefix are adjacent and each node of
+    /// the trie is visited once per batch instead of once per key. It makes
+    /// building a large trie from scratch significantly cheaper.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::common::multitrie::*;
+    ///
+    /// fn collect<'a, T, I>(it: I) -> Vec<T> where T: Clone + 'a, I: Iterator<Item=&'a T>, {
+    ///     it.cloned().collect()
+    /// }
+    ///
+    /// let mut trie = MultiTrie::new();
+    ///
+    /// let ab = TrieKey::from([TrieToken::Exact("A"), TrieToken::Exact("B")]);
+    /// let ac = TrieKey::from([TrieToken::Exact("A"), TrieToken::Exact("C")]);
+    ///
+    /// trie.insert_batch([(ab.clone(), "AB"), (ac.clone(), "AC")]);
+    ///
+    /// assert_eq!(collect(trie.get(&ab)), vec!["AB"]);
+    /// assert_eq!(collect(trie.get(&ac)), vec!["AC"]);
+    /// ```
+    pub fn insert_batch<I: IntoIterator<Item=(TrieKey<K>, V)>>(&mut self, entries: I) where K: Ord {
+        let mut entries: Vec<(TrieKey<K>, V)> = entries.into_iter().collect();
+        log::debug!("MultiTrie::insert_batch(): {} entries", entries.len());
+        entries.sort_unstable_by(|(a, _), (b, _)| a.tokens.cmp(&b.tokens));
+        self.0.insert_sorted(entries);
+    }
+
     /// Get values from the trie by the given `key`. Returns an iterator through
     /// values found.
     ///
@@ -424,6 +456,49 @@ where
         }
     }
 
+    /// Inserts entries sorted by key. Entries which have the same head token
+    /// are adjacent and inserted into the child node together. Returns the
+    /// stacks of the right parenthesis nodes collected for each entry, see
+    /// [MultiTrieNode::insert_internal] for the meaning of the stack.
+    fn insert_sorted(&mut self, entries: Vec<(TrieKey<K>, V)>) -> Vec<Vec<Shared<Self>>> {
+        let mut right_par_stacks = Vec::with_capacity(entries.len());
+        let mut entries = entries.into_iter().peekable();
+        while let Some((mut key, value)) = entries.next() {
+            let token = match key.pop_head() {
+                None => {
+                    self.values.insert(value);
+                    right_par_stacks.push(Vec::new());
+                    continue;
+                },
+                Some(token) => token,
+            };
+            let mut group = vec![(key, value)];
+            while let Some((mut key, value)) = entries.next_if(|(key, _)| key.tokens.front() == Some(&token)) {
+                key.pop_head();
+                group.push((key, value));
+            }
+            let child = self.get_or_insert_child(token.clone());
+            let child_stacks = child.borrow_mut().insert_sorted(group);
+            match token {
+                TrieToken::LeftPar => {
+                    for mut right_par_nodes in child_stacks {
+                        let right_par = right_par_nodes.pop().expect("Unbalanced key");
+                        self.end_of_expr.insert(right_par.as_ptr(), right_par);
+                        right_par_stacks.push(right_par_nodes);
+                    }
+                },
+                TrieToken::RightPar => {
+                    for mut right_par_nodes in child_stacks {
+                        right_par_nodes.push(child.clone());
+                        right_par_stacks.push(right_par_nodes);
+                    }
+                },
+                _ => right_par_stacks.extend(child_stacks),
+            }
+        }
+        right_par_stacks
+    }
+
     fn get<'a>(&'a self, key: &'a TrieKey<K>) -> impl Iterator<Item=&'a V> + 'a {
         MultiValueIter::new(self, key.iter()).flat_map(|node| node.values.iter())
     }
@@ -597,6 +672,29 @@ mod test {
         assert_eq!(trie.get(&triekey!([*])).to_sorted(), vec!["pars_a", "pars_b"]);
     }
 
+    #[test]
+    fn multi_trie_insert_batch() {
+        let entries = vec![
+            (triekey!("A"), "exact_a"),
+            (triekey!(*), "wild"),
+            (triekey!(["A", "B"]), "pars_a_b"),
+            (triekey!("A", "B"), "a_b"),
+            (triekey!([["A"], "B"], "C"), "pars_pars_a_b_c"),
+            (triekey!([["A"], *], *), "pars_pars_a_x_x"),
+        ];
+        let mut expected = MultiTrie::new();
+        entries.iter().cloned().for_each(|(key, value)| expected.insert(key, value));
+        let mut trie = MultiTrie::new();
+        trie.insert_batch(entries.clone());
+
+        assert_eq!(trie.size(), expected.size());
+        for (key, _value) in entries {
+            assert_eq!(trie.get(&key).to_sorted(), expected.get(&key).to_sorted());
+        }
+        let query = triekey!([*, "B"], *);
+        assert_eq!(trie.get(&query).to_sorted(), vec!["pars_pars_a_b_c", "pars_pars_a_x_x"]);
+    }
+
     #[test]
     fn multi_trie_remove_basic() {
         let mut trie = MultiTrie::new();
@@ -30,6 +30,11 @@
 //!     then the whole expression is not interpreted further.
 //!   * Note: this step may return more than one result because each
 //!     argument can be interpreted by more than one way.
+//!   * In gradual typing mode (see [interpret_init_gradual]) the argument
+//!     which is a call of the operation without function type is not checked
+//!     before the call. Instead each value it returns is checked against the
+//!     type of the argument. If value has incorrect type then the error which
+//!     explains the mismatch is returned.
 //! * If operation type is not function:
 //!   * Return a sequence plan which interprets each member using
 //!     `Undefined` type and calls resulting expression. If member's
@@ -69,16 +74,16 @@ use crate::common::plan::*;
 use crate::atom::subexpr::*;
 use crate::atom::matcher::*;
 use crate::space::*;
-use crate::common::collections::ListMap;
 use crate::metta::*;
 use crate::metta::types::{is_func, get_arg_types, get_type_bindings,
-    get_atom_types, match_reducted_types};
+    get_atom_types, match_reducted_types, check_type, get_type_error};
 use crate::common::ReplacingMapper;
 
 use std::ops::Deref;
 use std::rc::Rc;
+use std::cell::Cell;
 use std::fmt::{Debug, Display, Formatter};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Wrapper, So the old interpreter can present the same public interface as the new intperpreter
 pub struct InterpreterState<'a, T: SpaceRef<'a>> {
@@ -173,12 +178,25 @@ type NoInputPlan<'a> = Box<dyn Plan<'a, (), Results, InterpreterError> + 'a>;
 /// * `space` - atomspace to query for interpretation
 /// * `expr` - atom to interpret
 pub fn interpret_init<'a, T: Space + 'a>(space: T, expr: &Atom) -> InterpreterState<'a, T> {
-    let step_result = interpret_init_internal(space, expr);
+    let step_result = interpret_init_internal(space, expr, false, None);
     InterpreterState { step_result: step_result, phantom: <_>::default() }
 }
 
-fn interpret_init_internal<'a, T: Space + 'a>(space: T, expr: &Atom) -> StepResult<'a, Results, InterpreterError> {
-    let context = InterpreterContextRef::new(space);
+/// Initialize interpreter in gradual typing mode and returns the result of
+/// the zero step. In this mode values returned by the untyped calls are
+/// checked at runtime when they are passed to the typed functions.
+/// See [crate::metta::interpreter] for algorithm explanation.
+///
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+pub fn interpret_init_gradual<'a, T: Space + 'a>(space: T, expr: &Atom) -> InterpreterState<'a, T> {
+    let step_result = interpret_init_internal(space, expr, true, None);
+    InterpreterState { step_result, phantom: <_>::default() }
+}
+
+fn interpret_init_internal<'a, T: Space + 'a>(space: T, expr: &Atom, gradual: bool, limit: Option<ResultsLimit>) -> StepResult<'a, Results, InterpreterError> {
+    let context = InterpreterContextRef::new(space, gradual, limit);
     interpret_as_type_plan(context,
         InterpretedAtom(expr.clone(), Bindings::new()),
         ATOM_TYPE_UNDEFINED)
@@ -206,7 +224,36 @@ pub fn interpret_step<'a, T: Space + 'a>(step: InterpreterState<'a, T>) -> Inter
 /// * `space` - atomspace to query for interpretation
 /// * `expr` - atom to interpret
 pub fn interpret<T: Space>(space: T, expr: &Atom) -> Result<Vec<Atom>, String> {
-    let mut step = interpret_init(space, expr);
+    interpret_to_completion(interpret_init(space, expr))
+}
+
+/// Interpret passed atom in gradual typing mode, see [interpret_init_gradual].
+/// This function blocks until result is calculated.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+pub fn interpret_gradual<T: Space>(space: T, expr: &Atom) -> Result<Vec<Atom>, String> {
+    interpret_to_completion(interpret_init_gradual(space, expr))
+}
+
+/// Interpret passed atom and return no more than `limit` first results.
+/// Alternatives which are left when enough results are found are not
+/// explored. This function blocks until result is calculated.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+/// * `limit` - maximum number of results to return
+pub fn interpret_limited<T: Space>(space: T, expr: &Atom, limit: usize) -> Result<Vec<Atom>, String> {
+    if limit == 0 {
+        return Ok(vec![]);
+    }
+    let step_result = interpret_init_internal(space, expr, false, Some(Rc::new(Cell::new(limit))));
+    let mut result = interpret_to_completion(InterpreterState::<T>{ step_result, phantom: <_>::default() })?;
+    result.truncate(limit);
+    Ok(result)
+}
+
+fn interpret_to_completion<'a, T: Space + 'a>(mut step: InterpreterState<'a, T>) -> Result<Vec<Atom>, String> {
     while step.step_result.has_next() {
         step = interpret_step(step);
     }
@@ -219,14 +266,12 @@ pub fn interpret<T: Space>(space: T, expr: &Atom) -> Result<Vec<Atom>, String> {
     }
 }
 
-// TODO: ListMap is not effective but we cannot use HashMap here without
-// requiring hash functions for the grounded atoms.
 #[derive(Debug)]
-struct InterpreterCache(ListMap<Atom, Results>);
+struct InterpreterCache(HashMap<Atom, Results>);
 
 impl InterpreterCache {
     fn new() -> Self {
-        Self(ListMap::new())
+        Self(HashMap::new())
     }
 
     fn get(&self, key: &Atom) -> Option<Results> {
@@ -254,7 +299,7 @@ impl InterpreterCache {
             res.0 = apply_bindings_to_atom(&res.0, &res.1);
             res.1.retain(|v| vars.contains(v));
         });
-        self.0.insert(key, value)
+        self.0.insert(key, value);
     }
 
     fn reset(&mut self) {
@@ -277,16 +322,30 @@ impl<'a, T: Space + 'a> SpaceRef<'a> for T {}
 struct InterpreterContext<'a, T: SpaceRef<'a>> {
     space: T,
     cache: SpaceObserverRef<InterpreterCache>,
+    gradual: bool,
     phantom: PhantomData<&'a T>,
 }
 
-struct InterpreterContextRef<'a, T: SpaceRef<'a>>(Rc<InterpreterContext<'a, T>>);
+// Number of results which are still required from the alternative being
+// interpreted. It is shared by the alternatives plans which give the results
+// of the root atom, each plan updates it before interpreting next alternative.
+// Alternatives plans of the arguments are not limited.
+type ResultsLimit = Rc<Cell<usize>>;
+
+struct InterpreterContextRef<'a, T: SpaceRef<'a>> {
+    context: Rc<InterpreterContext<'a, T>>,
+    limit: Option<ResultsLimit>,
+}
 
 impl<'a, T: SpaceRef<'a>> InterpreterContextRef<'a, T> {
-    fn new(space: T) -> Self {
+    fn new(space: T, gradual: bool, limit: Option<ResultsLimit>) -> Self {
         let cache = space.common().register_observer(InterpreterCache::new());
 
-        Self(Rc::new(InterpreterContext{ space, cache, phantom: PhantomData }))
+        Self{ context: Rc::new(InterpreterContext{ space, cache, gradual, phantom: PhantomData }), limit }
+    }
+
+    fn unlimited(&self) -> Self {
+        Self{ context: Rc::clone(&self.context), limit: None }
     }
 }
 
@@ -294,13 +353,13 @@ impl<'a, T: SpaceRef<'a>> Deref for InterpreterContextRef<'a, T> {
     type Target = InterpreterContext<'a, T>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.context
     }
 }
 
 impl<'a, T: SpaceRef<'a>> Clone for InterpreterContextRef<'a, T> {
     fn clone(&self) -> Self {
-        Self(Rc::clone(&self.0))
+        Self{ context: Rc::clone(&self.context), limit: self.limit.clone() }
     }
 }
 
@@ -395,7 +454,7 @@ fn interpret_expression_as_type_plan<'a, T: SpaceRef<'a>>(context: InterpreterCo
         input: InterpretedAtom, typ: Atom) -> OperatorPlan<'a, Vec<Atom>, Results, InterpreterError> {
     let descr = format!("form alternative plans for expression {} using types", input);
     OperatorPlan::new(move |op_types: Vec<Atom>| {
-        make_alternives_plan(input.0.clone(), op_types, move |op_typ| {
+        make_alternives_plan(input.0.clone(), op_types, context.limit.clone(), move |op_typ| {
             interpret_expression_as_type_op(context.clone(),
                 input.clone(), op_typ, typ.clone())
         })
@@ -442,17 +501,26 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
                 } else {
                     op_typ.clone()
                 };
-                let context = context.clone();
+                let check_at_runtime = context.gradual && expr_idx > 0
+                    && is_untyped_call(&context.space, &arg);
+                let context = context.unlimited();
                 plan = Box::new(SequencePlan::new(
                     plan,
                     OperatorPlan::new(move |results: Results| {
-                        make_alternives_plan(arg.clone(), results, move |result| -> NoInputPlan {
+                        make_alternives_plan(arg.clone(), results, None, move |result| -> NoInputPlan {
                             let arg_typ = apply_bindings_to_atom(&arg_typ, result.bindings());
-                            Box::new(SequencePlan::new(
-                                interpret_as_type_plan(context.clone(),
-                                    InterpretedAtom(arg.clone(), result.bindings().clone()),
-                                    arg_typ),
-                                insert_reducted_arg_plan(result, expr_idx)))
+                            let interpret_arg = interpret_as_type_plan(context.clone(),
+                                InterpretedAtom(arg.clone(), result.bindings().clone()),
+                                arg_typ.clone());
+                            if check_at_runtime {
+                                Box::new(SequencePlan::new(
+                                    SequencePlan::new(interpret_arg,
+                                        check_arg_type_plan(context.clone(), result.clone(), expr_idx, arg_typ)),
+                                    insert_reducted_arg_plan(result, expr_idx)))
+                            } else {
+                                Box::new(SequencePlan::new(interpret_arg,
+                                    insert_reducted_arg_plan(result, expr_idx)))
+                            }
                         })
                     }, format!("Interpret {} argument", expr_idx))
                 ))
@@ -464,11 +532,11 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
         let mut plan: NoInputPlan = Box::new(StepResult::ret(vec![input.clone()]));
         for expr_idx in 0..(expr.children().len()) {
             let arg = expr.children()[expr_idx].clone();
-            let context = context.clone();
+            let context = context.unlimited();
             plan = Box::new(SequencePlan::new(
                 plan,
                 OperatorPlan::new(move |results: Results| {
-                    make_alternives_plan(arg.clone(), results, move |result| -> NoInputPlan {
+                    make_alternives_plan(arg.clone(), results, None, move |result| -> NoInputPlan {
                         Box::new(SequencePlan::new(
                             interpret_as_type_plan(context.clone(),
                                 InterpretedAtom(arg.clone(), result.bindings().clone()),
@@ -485,12 +553,47 @@ fn interpret_expression_as_type_op<'a, T: SpaceRef<'a>>(context: InterpreterCont
 fn call_alternatives_plan<'a, T: SpaceRef<'a>>(plan: NoInputPlan<'a>, context: InterpreterContextRef<'a, T>,
     input: InterpretedAtom) -> NoInputPlan<'a> {
     Box::new(SequencePlan::new(plan, OperatorPlan::new(move |results: Results| {
-        make_alternives_plan(input.0, results, move |result| {
+        make_alternives_plan(input.0, results, context.limit.clone(), move |result| {
             call_plan(context.clone(), result)
         })
     }, "interpret each alternative")))
 }
 
+fn is_untyped_call(space: &dyn Space, atom: &Atom) -> bool {
+    match atom {
+        Atom::Expression(expr) if !expr.children().is_empty() =>
+            !get_atom_types(space, &expr.children()[0]).iter().any(is_func),
+        _ => false,
+    }
+}
+
+fn check_arg_type_plan<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>,
+        expr: InterpretedAtom, atom_idx: usize, typ: Atom) -> OperatorPlan<'a, Results, Results, InterpreterError> {
+    let descr = format!("check type of the child {} of {} at runtime", atom_idx, expr);
+    OperatorPlan::new(move |arg_variants| check_arg_type_op(context, expr, atom_idx, typ, arg_variants), descr)
+}
+
+fn check_arg_type_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>,
+        expr: InterpretedAtom, atom_idx: usize, typ: Atom, arg_variants: Results) -> StepResult<'a, Results, InterpreterError> {
+    let (checked, failed): (Results, Results) = arg_variants.into_iter()
+        .partition(|InterpretedAtom(arg, bindings)| {
+            atom_is_error(arg) || check_type(&context.space, arg, &apply_bindings_to_atom(&typ, bindings))
+        });
+    match failed.into_iter().next() {
+        Some(InterpretedAtom(arg, bindings)) if checked.is_empty() => {
+            log::debug!("check_arg_type_op: value {} of the child {} of {} has incorrect type", arg, atom_idx, expr);
+            let call_site = get_expr(expr.atom()).children()[atom_idx].clone();
+            let mut call = expr.atom().clone();
+            get_expr_mut(&mut call).children_mut()[atom_idx] = arg;
+            let call = apply_bindings_to_atom(&call, &bindings);
+            let reason = get_type_error(&context.space, &call)
+                .map_or(BAD_TYPE_SYMBOL, |err| err.to_atom());
+            StepResult::err((call_site, reason))
+        },
+        _ => StepResult::ret(checked),
+    }
+}
+
 fn insert_reducted_arg_plan<'a>(expr: InterpretedAtom, atom_idx: usize) -> OperatorPlan<'a, Results, Results, InterpreterError> {
     let descr = format!("insert right element as child {} of left element", atom_idx);
     OperatorPlan::new(move |arg_variants| insert_reducted_arg_op(expr, atom_idx, arg_variants), descr)
@@ -524,7 +627,9 @@ fn call_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input: In
         return_cached_result_plan(result)
     } else {
         if let Atom::Expression(expr) = input.atom() {
-            if !has_grounded_sub_expr(expr) {
+            // Results are not cached when limit is set because some of them
+            // may be not calculated
+            if context.limit.is_none() && !has_grounded_sub_expr(expr) {
                 let key = input.atom().clone();
                 StepResult::execute(SequencePlan::new(
                     OrPlan::new(
@@ -596,7 +701,7 @@ fn execute_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input:
                         if results.is_empty() {
                             StepResult::ret(results)
                         } else {
-                            make_alternives_plan(input.0, results, move |result| {
+                            make_alternives_plan(input.0, results, context.limit.clone(), move |result| {
                                 interpret_as_type_plan(context.clone(),
                                     result, ATOM_TYPE_UNDEFINED)
                             })
@@ -641,13 +746,13 @@ fn match_op<'a, T: SpaceRef<'a>>(context: InterpreterContextRef<'a, T>, input: I
         .filter(|(_, bindings)| bindings.is_ok())
         .map(|(result, bindings)| InterpretedAtom(result, bindings.unwrap()))
         .collect();
-    make_alternives_plan(input.0, results, move |result| {
+    make_alternives_plan(input.0, results, context.limit.clone(), move |result| {
         interpret_as_type_plan(context.clone(), result, ATOM_TYPE_UNDEFINED)
     })
 }
 
 fn make_alternives_plan<'a, T: Debug, F, P>(input: Atom, mut results: Vec<T>,
-    plan: F) -> StepResult<'a, Results, InterpreterError>
+    limit: Option<ResultsLimit>, plan: F) -> StepResult<'a, Results, InterpreterError>
 where
     F: 'a + Fn(T) -> P,
     P: 'a + Plan<'a, (), Results, InterpreterError>
@@ -661,7 +766,7 @@ where
                 input,
                 results.drain(0..)
                     .map(|result| -> NoInputPlan { Box::new(plan(result)) })
-                    .collect()))
+                    .collect()).with_limit(limit))
         },
     }
 }
@@ -671,11 +776,16 @@ use std::collections::VecDeque;
 /// Plan which interprets in parallel alternatives of the expression.
 /// Each successful result is appended to the overall result of the plan.
 /// If no alternatives returned successful result the plan returns error.
+/// If limit is set the plan returns as soon as enough results are collected
+/// and rest of the alternatives are not interpreted. In this case alternatives
+/// are interpreted step by step in turn, thus an alternative which never
+/// finishes doesn't prevent getting results of the others.
 pub struct AlternativeInterpretationsPlan<'a, T> {
     atom: Atom,
     plans: VecDeque<Box<dyn Plan<'a, (), Vec<T>, InterpreterError> + 'a>>,
     results: Vec<T>,
     success: bool,
+    limit: Option<(usize, ResultsLimit)>,
 }
 
 impl<'a, T> AlternativeInterpretationsPlan<'a, T> {
@@ -685,7 +795,12 @@ impl<'a, T> AlternativeInterpretationsPlan<'a, T> {
     /// `atom` - atom to be printed as root of the alternative interpretations
     /// `plan` - altenative plans for the atom
     pub fn new(atom: Atom, plans: Vec<Box<dyn Plan<'a, (), Vec<T>, InterpreterError> + 'a>>) -> Self {
-        Self{ atom, plans: plans.into(), results: Vec::new(), success: false }
+        Self{ atom, plans: plans.into(), results: Vec::new(), success: false, limit: None }
+    }
+
+    fn with_limit(mut self, limit: Option<ResultsLimit>) -> Self {
+        self.limit = limit.map(|limit| (limit.get(), limit));
+        self
     }
 }
 
@@ -700,15 +815,25 @@ impl<'a, T: Debug> Plan<'a, (), Vec<T>, InterpreterError> for AlternativeInterpr
             }
         } else {
             let plan = self.plans.pop_front().unwrap();
+            if let Some((required, limit)) = &self.limit {
+                limit.set(required - self.results.len());
+            }
             match plan.step(()) {
                 StepResult::Execute(next) => {
-                    self.plans.push_front(next);
+                    if self.limit.is_some() {
+                        self.plans.push_back(next);
+                    } else {
+                        self.plans.push_front(next);
+                    }
                     StepResult::Execute(self)
                 },
                 StepResult::Return(mut result) => {
                     self.results.append(&mut result);
                     self.success = true;
-                    StepResult::Execute(self)
+                    match self.limit {
+                        Some((required, _)) if self.results.len() >= required => StepResult::ret(self.results),
+                        _ => StepResult::Execute(self),
+                    }
                 },
                 StepResult::Error(err) => {
                     log::debug!("skip alternative because of error returned: {:?}", err);
@@ -813,7 +938,7 @@ mod tests {
     #[test]
     fn test_make_alternatives_plan_no_alternative() {
         let plan = make_alternives_plan(sym!("Test"),
-            vec![], |_res: InterpretedAtom| StepResult::ret(vec![]));
+            vec![], None, |_res: InterpretedAtom| StepResult::ret(vec![]));
 
         let result = test_interpret(plan, ());
 
@@ -864,6 +989,67 @@ mod tests {
         assert_eq!(Err((sym!("Test"), NO_VALID_ALTERNATIVES)), result);
     }
 
+    #[test]
+    fn test_alternatives_plan_limit() {
+        let plan = AlternativeInterpretationsPlan::new(sym!("Test"),
+            vec![Box::new(StepResult::ret(vec!["A", "B"])),
+                Box::new(OperatorPlan::new(|_| -> StepResult<Vec<&str>, InterpreterError> {
+                    panic!("Alternative is not expected to be interpreted")
+                }, "unexpected alternative"))]).with_limit(Some(Rc::new(Cell::new(2))));
+
+        let result = test_interpret(plan, ());
+
+        assert_eq!(Ok(vec!["A", "B"]), result);
+    }
+
+    #[derive(PartialEq, Clone, Debug)]
+    struct Naturals();
+
+    impl Grounded for Naturals {
+        fn type_(&self) -> Atom {
+            expr!("->" "i32" "%Undefined%")
+        }
+        fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+            let n = *args[0].as_gnd::<i32>().unwrap();
+            Ok(vec![Atom::value(n), Atom::expr([Atom::gnd(Naturals()), Atom::value(n + 1)])])
+        }
+        fn match_(&self, other: &Atom) -> matcher::MatchResultIter {
+            match_by_equality(self, other)
+        }
+    }
+
+    impl Display for Naturals {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "naturals")
+        }
+    }
+
+    #[test]
+    fn test_interpret_limited() {
+        let space = GroundingSpace::new();
+        let naturals = Atom::expr([Atom::gnd(Naturals()), Atom::value(0)]);
+
+        assert_eq!(interpret_limited(&space, &naturals, 3),
+            Ok(vec![Atom::value(0), Atom::value(1), Atom::value(2)]));
+        assert_eq!(interpret_limited(&space, &naturals, 0), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_interpret_limited_returns_all_results_when_less_than_limit() {
+        let mut space = GroundingSpace::new();
+        space.add(expr!("=" ("color") "red"));
+        space.add(expr!("=" ("color") "green"));
+
+        let result = interpret_limited(&space, &expr!(("color")), 1).unwrap();
+        assert_eq!(result.len(), 1);
+        assert!(result[0] == expr!("red") || result[0] == expr!("green"));
+        assert_eq_no_order!(interpret_limited(&space, &expr!(("color")), 5).unwrap(),
+            vec![expr!("red"), expr!("green")]);
+        // partial result of the limited call is not cached
+        assert_eq_no_order!(interpret(&space, &expr!(("color"))).unwrap(),
+            vec![expr!("red"), expr!("green")]);
+    }
+
     #[test]
     fn test_variable_defined_via_variable() {
         let mut space = GroundingSpace::new();
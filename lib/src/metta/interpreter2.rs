@@ -205,6 +205,12 @@ impl<'a, T: SpaceRef<'a>> InterpreterState<'a, T> {
         }
     }
 
+    /// Returns results which are found so far and removes them from the state
+    #[allow(dead_code)] //TODO: MINIMAL only silence the warning until interpreter2 replaces interpreter
+    pub(crate) fn take_results(&mut self) -> Vec<Atom> {
+        std::mem::take(&mut self.finished)
+    }
+
     fn pop(&mut self) -> Option<InterpretedAtom> {
         self.plan.pop()
     }
@@ -276,6 +282,23 @@ pub fn interpret<T: Space>(space: T, expr: &Atom) -> Result<Vec<Atom>, String> {
     state.into_result()
 }
 
+/// Interpret passed atom and return no more than `limit` first results.
+/// Interpretation is stopped as soon as enough results are found and the
+/// rest of the plan is dropped. This function blocks until result is calculated.
+/// # Arguments
+/// * `space` - atomspace to query for interpretation
+/// * `expr` - atom to interpret
+/// * `limit` - maximum number of results to return
+pub fn interpret_limited<T: Space>(space: T, expr: &Atom, limit: usize) -> Result<Vec<Atom>, String> {
+    let mut state = interpret_init(space, expr);
+    while state.has_next() && state.finished.len() < limit {
+        state = interpret_step(state);
+    }
+    let mut result = state.finished;
+    result.truncate(limit);
+    Ok(result)
+}
+
 fn is_embedded_op(atom: &Atom) -> bool {
     let expr = atom_as_slice(&atom);
     match expr {
@@ -286,7 +309,9 @@ fn is_embedded_op(atom: &Atom) -> bool {
             || *op == DECONS_ATOM_SYMBOL
             || *op == FUNCTION_SYMBOL
             || *op == COLLAPSE_BIND_SYMBOL
-            || *op == SUPERPOSE_BIND_SYMBOL,
+            || *op == SUPERPOSE_BIND_SYMBOL
+            || *op == CATCH_SYMBOL
+            || *op == THROW_SYMBOL,
         _ => false,
     }
 }
@@ -383,6 +408,12 @@ fn interpret_nested_atom<'a, T: SpaceRef<'a>>(context: &InterpreterContext<'a, T
             Some([op, ..]) if *op == SUPERPOSE_BIND_SYMBOL => {
                 superpose_bind(stack, bindings)
             },
+            Some([op, ..]) if *op == CATCH_SYMBOL => {
+                panic!("Unexpected state")
+            },
+            Some([op, ..]) if *op == THROW_SYMBOL => {
+                throw(stack, bindings)
+            },
             _ => {
                 stack.finished = true;
                 vec![InterpretedAtom(stack, bindings)]
@@ -444,8 +475,16 @@ fn eval<'a, T: SpaceRef<'a>>(context: &InterpreterContext<'a, T>, stack: Stack,
                             .collect()
                     }
                 },
-                Err(ExecError::Runtime(err)) =>
-                    finished_result(error_atom(query_atom, err), bindings, prev),
+                Err(ExecError::Runtime(err)) => {
+                    // Error is thrown only when it is going to be caught,
+                    // otherwise it is returned as a value to keep code which
+                    // checks results for errors working
+                    let error = error_atom(query_atom, err);
+                    match find_catch(&prev, &error, &bindings) {
+                        Some(catch) => throw_to_catch(prev, catch),
+                        None => finished_result(error, bindings, prev),
+                    }
+                },
                 Err(ExecError::NoReduce) =>
                     // TODO: we could remove ExecError::NoReduce and explicitly
                     // return NOT_REDUCIBLE_SYMBOL from the grounded function instead.
@@ -507,6 +546,9 @@ fn atom_to_stack(atom: Atom, prev: Option<Rc<RefCell<Stack>>>) -> Stack {
         Some([op, ..]) if *op == COLLAPSE_BIND_SYMBOL => {
             collapse_bind_to_stack(atom, prev)
         },
+        Some([op, ..]) if *op == CATCH_SYMBOL => {
+            catch_to_stack(atom, prev)
+        },
         Some([op, ..]) if *op == EVAL_SYMBOL
                        || *op == UNIFY_SYMBOL => {
             Stack::from_prev_vars(prev, atom, no_handler)
@@ -738,6 +780,109 @@ fn superpose_bind(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
         .collect()
 }
 
+fn catch_to_stack(mut atom: Atom, prev: Option<Rc<RefCell<Stack>>>) -> Stack {
+    let mut nested = Atom::sym("%Nested%");
+    let nested_arg = match atom_as_slice_mut(&mut atom) {
+        Some([_op, nested, _pattern, _handler]) => nested,
+        _ => {
+            let error: String = format!("expected: ({} <nested> <pattern> <handler>), found: {}", CATCH_SYMBOL, atom);
+            return Stack::finished(prev, error_atom(atom, error));
+        },
+    };
+    std::mem::swap(nested_arg, &mut nested);
+    let cur = Stack::from_prev_vars(prev, atom, catch_ret);
+    atom_to_stack(nested, Some(Rc::new(RefCell::new(cur))))
+}
+
+fn catch_ret(stack: Rc<RefCell<Stack>>, atom: Atom, _bindings: Bindings) -> Option<Stack> {
+    // nested atom is evaluated without errors, its result is returned as is
+    let mut stack = (*stack.borrow()).clone();
+    stack.atom = atom;
+    stack.finished = true;
+    Some(stack)
+}
+
+fn throw(stack: Stack, bindings: Bindings) -> Vec<InterpretedAtom> {
+    let Stack{ prev, atom: throw, ret: _, finished: _, vars: _ } = stack;
+    let error = match_atom!{
+        throw ~ [_op, error] => error,
+        _ => {
+            let error: String = format!("expected: ({} <error>), found: {}", THROW_SYMBOL, throw);
+            return finished_result(error_atom(throw, error), bindings, prev);
+        }
+    };
+    match find_catch(&prev, &error, &bindings) {
+        Some(catch) => throw_to_catch(prev, catch),
+        None => {
+            // error which is not caught becomes a result of the whole evaluation
+            unwind(prev, None);
+            vec![InterpretedAtom(Stack::finished(None, error), bindings)]
+        },
+    }
+}
+
+/// Looks for the nearest `catch` frame which pattern matches the error.
+/// Returns the frame and the bindings of the pattern variables.
+fn find_catch(prev: &Option<Rc<RefCell<Stack>>>, error: &Atom, bindings: &Bindings) -> Option<(Rc<RefCell<Stack>>, Vec<Bindings>)> {
+    let mut frame = prev.clone();
+    while let Some(cur) = frame {
+        {
+            let stack = cur.borrow();
+            if let Some([op, _nested, pattern, _handler]) = atom_as_slice(&stack.atom) {
+                if *op == CATCH_SYMBOL {
+                    let matches: Vec<Bindings> = match_atoms(error, pattern)
+                        .flat_map(|b| b.narrow_vars(&stack.vars).merge_v2(bindings))
+                        .filter(|b| !b.has_loops())
+                        .collect();
+                    if !matches.is_empty() {
+                        drop(stack);
+                        return Some((cur, matches));
+                    }
+                }
+            }
+        }
+        frame = cur.borrow().prev.clone();
+    }
+    None
+}
+
+/// Drops frames of the current alternative up to the catch frame and
+/// continues with the handler of the catch
+fn throw_to_catch(prev: Option<Rc<RefCell<Stack>>>, catch: (Rc<RefCell<Stack>>, Vec<Bindings>)) -> Vec<InterpretedAtom> {
+    let (catch, matches) = catch;
+    unwind(prev, Some(&catch));
+    let catch = catch.borrow();
+    let handler = match atom_as_slice(&catch.atom) {
+        Some([_op, _nested, _pattern, handler]) => handler,
+        _ => panic!("Unexpected state"),
+    };
+    matches.into_iter().map(|b| {
+        let handler = apply_bindings_to_atom(handler, &b);
+        InterpretedAtom(atom_to_stack(handler, catch.prev.clone()), b)
+    }).collect()
+}
+
+/// Drops frames of the current alternative up to the `until` frame. Other
+/// alternatives can share the frames with the current one. When error leaves
+/// a `collapse-bind` frame the frame is discarded: it never returns and the
+/// results of the rest of its alternatives are dropped.
+fn unwind(prev: Option<Rc<RefCell<Stack>>>, until: Option<&Rc<RefCell<Stack>>>) {
+    let mut frame = prev;
+    while let Some(cur) = frame {
+        if until.is_some_and(|until| Rc::ptr_eq(until, &cur)) {
+            break;
+        }
+        if is_op(&cur.borrow().atom, &COLLAPSE_BIND_SYMBOL) {
+            cur.borrow_mut().ret = collapse_bind_discarded_ret;
+        }
+        frame = cur.borrow().prev.clone();
+    }
+}
+
+fn collapse_bind_discarded_ret(_stack: Rc<RefCell<Stack>>, _atom: Atom, _bindings: Bindings) -> Option<Stack> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -975,6 +1120,84 @@ mod tests {
     }
 
 
+    #[test]
+    fn interpret_atom_catch_incorrect_args() {
+        assert_eq!(call_interpret(&space(""), &metta_atom("(catch a p)")),
+            vec![expr!("Error" ("catch" "a" "p") "expected: (catch <nested> <pattern> <handler>), found: (catch a p)")]);
+        assert_eq!(call_interpret(&space(""), &metta_atom("(throw)")),
+            vec![expr!("Error" ("throw") "expected: (throw <error>), found: (throw)")]);
+    }
+
+    #[test]
+    fn interpret_atom_catch_no_error() {
+        let space = space("(= (foo $a) ($a B))");
+        let result = call_interpret(&space, &metta_atom("(catch (eval (foo A)) (Error $a $m) (caught $a))"));
+        assert_eq!(result, vec![metta_atom("(A B)")]);
+    }
+
+    #[test]
+    fn interpret_atom_catch_throw() {
+        let result = call_interpret(&space(""), &metta_atom("(catch (chain (throw (Error a msg)) $x (bar $x)) (Error $a $m) (caught $a $m))"));
+        assert_eq!(result, vec![metta_atom("(caught a msg)")]);
+    }
+
+    #[test]
+    fn interpret_atom_throw_through_function() {
+        let space = space("
+            (= (fail $x) (function (chain (throw (Error $x failed)) $y (return $y))))
+            (= (foo $x) (function (chain (eval (fail $x)) $y (return (foo $y)))))
+        ");
+        let result = call_interpret(&space, &metta_atom("(catch (chain (eval (foo A)) $r (ok $r)) (Error $a $m) (caught $a))"));
+        assert_eq!(result, vec![metta_atom("(caught A)")]);
+    }
+
+    #[test]
+    fn interpret_atom_throw_to_outer_catch() {
+        let result = call_interpret(&space(""), &metta_atom("
+            (catch
+              (catch (throw (Error a other)) (Error $a msg) (inner $a))
+              (Error $a $m) (outer $a $m))
+        "));
+        assert_eq!(result, vec![metta_atom("(outer a other)")]);
+    }
+
+    #[test]
+    fn interpret_atom_throw_uncaught() {
+        let result = call_interpret(&space(""), &metta_atom("(chain (chain (throw (Error a msg)) $x (bar $x)) $y (baz $y))"));
+        assert_eq!(result, vec![metta_atom("(Error a msg)")]);
+        let result = call_interpret(&space(""), &metta_atom("(catch (throw (Error a msg)) (Error b $m) (caught $m))"));
+        assert_eq!(result, vec![metta_atom("(Error a msg)")]);
+    }
+
+    #[test]
+    fn interpret_atom_catch_grounded_error() {
+        let result = call_interpret(&space(""), &expr!("catch" ("eval" ({ThrowError()} {"Test error"})) ("Error" a m) ("caught" m)));
+        assert_eq!(result, vec![expr!("caught" "Test error")]);
+    }
+
+    #[test]
+    fn interpret_atom_throw_through_collapse_bind() {
+        let space = space("
+            (= (color) red)
+            (= (color) (function (throw (Error color failed))))
+        ");
+        let result = call_interpret(&space, &metta_atom("(catch (collapse-bind (eval (color))) (Error $a $m) (caught $m))"));
+        assert_eq!(result, vec![metta_atom("(caught failed)")]);
+    }
+
+    #[test]
+    fn interpret_atom_throw_from_each_collapse_bind_alternative() {
+        let space = space("
+            (= (g) a)
+            (= (g) b)
+        ");
+        let result = call_interpret(&space, &metta_atom("(collapse-bind (chain (eval (g)) $x (throw (Error $x boom))))"));
+        assert_eq_no_order!(result, vec![metta_atom("(Error a boom)"), metta_atom("(Error b boom)")]);
+        let result = call_interpret(&space, &metta_atom("(catch (collapse-bind (chain (eval (g)) $x (throw (Error $x boom)))) (Error $a $m) (caught $a))"));
+        assert_eq_no_order!(result, vec![metta_atom("(caught a)"), metta_atom("(caught b)")]);
+    }
+
+
     #[test]
     fn metta_turing_machine() {
         let space = space("
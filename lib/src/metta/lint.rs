@@ -0,0 +1,458 @@
+//! Static checks of the MeTTa code which don't prevent it from running but
+//! point to the likely mistakes.
+//!
+//! [check_cases] looks for `case` expressions which don't cover all values of
+//! the matched type and for branches which are shadowed by previous branches.
+//! Values of the type are enumerated using constructors declared in the space.
+//! Constructor is a symbol which has a type assigned, for example
+//! `(: Cons (-> $t (List $t) (List $t)))` or `(: Nil (List $t))`, but has no
+//! `=` definitions.
+//!
+//! The pass is available in MeTTa programs as `(lint <atom>)` operation which
+//! returns the list of the warnings found.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use super::*;
+use super::types::{is_func, get_arg_types};
+use super::runner::stdlib::{CaseOp, VOID_SYMBOL};
+use crate::atom::matcher::{apply_bindings_to_atom, match_atoms};
+use crate::space::Space;
+
+pub const NON_EXHAUSTIVE_CASE_SYMBOL : Atom = sym!("NonExhaustiveCase");
+pub const UNREACHABLE_CASE_BRANCH_SYMBOL : Atom = sym!("UnreachableCaseBranch");
+
+/// Warning reported by the [check_cases] pass.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintWarning {
+    /// `case` expression has no branch for values matched by `missing`
+    /// patterns.
+    NonExhaustiveCase{ case: Atom, missing: Vec<Atom> },
+    /// `branch` of the `case` expression is never selected because all
+    /// values it matches are matched by the previous branches.
+    UnreachableCaseBranch{ case: Atom, branch: Atom },
+}
+
+impl LintWarning {
+    /// Converts warning into `(NonExhaustiveCase <case> (<missing>...))` or
+    /// `(UnreachableCaseBranch <case> <branch>)` atom.
+    pub fn to_atom(&self) -> Atom {
+        match self {
+            LintWarning::NonExhaustiveCase{ case, missing } =>
+                Atom::expr([NON_EXHAUSTIVE_CASE_SYMBOL, case.clone(), Atom::expr(missing.clone())]),
+            LintWarning::UnreachableCaseBranch{ case, branch } =>
+                Atom::expr([UNREACHABLE_CASE_BRANCH_SYMBOL, case.clone(), branch.clone()]),
+        }
+    }
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LintWarning::NonExhaustiveCase{ case, missing } => {
+                write!(f, "non-exhaustive {}, patterns not covered:", case)?;
+                missing.iter().try_for_each(|pattern| write!(f, " {}", pattern))
+            },
+            LintWarning::UnreachableCaseBranch{ case, branch } =>
+                write!(f, "unreachable branch {} in {}", branch, case),
+        }
+    }
+}
+
+/// Finds all `case` expressions inside `atom` and checks them in context of
+/// the constructors declared in the `space`. Returns list of the warnings
+/// found.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::lint::{check_cases, LintWarning};
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("
+///     (: Nil (List $t))
+///     (: Cons (-> $t (List $t) (List $t)))
+/// ")).unwrap();
+///
+/// let case = expr!("case" l ((("Cons" h t) h)));
+/// assert_eq!(check_cases(&metta.space(), &case),
+///     vec![LintWarning::NonExhaustiveCase{ case: case.clone(), missing: vec![expr!("Nil")] }]);
+/// ```
+pub fn check_cases(space: &dyn Space, atom: &Atom) -> Vec<LintWarning> {
+    let mut checker = CaseChecker::new(space);
+    let mut warnings = Vec::new();
+    checker.check_atom(atom, &mut warnings);
+    warnings
+}
+
+fn is_case_op(atom: &Atom) -> bool {
+    if *atom == sym!("case") || atom.as_gnd::<CaseOp>().is_some() {
+        return true;
+    }
+    #[cfg(feature = "minimal")]
+    if atom.as_gnd::<super::runner::stdlib2::CaseOp>().is_some() {
+        return true;
+    }
+    false
+}
+
+fn wildcard() -> Atom {
+    Atom::var("_")
+}
+
+/// Head and number of arguments of the pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Ctor {
+    head: Atom,
+    arity: usize,
+}
+
+impl Ctor {
+    fn build(&self, args: &[Atom]) -> Atom {
+        if self.arity == 0 {
+            self.head.clone()
+        } else {
+            let mut children = vec![self.head.clone()];
+            children.extend_from_slice(args);
+            Atom::expr(children)
+        }
+    }
+}
+
+/// Returns constructor and arguments of the pattern or `None` when pattern
+/// matches any value.
+fn pattern_ctor(pattern: &Atom) -> Option<(Ctor, &[Atom])> {
+    match pattern {
+        Atom::Variable(_) => None,
+        Atom::Expression(expr) => match expr.children().as_slice() {
+            [head @ Atom::Symbol(_), args @ ..] => Some((Ctor{ head: head.clone(), arity: args.len() }, args)),
+            _ => Some((Ctor{ head: pattern.clone(), arity: 0 }, &[])),
+        },
+        _ => Some((Ctor{ head: pattern.clone(), arity: 0 }, &[])),
+    }
+}
+
+type Row = Vec<Atom>;
+
+struct CaseChecker<'a> {
+    space: &'a dyn Space,
+    signatures: HashMap<Ctor, Option<Vec<Ctor>>>,
+}
+
+impl<'a> CaseChecker<'a> {
+    fn new(space: &'a dyn Space) -> Self {
+        Self{ space, signatures: HashMap::new() }
+    }
+
+    fn check_atom(&mut self, atom: &Atom, warnings: &mut Vec<LintWarning>) {
+        if let Atom::Expression(expr) = atom {
+            if let [op, _atom, Atom::Expression(cases)] = expr.children().as_slice() {
+                if is_case_op(op) {
+                    self.check_case(atom, cases.children(), warnings);
+                }
+            }
+            expr.children().iter().for_each(|child| self.check_atom(child, warnings));
+        }
+    }
+
+    fn check_case(&mut self, case: &Atom, cases: &[Atom], warnings: &mut Vec<LintWarning>) {
+        let branches = cases.iter()
+            .filter_map(|branch| match branch {
+                Atom::Expression(expr) if expr.children().len() == 2 => Some((&expr.children()[0], branch)),
+                _ => None,
+            })
+            // %void% branch is selected when there are no results at all
+            .filter(|(pattern, _branch)| **pattern != VOID_SYMBOL);
+
+        let mut rows: Vec<Row> = Vec::new();
+        for (pattern, branch) in branches {
+            let row = vec![pattern.clone()];
+            if !self.is_useful(&rows, &row) {
+                warnings.push(LintWarning::UnreachableCaseBranch{ case: case.clone(), branch: branch.clone() });
+            }
+            rows.push(row);
+        }
+
+        let missing: Vec<Atom> = self.missing(&rows, 1).into_iter()
+            .map(|mut row| row.remove(0))
+            .collect();
+        if !missing.is_empty() {
+            warnings.push(LintWarning::NonExhaustiveCase{ case: case.clone(), missing });
+        }
+    }
+
+    /// Returns true if there is a value which is matched by the `row` but is
+    /// not matched by any of the `rows`.
+    fn is_useful(&mut self, rows: &[Row], row: &[Atom]) -> bool {
+        let (first, rest) = match row.split_first() {
+            None => return rows.is_empty(),
+            Some(split) => split,
+        };
+        match pattern_ctor(first) {
+            Some((ctor, args)) => {
+                let mut row: Row = args.to_vec();
+                row.extend_from_slice(rest);
+                self.is_useful(&specialize(rows, &ctor), &row)
+            },
+            None => match self.complete_signature(rows) {
+                Some(signature) => signature.iter().any(|ctor| {
+                    let mut row = vec![wildcard(); ctor.arity];
+                    row.extend_from_slice(rest);
+                    self.is_useful(&specialize(rows, ctor), &row)
+                }),
+                None => self.is_useful(&default_rows(rows), rest),
+            },
+        }
+    }
+
+    /// Returns rows of `width` patterns which are not matched by any of the
+    /// `rows`.
+    fn missing(&mut self, rows: &[Row], width: usize) -> Vec<Row> {
+        if width == 0 {
+            return if rows.is_empty() { vec![vec![]] } else { vec![] };
+        }
+        let mut result = Vec::new();
+        if let Some(signature) = self.complete_signature(rows) {
+            for ctor in signature {
+                for row in self.missing(&specialize(rows, &ctor), ctor.arity + width - 1) {
+                    let mut missing = vec![ctor.build(&row[..ctor.arity])];
+                    missing.extend_from_slice(&row[ctor.arity..]);
+                    result.push(missing);
+                }
+            }
+        } else {
+            let rest = self.missing(&default_rows(rows), width - 1);
+            if rest.is_empty() {
+                return result;
+            }
+            let ctors = column_ctors(rows);
+            let heads: Vec<Atom> = match self.column_signature(&ctors) {
+                Some(signature) if !ctors.is_empty() => signature.iter()
+                    .filter(|ctor| !ctors.contains(ctor))
+                    .map(|ctor| ctor.build(&vec![wildcard(); ctor.arity]))
+                    .collect(),
+                _ => vec![wildcard()],
+            };
+            for head in heads {
+                for row in &rest {
+                    let mut missing = vec![head.clone()];
+                    missing.extend_from_slice(row);
+                    result.push(missing);
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns all constructors of the first column type if each of them is
+    /// used in the first column of the `rows`.
+    fn complete_signature(&mut self, rows: &[Row]) -> Option<Vec<Ctor>> {
+        let ctors = column_ctors(rows);
+        self.column_signature(&ctors)
+            .filter(|signature| signature.iter().all(|ctor| ctors.contains(ctor)))
+    }
+
+    fn column_signature(&mut self, ctors: &[Ctor]) -> Option<Vec<Ctor>> {
+        ctors.iter().find_map(|ctor| self.signature(ctor))
+    }
+
+    /// Returns all constructors of the type constructed by `ctor`.
+    fn signature(&mut self, ctor: &Ctor) -> Option<Vec<Ctor>> {
+        if let Some(signature) = self.signatures.get(ctor) {
+            return signature.clone();
+        }
+        let signature = self.find_signature(ctor);
+        self.signatures.insert(ctor.clone(), signature.clone());
+        signature
+    }
+
+    fn find_signature(&self, ctor: &Ctor) -> Option<Vec<Ctor>> {
+        if !matches!(ctor.head, Atom::Symbol(_)) || self.is_defined(ctor) {
+            return None;
+        }
+        let typ = self.declarations(&ctor.head).into_iter()
+            .map(|(_, typ)| typ)
+            .find(|typ| ctor_arity(typ) == ctor.arity)?;
+        let ret_typ = make_variables_unique(ctor_return_type(&typ).clone());
+        let var_ctor = Atom::var("ctor");
+        let mut signature: Vec<Ctor> = self.declarations(&var_ctor).into_iter()
+            .filter(|(head, _)| matches!(head, Atom::Symbol(_)))
+            .filter(|(_, typ)| {
+                let ret = ctor_return_type(typ);
+                !matches!(ret, Atom::Variable(_)) && match_atoms(ret, &ret_typ).next().is_some()
+            })
+            .map(|(head, typ)| Ctor{ head, arity: ctor_arity(&typ) })
+            .filter(|ctor| !self.is_defined(ctor))
+            .collect();
+        let mut unique = Vec::new();
+        signature.drain(..).for_each(|ctor| if !unique.contains(&ctor) { unique.push(ctor) });
+        // space query doesn't keep the order of declarations, sort
+        // constructors to make warnings reproducible
+        unique.sort_by_key(|ctor| ctor.head.to_string());
+        Some(unique)
+    }
+
+    /// Returns pairs of the atom and its type for all `(: <atom> <type>)`
+    /// declarations which match `atom`.
+    fn declarations(&self, atom: &Atom) -> Vec<(Atom, Atom)> {
+        let typ = Atom::Variable(VariableAtom::new("type").make_unique());
+        let query = Atom::expr([HAS_TYPE_SYMBOL, atom.clone(), typ.clone()]);
+        self.space.query(&query).into_iter()
+            .map(|bindings| (apply_bindings_to_atom(atom, &bindings), apply_bindings_to_atom(&typ, &bindings)))
+            .collect()
+    }
+
+    /// Returns true if there are `=` definitions for `ctor`, which means it
+    /// is a function and not a constructor.
+    fn is_defined(&self, ctor: &Ctor) -> bool {
+        let args: Vec<Atom> = (0..ctor.arity)
+            .map(|_| Atom::Variable(VariableAtom::new("arg").make_unique()))
+            .collect();
+        let body = Atom::Variable(VariableAtom::new("body").make_unique());
+        let query = Atom::expr([EQUAL_SYMBOL, ctor.build(&args), body]);
+        !self.space.query(&query).is_empty()
+    }
+}
+
+fn ctor_arity(typ: &Atom) -> usize {
+    if is_func(typ) { get_arg_types(typ).0.len() } else { 0 }
+}
+
+fn ctor_return_type(typ: &Atom) -> &Atom {
+    if is_func(typ) { get_arg_types(typ).1 } else { typ }
+}
+
+fn column_ctors(rows: &[Row]) -> Vec<Ctor> {
+    let mut ctors: Vec<Ctor> = Vec::new();
+    for (ctor, _args) in rows.iter().filter_map(|row| pattern_ctor(&row[0])) {
+        if !ctors.contains(&ctor) {
+            ctors.push(ctor);
+        }
+    }
+    ctors
+}
+
+/// Keeps rows which match values constructed by `ctor` and replaces the first
+/// pattern of each row by the patterns of the constructor arguments.
+fn specialize(rows: &[Row], ctor: &Ctor) -> Vec<Row> {
+    rows.iter().filter_map(|row| {
+        let mut result: Row = match pattern_ctor(&row[0]) {
+            None => vec![wildcard(); ctor.arity],
+            Some((row_ctor, args)) if row_ctor == *ctor => args.to_vec(),
+            Some(_) => return None,
+        };
+        result.extend_from_slice(&row[1..]);
+        Some(result)
+    }).collect()
+}
+
+/// Keeps rows which first pattern matches any value and removes the first
+/// pattern from them.
+fn default_rows(rows: &[Row]) -> Vec<Row> {
+    rows.iter()
+        .filter(|row| pattern_ctor(&row[0]).is_none())
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::{SExprParser, Tokenizer};
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::space::grounding::GroundingSpace;
+
+    fn metta_space(text: &str) -> GroundingSpace {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let mut space = GroundingSpace::new();
+        let mut parser = SExprParser::new(text);
+        while let Some(atom) = parser.parse(&*metta.tokenizer().borrow()).unwrap() {
+            space.add(atom);
+        }
+        space
+    }
+
+    fn atom(atom_str: &str) -> Atom {
+        let mut parser = SExprParser::new(atom_str);
+        let atom = parser.parse(&Tokenizer::new()).unwrap().expect("Single atom is expected");
+        atom
+    }
+
+    fn list_space() -> GroundingSpace {
+        metta_space("
+            (: List (-> $t Type))
+            (: Nil (List $t))
+            (: Cons (-> $t (List $t) (List $t)))
+            (: Color Type)
+            (: Red Color)
+            (: Green Color)
+            (: Blue Color)
+            (: len (-> (List $t) Number))
+            (= (len Nil) 0)
+        ")
+    }
+
+    #[test]
+    fn check_cases_exhaustive() {
+        let space = list_space();
+
+        assert_eq!(check_cases(&space, &atom("(case $l ((Nil 0) ((Cons $h $t) 1)))")), vec![]);
+        assert_eq!(check_cases(&space, &atom("(case $l (((Cons $h Nil) 1) ((Cons $h (Cons $x $t)) 2) (Nil 0)))")), vec![]);
+        assert_eq!(check_cases(&space, &atom("(case $c ((Red 0) ($_ 1)))")), vec![]);
+    }
+
+    #[test]
+    fn check_cases_reports_missing_constructors() {
+        let space = list_space();
+        let case = atom("(case $c ((Red 0)))");
+        assert_eq!(check_cases(&space, &case), vec![LintWarning::NonExhaustiveCase{
+            case: case.clone(), missing: vec![atom("Blue"), atom("Green")] }]);
+
+        let case = atom("(case $l ((Nil 0) ((Cons $h Nil) 1)))");
+        assert_eq!(check_cases(&space, &case), vec![LintWarning::NonExhaustiveCase{
+            case: case.clone(), missing: vec![atom("(Cons $_ (Cons $_ $_))")] }]);
+    }
+
+    #[test]
+    fn check_cases_reports_unreachable_branches() {
+        let space = list_space();
+        let case = atom("(case $l (($x 0) (Nil 1) ((Cons $h $t) 2)))");
+
+        assert_eq!(check_cases(&space, &case), vec![
+            LintWarning::UnreachableCaseBranch{ case: case.clone(), branch: atom("(Nil 1)") },
+            LintWarning::UnreachableCaseBranch{ case: case.clone(), branch: atom("((Cons $h $t) 2)") },
+        ]);
+    }
+
+    #[test]
+    fn check_cases_without_constructors() {
+        let space = list_space();
+        let case = atom("(case $n ((1 one) (2 two)))");
+
+        assert_eq!(check_cases(&space, &case), vec![LintWarning::NonExhaustiveCase{
+            case: case.clone(), missing: vec![atom("$_")] }]);
+        assert_eq!(check_cases(&space, &atom("(case (len $l) ((0 zero) ($n other) (%void% none)))")), vec![]);
+    }
+
+    #[test]
+    fn check_cases_ignores_functions() {
+        let space = list_space();
+        let case = atom("(case $l (((len Nil) 0) ($x 1)))");
+
+        assert_eq!(check_cases(&space, &case), vec![]);
+    }
+
+    #[test]
+    fn check_cases_nested_case() {
+        let space = list_space();
+        let inner = atom("(case $c ((Red 0) (Red 1) ($x 2)))");
+        let outer = Atom::expr([sym!("foo"), inner.clone()]);
+
+        assert_eq!(check_cases(&space, &outer), vec![
+            LintWarning::UnreachableCaseBranch{ case: inner, branch: atom("(Red 1)") }]);
+    }
+}
@@ -5,6 +5,7 @@ pub mod interpreter;
 #[cfg(feature = "minimal")]
 pub mod interpreter2;
 pub mod types;
+pub mod lint;
 pub mod runner;
 
 use crate::*;
@@ -24,6 +25,8 @@ pub const EQUAL_SYMBOL : Atom = sym!("=");
 pub const ARROW_SYMBOL : Atom = sym!("->");
 pub const ERROR_SYMBOL : Atom = sym!("Error");
 pub const BAD_TYPE_SYMBOL : Atom = sym!("BadType");
+pub const BAD_ARG_TYPE_SYMBOL : Atom = sym!("BadArgType");
+pub const BAD_RETURN_TYPE_SYMBOL : Atom = sym!("BadReturnType");
 pub const INCORRECT_NUMBER_OF_ARGUMENTS_SYMBOL : Atom = sym!("IncorrectNumberOfArguments");
 pub const NOT_REDUCIBLE_SYMBOL : Atom = sym!("NotReducible");
 pub const NO_VALID_ALTERNATIVES : Atom = sym!("NoValidAlternatives");
@@ -39,8 +42,11 @@ pub const FUNCTION_SYMBOL : Atom = sym!("function");
 pub const RETURN_SYMBOL : Atom = sym!("return");
 pub const COLLAPSE_BIND_SYMBOL : Atom = sym!("collapse-bind");
 pub const SUPERPOSE_BIND_SYMBOL : Atom = sym!("superpose-bind");
+pub const CATCH_SYMBOL : Atom = sym!("catch");
+pub const THROW_SYMBOL : Atom = sym!("throw");
 
 pub const INTERPRET_SYMBOL : Atom = sym!("interpret");
+pub const INTERPRET_EXPRESSION_SYMBOL : Atom = sym!("interpret-expression");
 
 //TODO: convert these from functions to static strcutures, when Atoms are Send+Sync
 #[allow(non_snake_case)]
@@ -0,0 +1,244 @@
+//! Aggregation operations over the results of the space query. Operations
+//! take a space and a pattern as [super::stdlib::MatchOp] does and fold the
+//! bindings returned by [Space::query] directly, without collecting the
+//! instantiated templates into intermediate expressions.
+
+use crate::*;
+use crate::matcher::{Bindings, MatchResultIter, apply_bindings_to_atom};
+use crate::space::*;
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Number, ATOM_TYPE_NUMBER};
+use crate::metta::runner::stdlib::compare_atoms;
+
+use std::fmt::Display;
+use std::collections::HashMap;
+
+/// Queries the space passed as the `n`-th argument using the pattern passed
+/// as the next argument.
+fn query_arg(args: &[Atom], n: usize, usage: &'static str) -> Result<impl Iterator<Item=Bindings>, ExecError> {
+    let space = args.get(n).and_then(Atom::as_gnd::<DynSpace>).ok_or(usage)?;
+    let pattern = args.get(n + 1).ok_or(usage)?;
+    Ok(space.borrow().query(pattern).into_iter())
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CountMatchesOp {}
+
+impl Display for CountMatchesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "count-matches")
+    }
+}
+
+impl Grounded for CountMatchesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let count = query_arg(args, 0, "count-matches expects space and pattern as arguments")?.count();
+        Ok(vec![Atom::gnd(Number::Integer(count as i64))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SumMatchesOp {}
+
+impl Display for SumMatchesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sum")
+    }
+}
+
+impl Grounded for SumMatchesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "sum expects space, pattern and template as arguments";
+        let template = args.get(2).ok_or(usage)?;
+        let sum = query_arg(args, 0, usage)?.try_fold(Number::Integer(0), |sum, bindings| {
+            let value = apply_bindings_to_atom(template, &bindings);
+            match value.as_gnd::<Number>() {
+                Some(value) => sum.sum(value),
+                None => Err(format!("sum expects template to be a number for each match, found: {}", value).into()),
+            }
+        })?;
+        Ok(vec![Atom::gnd(sum)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MinByOp {}
+
+impl Display for MinByOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "min-by")
+    }
+}
+
+impl Grounded for MinByOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "min-by expects key template, space, pattern and template as arguments";
+        let key = args.first().ok_or(usage)?;
+        let template = args.get(3).ok_or(usage)?;
+        let min = query_arg(args, 1, usage)?
+            .map(|bindings| (apply_bindings_to_atom(key, &bindings), bindings))
+            .min_by(|(a, _), (b, _)| compare_atoms(a, b));
+        Ok(min.map(|(_, bindings)| apply_bindings_to_atom(template, &bindings)).into_iter().collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GroupByOp {}
+
+impl Display for GroupByOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "group-by")
+    }
+}
+
+impl Grounded for GroupByOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, rust_type_atom::<DynSpace>(), ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "group-by expects key template, space, pattern and template as arguments";
+        let key = args.first().ok_or(usage)?;
+        let template = args.get(3).ok_or(usage)?;
+        let mut groups: HashMap<Atom, Vec<Atom>> = HashMap::new();
+        for bindings in query_arg(args, 1, usage)? {
+            let key = apply_bindings_to_atom(key, &bindings);
+            let value = apply_bindings_to_atom(template, &bindings);
+            groups.entry(key).or_default().push(value);
+        }
+        let mut groups: Vec<(Atom, Vec<Atom>)> = groups.into_iter().collect();
+        groups.sort_by(|(a, _), (b, _)| compare_atoms(a, b));
+        Ok(vec![Atom::expr(groups.into_iter()
+            .map(|(key, values)| Atom::expr([key, Atom::expr(values)]))
+            .collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::metta::runner::arithmetics::Bool;
+
+    fn people() -> DynSpace {
+        let mut space = GroundingSpace::new();
+        for (name, city, age) in [("alice", "paris", 30), ("bob", "london", 25), ("carol", "paris", 35)] {
+            space.add(Atom::expr([sym!("person"), Atom::sym(name), Atom::sym(city), Atom::gnd(Number::Integer(age))]));
+        }
+        DynSpace::new(space)
+    }
+
+    #[test]
+    fn count_matches_op() {
+        let space = Atom::gnd(people());
+        assert_eq!(CountMatchesOp{}.execute(&mut vec![space.clone(), expr!("person" n "paris" a)]),
+            Ok(vec![Atom::gnd(Number::Integer(2))]));
+        assert_eq!(CountMatchesOp{}.execute(&mut vec![space, expr!("person" n "rome" a)]),
+            Ok(vec![Atom::gnd(Number::Integer(0))]));
+    }
+
+    #[test]
+    fn sum_matches_op() {
+        let space = Atom::gnd(people());
+        assert_eq!(SumMatchesOp{}.execute(&mut vec![space.clone(), expr!("person" n c a), expr!(a)]),
+            Ok(vec![Atom::gnd(Number::Integer(90))]));
+        assert_eq!(SumMatchesOp{}.execute(&mut vec![space.clone(), expr!("person" n "rome" a), expr!(a)]),
+            Ok(vec![Atom::gnd(Number::Integer(0))]));
+        assert!(SumMatchesOp{}.execute(&mut vec![space, expr!("person" n c a), expr!(n)]).is_err());
+    }
+
+    #[test]
+    fn min_by_op() {
+        let space = Atom::gnd(people());
+        assert_eq!(MinByOp{}.execute(&mut vec![expr!(a), space.clone(), expr!("person" n c a), expr!(n)]),
+            Ok(vec![expr!("bob")]));
+        assert_eq!(MinByOp{}.execute(&mut vec![expr!(n), space.clone(), expr!("person" n "paris" a), expr!(n a)]),
+            Ok(vec![expr!("alice" {Number::Integer(30)})]));
+        assert_eq!(MinByOp{}.execute(&mut vec![expr!(a), space, expr!("person" n "rome" a), expr!(n)]),
+            Ok(vec![]));
+    }
+
+    #[test]
+    fn group_by_op() {
+        let space = Atom::gnd(people());
+        let result = GroupByOp{}.execute(&mut vec![expr!(c), space, expr!("person" n c a), expr!(n)]).unwrap();
+        let groups = match result.as_slice() {
+            [Atom::Expression(groups)] => groups.children().clone(),
+            _ => panic!("Single expression is expected: {:?}", result),
+        };
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], expr!("london" ("bob")));
+        match &groups[1] {
+            Atom::Expression(group) => {
+                assert_eq!(group.children()[0], expr!("paris"));
+                assert_eq_no_order!(<&ExpressionAtom>::try_from(&group.children()[1]).unwrap().children(),
+                    vec![expr!("alice"), expr!("carol")]);
+            },
+            _ => panic!("Group is expected: {}", groups[1]),
+        }
+    }
+
+    #[test]
+    fn group_by_op_keeps_atoms_with_same_text_apart() {
+        let mut space = GroundingSpace::new();
+        space.add(expr!("item" "a" "True"));
+        space.add(expr!("item" "b" {Bool(true)}));
+        let result = GroupByOp{}.execute(&mut vec![expr!(k), Atom::gnd(DynSpace::new(space)), expr!("item" n k), expr!(n)]).unwrap();
+        let groups = match result.as_slice() {
+            [Atom::Expression(groups)] => groups.children().clone(),
+            _ => panic!("Single expression is expected: {:?}", result),
+        };
+        assert_eq!(groups.len(), 2);
+        assert!(groups.contains(&expr!({Bool(true)} ("b"))));
+        assert!(groups.contains(&expr!("True" ("a"))));
+    }
+
+    #[test]
+    fn aggregates_in_runner() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("
+            (person alice paris 30)
+            (person bob london 25)
+            (person carol paris 35)
+            !(count-matches &self (person $n paris $a))
+            !(sum &self (person $n $c $a) $a)
+            !(min-by $a &self (person $n $c $a) $n)
+            !(group-by $c &self (person $n $c 25) $n)
+        "));
+        assert_eq!(result, Ok(vec![
+            vec![Atom::gnd(Number::Integer(2))],
+            vec![Atom::gnd(Number::Integer(90))],
+            vec![expr!("bob")],
+            vec![expr!((("london" ("bob"))))],
+        ]));
+    }
+}
@@ -3,23 +3,43 @@ use crate::metta::*;
 use crate::matcher::MatchResultIter;
 
 use std::fmt::Display;
+use std::hash::Hasher;
+use std::cmp::Ordering;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, FromPrimitive, Zero, Signed};
 
 pub const ATOM_TYPE_NUMBER : Atom = sym!("Number");
 pub const ATOM_TYPE_BOOL : Atom = sym!("Bool");
 
+/// Number value. Integers are promoted to [Number::BigInteger] when the
+/// result of the operation doesn't fit into `i64`. Operations on exact numbers
+/// (integers and rationals) are exact, operations which have a float operand
+/// return float.
+///
+/// Numbers returned by the operations are normalized: [Number::BigInteger]
+/// holds only values which don't fit into `i64` and [Number::Rational] holds
+/// only fractions with denominator other than one.
 #[derive(Clone, Debug)]
 pub enum Number {
     Integer(i64),
+    BigInteger(BigInt),
+    Rational(BigRational),
     Float(f64),
 }
 
 impl PartialEq<Self> for Number {
     fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<Self> for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
-            (Number::Integer(a), Number::Integer(b)) => a == b,
-            (Number::Integer(a), Number::Float(b)) => (*a as f64) == *b,
-            (Number::Float(a), Number::Integer(b)) => *a == (*b as f64),
-            (Number::Float(a), Number::Float(b)) => a == b,
+            (Number::Integer(a), Number::Integer(b)) => a.partial_cmp(b),
+            (Number::Float(_), _) | (_, Number::Float(_)) => self.to_f64().partial_cmp(&other.to_f64()),
+            _ => self.to_rational().partial_cmp(&other.to_rational()),
         }
     }
 }
@@ -40,22 +60,140 @@ impl IntoNumber for f64 {
     }
 }
 
+impl IntoNumber for BigInt {
+    fn into_num(self) -> Number {
+        match self.to_i64() {
+            Some(n) => Number::Integer(n),
+            None => Number::BigInteger(self),
+        }
+    }
+}
+
+impl IntoNumber for BigRational {
+    fn into_num(self) -> Number {
+        if self.is_integer() {
+            self.to_integer().into_num()
+        } else {
+            Number::Rational(self)
+        }
+    }
+}
+
 impl Number {
     pub fn from_int_str(num: &str) -> Self {
-        let n = num.parse::<i64>().expect("Could not parse integer");
-        Self::Integer(n)
+        match num.parse::<i64>() {
+            Ok(n) => Self::Integer(n),
+            Err(_) => num.parse::<BigInt>().expect("Could not parse integer").into_num(),
+        }
     }
 
     pub fn from_float_str(num: &str) -> Self {
         let n = num.parse::<f64>().expect("Could not parse float");
         Self::Float(n)
     }
+
+    /// Parses rational number written as `<numerator>/<denominator>`.
+    pub fn from_rational_str(num: &str) -> Self {
+        let r = num.parse::<BigRational>().expect("Could not parse rational");
+        r.into_num()
+    }
+
+    /// Returns the value as f64, big integers and rationals can be rounded.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Integer(n) => *n as f64,
+            Number::BigInteger(n) => n.to_f64().unwrap_or(f64::NAN),
+            Number::Rational(r) => r.to_f64().unwrap_or(f64::NAN),
+            Number::Float(n) => *n,
+        }
+    }
+
+    /// Returns the exact value of the number or `None` if number is float.
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Integer(n) => Some(BigRational::from_integer(BigInt::from(*n))),
+            Number::BigInteger(n) => Some(BigRational::from_integer(n.clone())),
+            Number::Rational(r) => Some(r.clone()),
+            Number::Float(_) => None,
+        }
+    }
+
+    /// Applies binary operation to the numbers. `int` is used when both
+    /// numbers are i64, it returns `None` on overflow, then `exact` is used.
+    /// `exact` is used when both numbers are exact and it returns `None` when
+    /// the result is undefined. `float` is used when any of the numbers is
+    /// float.
+    fn binary_op<I, E, F>(&self, other: &Self, int: I, exact: E, float: F) -> Result<Number, ExecError>
+        where I: Fn(i64, i64) -> Option<i64>,
+              E: Fn(BigRational, BigRational) -> Option<BigRational>,
+              F: Fn(f64, f64) -> f64,
+    {
+        if let (Number::Integer(a), Number::Integer(b)) = (self, other) {
+            if let Some(n) = int(*a, *b) {
+                return Ok(Number::Integer(n));
+            }
+        }
+        match (self.to_rational(), other.to_rational()) {
+            (Some(a), Some(b)) => exact(a, b).map(IntoNumber::into_num)
+                .ok_or_else(|| ExecError::from("Division by zero")),
+            _ => Ok(Number::Float(float(self.to_f64(), other.to_f64()))),
+        }
+    }
+
+    pub(crate) fn sum(&self, other: &Self) -> Result<Number, ExecError> {
+        self.binary_op(other, i64::checked_add, |a, b| Some(a + b), |a, b| a + b)
+    }
+
+    fn sub(&self, other: &Self) -> Result<Number, ExecError> {
+        self.binary_op(other, i64::checked_sub, |a, b| Some(a - b), |a, b| a - b)
+    }
+
+    fn mul(&self, other: &Self) -> Result<Number, ExecError> {
+        self.binary_op(other, i64::checked_mul, |a, b| Some(a * b), |a, b| a * b)
+    }
+
+    /// Division of integers is an integer division, division of rationals
+    /// is exact.
+    fn div(&self, other: &Self) -> Result<Number, ExecError> {
+        self.binary_op(other, i64::checked_div, |a, b| {
+            if b.is_zero() {
+                None
+            } else if a.is_integer() && b.is_integer() {
+                Some(BigRational::from_integer(a.to_integer() / b.to_integer()))
+            } else {
+                Some(a / b)
+            }
+        }, |a, b| a / b)
+    }
+
+    fn rem(&self, other: &Self) -> Result<Number, ExecError> {
+        self.binary_op(other, i64::checked_rem,
+            |a, b| if b.is_zero() { None } else { Some(a % b) }, |a, b| a % b)
+    }
+}
+
+impl std::str::FromStr for Number {
+    type Err = String;
+
+    /// Parses integer, rational `<numerator>/<denominator>` or float number.
+    fn from_str(num: &str) -> Result<Self, Self::Err> {
+        if let Ok(n) = num.parse::<BigInt>() {
+            Ok(n.into_num())
+        } else if let Ok(r) = num.parse::<BigRational>() {
+            Ok(r.into_num())
+        } else {
+            num.parse::<f64>().map(Number::Float)
+                .map_err(|_| format!("Could not parse number: {}", num))
+        }
+    }
 }
 
 impl Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer(n) => write!(f, "{}", n),
+            Self::BigInteger(n) => write!(f, "{}", n),
+            Self::Rational(r) => write!(f, "{}", r),
             Self::Float(n) => write!(f, "{}", n),
         }
     }
@@ -73,6 +211,13 @@ impl Grounded for Number {
     fn match_(&self, other: &Atom) -> MatchResultIter {
         match_by_equality(self, other)
     }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        // Exact numbers are equal to Float when they are equal after
+        // conversion to f64, so all numbers are hashed as f64. Adding 0.0
+        // makes -0.0 equal to 0.0.
+        state.write_u64((self.to_f64() + 0.0).to_bits());
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -109,10 +254,14 @@ impl Grounded for Bool {
     fn match_(&self, other: &Atom) -> MatchResultIter {
         match_by_equality(self, other)
     }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        state.write_u8(self.0 as u8);
+    }
 }
 
 macro_rules! def_binary_number_op {
-    ($name:ident, $op:tt, $r:ident, $cast:expr) => {
+    ($name:ident, $op:tt, $r:ident, $eval:expr) => {
         #[derive(Clone, PartialEq, Debug)]
         pub struct $name{}
 
@@ -132,12 +281,7 @@ macro_rules! def_binary_number_op {
                 let a = args.get(0).ok_or_else(arg_error)?.as_gnd::<Number>().ok_or_else(arg_error)?;
                 let b = args.get(1).ok_or_else(arg_error)?.as_gnd::<Number>().ok_or_else(arg_error)?;
 
-                let res = match (a, b) {
-                    (&Number::Integer(a), &Number::Integer(b)) => $cast(a $op b),
-                    (&Number::Integer(a), &Number::Float(b)) => $cast((a as f64) $op b),
-                    (&Number::Float(a), &Number::Integer(b)) => $cast(a $op (b as f64)),
-                    (&Number::Float(a), &Number::Float(b)) => $cast(a $op b),
-                };
+                let res = $eval(a, b)?;
 
                 Ok(vec![Atom::gnd(res)])
             }
@@ -145,19 +289,229 @@ macro_rules! def_binary_number_op {
             fn match_(&self, other: &Atom) -> MatchResultIter {
                 match_by_equality(self, other)
             }
+
+            fn is_type_level(&self) -> bool {
+                true
+            }
         }
     }
 }
 
-def_binary_number_op!(SumOp, +, ATOM_TYPE_NUMBER, IntoNumber::into_num);
-def_binary_number_op!(SubOp, -, ATOM_TYPE_NUMBER, IntoNumber::into_num);
-def_binary_number_op!(MulOp, *, ATOM_TYPE_NUMBER, IntoNumber::into_num);
-def_binary_number_op!(DivOp, /, ATOM_TYPE_NUMBER, IntoNumber::into_num);
-def_binary_number_op!(ModOp, %, ATOM_TYPE_NUMBER, IntoNumber::into_num);
-def_binary_number_op!(LessOp, <, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(GreaterOp, >, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(LessEqOp, <=, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(GreaterEqOp, >=, ATOM_TYPE_BOOL, Bool);
+def_binary_number_op!(SumOp, +, ATOM_TYPE_NUMBER, Number::sum);
+def_binary_number_op!(SubOp, -, ATOM_TYPE_NUMBER, Number::sub);
+def_binary_number_op!(MulOp, *, ATOM_TYPE_NUMBER, Number::mul);
+def_binary_number_op!(DivOp, /, ATOM_TYPE_NUMBER, Number::div);
+def_binary_number_op!(ModOp, %, ATOM_TYPE_NUMBER, Number::rem);
+def_binary_number_op!(LessOp, <, ATOM_TYPE_BOOL, |a, b| Ok::<_, ExecError>(Bool(a < b)));
+def_binary_number_op!(GreaterOp, >, ATOM_TYPE_BOOL, |a, b| Ok::<_, ExecError>(Bool(a > b)));
+def_binary_number_op!(LessEqOp, <=, ATOM_TYPE_BOOL, |a, b| Ok::<_, ExecError>(Bool(a <= b)));
+def_binary_number_op!(GreaterEqOp, >=, ATOM_TYPE_BOOL, |a, b| Ok::<_, ExecError>(Bool(a >= b)));
+
+/// Maximal size in bits of the exact integer result of the shift and power
+/// operations. Bigger results are reported as errors instead of exhausting the
+/// memory.
+const MAX_INTEGER_BITS: u64 = 1 << 24;
+
+fn check_integer_bits(bits: u64) -> Result<(), ExecError> {
+    if bits > MAX_INTEGER_BITS {
+        Err(ExecError::from(format!("Integer result is too big: more than {} bits", MAX_INTEGER_BITS)))
+    } else {
+        Ok(())
+    }
+}
+
+fn float_to_integer(n: f64) -> Result<Number, ExecError> {
+    BigInt::from_f64(n).map(IntoNumber::into_num)
+        .ok_or_else(|| ExecError::from(format!("Cannot convert {} to integer", n)))
+}
+
+impl Number {
+    fn to_big_int(&self) -> Result<BigInt, ExecError> {
+        match self {
+            Number::Integer(n) => Ok(BigInt::from(*n)),
+            Number::BigInteger(n) => Ok(n.clone()),
+            _ => Err(ExecError::from(format!("Integer is expected, found: {}", self))),
+        }
+    }
+
+    fn float_op(&self, op: fn(f64) -> f64) -> Result<Number, ExecError> {
+        Ok(Number::Float(op(self.to_f64())))
+    }
+
+    /// Rounds the number to the integer. Exact numbers are rounded using
+    /// `exact`, floats using `float`.
+    fn round_op(&self, exact: fn(&BigRational) -> BigRational, float: fn(f64) -> f64) -> Result<Number, ExecError> {
+        match self {
+            Number::Integer(_) | Number::BigInteger(_) => Ok(self.clone()),
+            Number::Rational(r) => Ok(exact(r).into_num()),
+            Number::Float(n) => float_to_integer(float(*n)),
+        }
+    }
+
+    fn bit_op(&self, other: &Self, op: fn(BigInt, BigInt) -> BigInt) -> Result<Number, ExecError> {
+        Ok(op(self.to_big_int()?, other.to_big_int()?).into_num())
+    }
+
+    fn shift_args(&self, other: &Self) -> Result<(BigInt, usize), ExecError> {
+        let shift = match other {
+            Number::Integer(n) if *n >= 0 => *n as u64,
+            _ => return Err(ExecError::from(format!("Non-negative shift is expected, found: {}", other))),
+        };
+        if shift > MAX_INTEGER_BITS {
+            return Err(ExecError::from(format!("Shift is too big: {}", shift)));
+        }
+        Ok((self.to_big_int()?, shift as usize))
+    }
+
+    fn shl(&self, other: &Self) -> Result<Number, ExecError> {
+        let (value, shift) = self.shift_args(other)?;
+        check_integer_bits(value.bits() + shift as u64)?;
+        Ok((value << shift).into_num())
+    }
+
+    fn shr(&self, other: &Self) -> Result<Number, ExecError> {
+        let (value, shift) = self.shift_args(other)?;
+        Ok((value >> shift).into_num())
+    }
+
+    fn abs(&self) -> Result<Number, ExecError> {
+        Ok(match self {
+            Number::Integer(n) => n.checked_abs().map_or_else(|| BigInt::from(*n).abs().into_num(), Number::Integer),
+            Number::BigInteger(n) => Number::BigInteger(n.abs()),
+            Number::Rational(r) => Number::Rational(r.abs()),
+            Number::Float(n) => Number::Float(n.abs()),
+        })
+    }
+
+    /// Exact numbers raised to the integer power give exact result, in other
+    /// cases the result is float.
+    fn pow(&self, exp: &Self) -> Result<Number, ExecError> {
+        match (self.to_rational(), exp) {
+            (Some(base), Number::Integer(exp)) => {
+                let exp = i32::try_from(*exp)
+                    .map_err(|_| ExecError::from(format!("Exponent is too big: {}", exp)))?;
+                if base.is_zero() && exp < 0 {
+                    return Err(ExecError::from("Division by zero"));
+                }
+                let base_bits = std::cmp::max(base.numer().bits(), base.denom().bits());
+                check_integer_bits(base_bits.saturating_sub(1).saturating_mul(exp.unsigned_abs() as u64))?;
+                Ok(base.pow(exp).into_num())
+            },
+            _ => Ok(Number::Float(self.to_f64().powf(exp.to_f64()))),
+        }
+    }
+
+    /// Integer division which rounds the quotient towards negative infinity.
+    fn int_div(&self, other: &Self) -> Result<Number, ExecError> {
+        match (self.to_rational(), other.to_rational()) {
+            (Some(_), Some(b)) if b.is_zero() => Err(ExecError::from("Division by zero")),
+            (Some(a), Some(b)) => Ok((a / b).floor().into_num()),
+            _ => float_to_integer((self.to_f64() / other.to_f64()).floor()),
+        }
+    }
+}
+
+macro_rules! def_unary_math_op {
+    ($name:ident, $disp:literal, $eval:expr) => {
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct $name{}
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, $disp)
+            }
+        }
+
+        impl Grounded for $name {
+            fn type_(&self) -> Atom {
+                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+            }
+
+            fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+                let arg_error = || ExecError::from(concat!($disp, " expects one number argument"));
+                let a = args.get(0).ok_or_else(arg_error)?.as_gnd::<Number>().ok_or_else(arg_error)?;
+
+                let res = $eval(a)?;
+
+                Ok(vec![Atom::gnd(res)])
+            }
+
+            fn match_(&self, other: &Atom) -> MatchResultIter {
+                match_by_equality(self, other)
+            }
+        }
+    }
+}
+
+macro_rules! def_binary_math_op {
+    ($name:ident, $disp:literal, $eval:expr) => {
+        def_binary_math_op!($name, $disp, false, $eval);
+    };
+    ($name:ident, $disp:literal, $type_level:literal, $eval:expr) => {
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct $name{}
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, $disp)
+            }
+        }
+
+        impl Grounded for $name {
+            fn type_(&self) -> Atom {
+                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+            }
+
+            fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+                let arg_error = || ExecError::from(concat!($disp, " expects two number arguments"));
+                let a = args.get(0).ok_or_else(arg_error)?.as_gnd::<Number>().ok_or_else(arg_error)?;
+                let b = args.get(1).ok_or_else(arg_error)?.as_gnd::<Number>().ok_or_else(arg_error)?;
+
+                let res = $eval(a, b)?;
+
+                Ok(vec![Atom::gnd(res)])
+            }
+
+            fn match_(&self, other: &Atom) -> MatchResultIter {
+                match_by_equality(self, other)
+            }
+
+            fn is_type_level(&self) -> bool {
+                $type_level
+            }
+        }
+    }
+}
+
+// Rounding functions and integer operations return exact numbers, functions
+// which cannot be computed exactly return Float.
+def_unary_math_op!(AbsOp, "abs-math", Number::abs);
+def_unary_math_op!(SqrtOp, "sqrt-math", |a: &Number| a.float_op(f64::sqrt));
+def_unary_math_op!(ExpOp, "exp-math", |a: &Number| a.float_op(f64::exp));
+def_unary_math_op!(LogOp, "log-math", |a: &Number| a.float_op(f64::ln));
+def_unary_math_op!(SinOp, "sin-math", |a: &Number| a.float_op(f64::sin));
+def_unary_math_op!(CosOp, "cos-math", |a: &Number| a.float_op(f64::cos));
+def_unary_math_op!(TanOp, "tan-math", |a: &Number| a.float_op(f64::tan));
+def_unary_math_op!(AsinOp, "asin-math", |a: &Number| a.float_op(f64::asin));
+def_unary_math_op!(AcosOp, "acos-math", |a: &Number| a.float_op(f64::acos));
+def_unary_math_op!(AtanOp, "atan-math", |a: &Number| a.float_op(f64::atan));
+def_unary_math_op!(FloorOp, "floor-math", |a: &Number| a.round_op(BigRational::floor, f64::floor));
+def_unary_math_op!(CeilOp, "ceil-math", |a: &Number| a.round_op(BigRational::ceil, f64::ceil));
+def_unary_math_op!(RoundOp, "round-math", |a: &Number| a.round_op(BigRational::round, f64::round));
+def_unary_math_op!(TruncOp, "trunc-math", |a: &Number| a.round_op(BigRational::trunc, f64::trunc));
+def_unary_math_op!(ToIntOp, "to-int", |a: &Number| a.round_op(BigRational::trunc, f64::trunc));
+def_unary_math_op!(ToFloatOp, "to-float", |a: &Number| a.float_op(|n| n));
+def_unary_math_op!(BitNotOp, "bit-not", |a: &Number| a.to_big_int().map(|n| (!n).into_num()));
+def_binary_math_op!(MinOp, "min-math", true, |a: &Number, b: &Number| Ok::<_, ExecError>(if b < a { b.clone() } else { a.clone() }));
+def_binary_math_op!(MaxOp, "max-math", true, |a: &Number, b: &Number| Ok::<_, ExecError>(if b > a { b.clone() } else { a.clone() }));
+def_binary_math_op!(PowOp, "pow-math", Number::pow);
+def_binary_math_op!(Atan2Op, "atan2-math", |a: &Number, b: &Number| Ok::<_, ExecError>(Number::Float(a.to_f64().atan2(b.to_f64()))));
+def_binary_math_op!(IntDivOp, "int-div", true, Number::int_div);
+def_binary_math_op!(BitAndOp, "bit-and", |a: &Number, b: &Number| a.bit_op(b, |a, b| a & b));
+def_binary_math_op!(BitOrOp, "bit-or", |a: &Number, b: &Number| a.bit_op(b, |a, b| a | b));
+def_binary_math_op!(BitXorOp, "bit-xor", |a: &Number, b: &Number| a.bit_op(b, |a, b| a ^ b));
+def_binary_math_op!(ShlOp, "bit-shl", Number::shl);
+def_binary_math_op!(ShrOp, "bit-shr", Number::shr);
 
 macro_rules! def_binary_bool_op {
     ($name:ident, $disp:ident, $op:tt) => {
@@ -261,6 +615,33 @@ mod tests {
         assert_eq!(format!("{}", Number::Float(123.45f64)), "123.45");
     }
 
+    #[test]
+    fn big_number() {
+        let big = "123456789012345678901234567890".parse::<BigInt>().unwrap();
+        assert_eq!(Number::from_int_str("123456789012345678901234567890"), Number::BigInteger(big.clone()));
+        assert_eq!(Number::from_int_str("9223372036854775807"), Number::Integer(i64::MAX));
+        assert_eq!(format!("{}", Number::BigInteger(big)), "123456789012345678901234567890");
+    }
+
+    #[test]
+    fn rational_number() {
+        let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+        assert_eq!(Number::from_rational_str("1/2"), Number::Rational(half.clone()));
+        assert_eq!(Number::from_rational_str("6/3"), Number::Integer(2));
+        assert_eq!(Number::from_rational_str("2/4"), Number::Rational(half.clone()));
+        assert_eq!(format!("{}", Number::Rational(half.clone())), "1/2");
+        assert_eq!(Number::Rational(half), Number::Float(0.5));
+    }
+
+    #[test]
+    fn number_from_str() {
+        assert_eq!("42".parse::<Number>(), Ok(Number::Integer(42)));
+        assert_eq!("-7/14".parse::<Number>(), Ok(Number::Rational(BigRational::new(BigInt::from(-1), BigInt::from(2)))));
+        assert_eq!("4.2".parse::<Number>(), Ok(Number::Float(4.2)));
+        assert!("1/0".parse::<Number>().is_err());
+        assert!("abc".parse::<Number>().is_err());
+    }
+
     #[test]
     fn bool() {
         assert_eq!(Bool::from_str("True"), Bool(true));
@@ -269,6 +650,24 @@ mod tests {
         assert_eq!(format!("{}", Bool(false)), "False");
     }
 
+    #[test]
+    fn number_hash_is_consistent_with_eq() {
+        use std::collections::HashSet;
+        let numbers: HashSet<Atom> = [Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Float(1.0)),
+            Atom::gnd(Number::Float(-0.0)), Atom::gnd(Number::Integer(0)),
+            Atom::gnd(Number::Float(1.5))].into_iter().collect();
+
+        assert_eq!(numbers.len(), 3);
+        assert!(numbers.contains(&Atom::gnd(Number::Float(0.0))));
+        assert!(!numbers.contains(&Atom::gnd(Number::Integer(2))));
+
+        let numbers: HashSet<Atom> = [Atom::gnd(Number::from_rational_str("1/2")),
+            Atom::gnd(Number::Float(0.5)), Atom::gnd(Number::from_int_str("18446744073709551616")),
+            Atom::gnd(Number::Float(18446744073709551616.0))].into_iter().collect();
+
+        assert_eq!(numbers.len(), 2);
+    }
+
     macro_rules! assert_binary_op {
         ($name:ident, $a: expr, $b: expr, $r: expr) => {
             assert_eq!($name{}.execute(&mut vec![Atom::gnd($a), Atom::gnd($b)]), Ok(vec![Atom::gnd($r)]));
@@ -342,4 +741,114 @@ mod tests {
         assert_binary_op!(ModOp, Number::Float(85.5), Number::Integer(43), Number::Float(42.5));
         assert_binary_op!(ModOp, Number::Float(85.5), Number::Float(43.5), Number::Float(42.0));
     }
+
+    #[test]
+    fn integer_overflow_promotes_to_big_integer() {
+        let max = Number::Integer(i64::MAX);
+        let big = Number::from_int_str("9223372036854775808");
+        assert_binary_op!(SumOp, max.clone(), Number::Integer(1), big.clone());
+        assert_binary_op!(SubOp, big.clone(), Number::Integer(1), max.clone());
+        assert_binary_op!(MulOp, max.clone(), Number::Integer(2), Number::from_int_str("18446744073709551614"));
+        assert_binary_op!(DivOp, Number::Integer(i64::MIN), Number::Integer(-1), big.clone());
+        assert_binary_op!(SubOp, Number::Integer(i64::MIN), Number::Integer(1), Number::from_int_str("-9223372036854775809"));
+        assert_binary_op!(LessOp, max.clone(), big.clone(), Bool(true));
+        assert_binary_op!(SumOp, big, Number::Float(1.0), Number::Float(9223372036854775809.0));
+    }
+
+    #[test]
+    fn rational_ops() {
+        let third = Number::from_rational_str("1/3");
+        assert_binary_op!(SumOp, third.clone(), Number::from_rational_str("1/6"), Number::from_rational_str("1/2"));
+        assert_binary_op!(SumOp, third.clone(), Number::from_rational_str("2/3"), Number::Integer(1));
+        assert_binary_op!(MulOp, third.clone(), Number::Integer(3), Number::Integer(1));
+        assert_binary_op!(DivOp, third.clone(), Number::Integer(2), Number::from_rational_str("1/6"));
+        assert_binary_op!(DivOp, Number::Integer(7), Number::Integer(2), Number::Integer(3));
+        assert_binary_op!(ModOp, Number::from_rational_str("7/2"), Number::Integer(2), Number::from_rational_str("3/2"));
+        assert_binary_op!(LessOp, third.clone(), Number::from_rational_str("1/2"), Bool(true));
+        assert_binary_op!(GreaterEqOp, third.clone(), Number::Float(0.5), Bool(false));
+        assert_binary_op!(SumOp, third, Number::Float(0.5), Number::Float(1.0 / 3.0 + 0.5));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(DivOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(0))]),
+            Err(ExecError::from("Division by zero")));
+        assert_eq!(ModOp{}.execute(&mut vec![Atom::gnd(Number::from_rational_str("1/2")), Atom::gnd(Number::Integer(0))]),
+            Err(ExecError::from("Division by zero")));
+    }
+
+    #[test]
+    fn abs_min_max_ops() {
+        assert_unary_op!(AbsOp, Number::Integer(-42), Number::Integer(42));
+        assert_unary_op!(AbsOp, Number::Integer(i64::MIN), Number::from_int_str("9223372036854775808"));
+        assert_unary_op!(AbsOp, Number::from_rational_str("1/2").sub(&Number::Integer(1)).unwrap(), Number::from_rational_str("1/2"));
+        assert_unary_op!(AbsOp, Number::Float(-4.2), Number::Float(4.2));
+        assert_binary_op!(MinOp, Number::Integer(2), Number::Float(1.5), Number::Float(1.5));
+        assert_binary_op!(MaxOp, Number::Integer(2), Number::Float(1.5), Number::Integer(2));
+    }
+
+    #[test]
+    fn pow_op() {
+        assert_binary_op!(PowOp, Number::Integer(2), Number::Integer(10), Number::Integer(1024));
+        assert_binary_op!(PowOp, Number::Integer(2), Number::Integer(64), Number::from_int_str("18446744073709551616"));
+        assert_binary_op!(PowOp, Number::Integer(2), Number::Integer(-2), Number::from_rational_str("1/4"));
+        assert_binary_op!(PowOp, Number::Integer(4), Number::Float(0.5), Number::Float(2.0));
+        assert_binary_op!(PowOp, Number::Float(1.5), Number::Integer(2), Number::Float(2.25));
+        assert_eq!(PowOp{}.execute(&mut vec![Atom::gnd(Number::Integer(0)), Atom::gnd(Number::Integer(-1))]),
+            Err(ExecError::from("Division by zero")));
+        assert_binary_op!(PowOp, Number::Integer(1), Number::Integer(2147483647), Number::Integer(1));
+        assert_eq!(PowOp{}.execute(&mut vec![Atom::gnd(Number::Integer(10)), Atom::gnd(Number::Integer(2147483647))]),
+            Err(ExecError::from("Integer result is too big: more than 16777216 bits")));
+    }
+
+    #[test]
+    fn float_functions() {
+        assert_unary_op!(SqrtOp, Number::Integer(16), Number::Float(4.0));
+        assert_unary_op!(ExpOp, Number::Integer(0), Number::Float(1.0));
+        assert_unary_op!(LogOp, Number::Integer(1), Number::Float(0.0));
+        assert_unary_op!(SinOp, Number::Integer(0), Number::Float(0.0));
+        assert_unary_op!(CosOp, Number::Integer(0), Number::Float(1.0));
+        assert_unary_op!(AtanOp, Number::Integer(1), Number::Float(std::f64::consts::FRAC_PI_4));
+        assert_binary_op!(Atan2Op, Number::Integer(1), Number::Integer(1), Number::Float(std::f64::consts::FRAC_PI_4));
+        assert_unary_op!(ToFloatOp, Number::from_rational_str("1/4"), Number::Float(0.25));
+    }
+
+    #[test]
+    fn rounding_ops() {
+        assert_unary_op!(FloorOp, Number::Float(-1.5), Number::Integer(-2));
+        assert_unary_op!(CeilOp, Number::Float(-1.5), Number::Integer(-1));
+        assert_unary_op!(RoundOp, Number::Float(2.5), Number::Integer(3));
+        assert_unary_op!(TruncOp, Number::Float(-1.5), Number::Integer(-1));
+        assert_unary_op!(FloorOp, Number::from_rational_str("7/2"), Number::Integer(3));
+        assert_unary_op!(RoundOp, Number::Integer(7), Number::Integer(7));
+        assert_unary_op!(ToIntOp, Number::Float(1e20), Number::from_int_str("100000000000000000000"));
+        assert_eq!(ToIntOp{}.execute(&mut vec![Atom::gnd(Number::Float(f64::NAN))]),
+            Err(ExecError::from("Cannot convert NaN to integer")));
+    }
+
+    #[test]
+    fn int_div_op() {
+        assert_binary_op!(IntDivOp, Number::Integer(7), Number::Integer(2), Number::Integer(3));
+        assert_binary_op!(IntDivOp, Number::Integer(-7), Number::Integer(2), Number::Integer(-4));
+        assert_binary_op!(IntDivOp, Number::Float(7.5), Number::Integer(2), Number::Integer(3));
+        assert_eq!(IntDivOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(0))]),
+            Err(ExecError::from("Division by zero")));
+    }
+
+    #[test]
+    fn bit_ops() {
+        assert_binary_op!(BitAndOp, Number::Integer(12), Number::Integer(10), Number::Integer(8));
+        assert_binary_op!(BitOrOp, Number::Integer(12), Number::Integer(10), Number::Integer(14));
+        assert_binary_op!(BitXorOp, Number::Integer(12), Number::Integer(10), Number::Integer(6));
+        assert_unary_op!(BitNotOp, Number::Integer(0), Number::Integer(-1));
+        assert_binary_op!(ShlOp, Number::Integer(1), Number::Integer(64), Number::from_int_str("18446744073709551616"));
+        assert_binary_op!(ShrOp, Number::Integer(-5), Number::Integer(1), Number::Integer(-3));
+        assert_binary_op!(ShrOp, Number::Integer(-5), Number::Integer(16777216), Number::Integer(-1));
+        assert_eq!(ShlOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(i64::MAX))]),
+            Err(ExecError::from("Shift is too big: 9223372036854775807")));
+        assert_eq!(ShlOp{}.execute(&mut vec![Atom::gnd(Number::Integer(3)), Atom::gnd(Number::Integer(16777215))]),
+            Err(ExecError::from("Integer result is too big: more than 16777216 bits")));
+        assert_eq!(BitAndOp{}.execute(&mut vec![Atom::gnd(Number::Float(1.0)), Atom::gnd(Number::Integer(1))]),
+            Err(ExecError::from("Integer is expected, found: 1")));
+    }
 }
@@ -0,0 +1,207 @@
+//! Bulk loading of large knowledge bases into the runner's space.
+
+use crate::*;
+use crate::space::grounding::GroundingSpace;
+use crate::metta::ERROR_SYMBOL;
+use crate::metta::text::Parser;
+
+use super::{Metta, EXEC_SYMBOL, atom_type_error};
+use super::data::{as_data_declaration, expand_data_declaration};
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// Statistics of the [Metta::load_bulk] call
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkLoadStats {
+    /// Number of atoms kept in the space
+    pub atoms: usize,
+    /// Errors for the atoms which didn't pass the type check and were removed
+    /// from the space
+    pub rejected: Vec<Atom>,
+    /// Time spent on parsing, indexing and type checking
+    pub elapsed: Duration,
+}
+
+impl BulkLoadStats {
+    /// Returns number of atoms processed per second
+    pub fn atoms_per_sec(&self) -> f64 {
+        let total = (self.atoms + self.rejected.len()) as f64;
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { total / secs } else { total }
+    }
+}
+
+impl Display for BulkLoadStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "loaded {} atoms, rejected {} atoms in {:.3}s ({:.0} atoms/s)",
+            self.atoms, self.rejected.len(), self.elapsed.as_secs_f64(), self.atoms_per_sec())
+    }
+}
+
+impl Metta {
+
+    /// Adds all atoms produced by `parser` into the runner's space without
+    /// evaluating them. Unlike [Metta::run] atoms are indexed in a single
+    /// batch, thus the method is suitable for importing large amount of
+    /// facts. The source is parsed completely before the space is modified,
+    /// so the space is left unchanged when the source cannot be parsed.
+    ///
+    /// When `check_types` is `true` type checking is deferred until all atoms
+    /// are added, thus atoms can refer to the types declared later in the
+    /// source. Atoms which are not correctly typed are removed from the space
+    /// and reported in [BulkLoadStats::rejected]. Atoms which were in the
+    /// space before the call are never removed.
+    ///
+    /// Data type declarations `(data <type> <constructor>...)` are expanded
+    /// as by [Metta::run].
+    ///
+    /// NOTE: The runner's space must be a [GroundingSpace], and the source
+    /// must not contain `!` expressions.
+    pub fn load_bulk(&self, mut parser: impl Parser, check_types: bool) -> Result<BulkLoadStats, String> {
+        let start_time = Instant::now();
+        let mut staged = Vec::new();
+        {
+            let tokenizer = self.tokenizer().borrow();
+            while let Some(atom) = parser.next_atom(&tokenizer)? {
+                if atom == EXEC_SYMBOL {
+                    return Err("Bulk loading doesn't support executing expressions".into());
+                }
+                match as_data_declaration(&atom) {
+                    Some((typ, ctors)) => staged.extend(expand_data_declaration(&tokenizer, typ, ctors)?),
+                    None => staged.push(atom),
+                }
+            }
+        }
+
+        let mut space = self.space().borrow_mut();
+        let space = space.as_any_mut()
+            .and_then(|any| any.downcast_mut::<GroundingSpace>())
+            .ok_or_else(|| "Bulk loading is supported only for GroundingSpace".to_string())?;
+        let loaded = staged.len();
+        let positions = space.add_bulk_positions(staged);
+
+        let mut rejected = Vec::new();
+        if check_types {
+            let space_ref: &GroundingSpace = space;
+            let bad_typed: Vec<(usize, Atom)> = positions.into_iter()
+                .filter_map(|position| {
+                    let atom = space_ref.bulk_added(position);
+                    atom_type_error(space_ref, atom)
+                        .map(|reason| (position, Atom::expr([ERROR_SYMBOL, atom.clone(), reason])))
+                })
+                .collect();
+            for (position, err) in bad_typed {
+                space.remove_bulk_added(position);
+                rejected.push(err);
+            }
+        }
+
+        let atoms = loaded - rejected.len();
+        let stats = BulkLoadStats{ atoms, rejected, elapsed: start_time.elapsed() };
+        log::debug!("Metta::load_bulk: {}", stats);
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::space::DynSpace;
+    use crate::common::shared::Shared;
+    use crate::metta::text::Tokenizer;
+    use crate::metta::runner::EnvBuilder;
+    use crate::metta::runner::arithmetics::Number;
+
+    fn new_core_metta() -> Metta {
+        Metta::new_core(DynSpace::new(GroundingSpace::new()),
+            Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()))
+    }
+
+    #[test]
+    fn load_bulk_adds_atoms() {
+        let metta = new_core_metta();
+        let stats = metta.load_bulk(SExprParser::new("
+            (parent Tom Bob)
+            (parent Bob Ann)
+        "), false).unwrap();
+
+        assert_eq!(stats.atoms, 2);
+        assert!(stats.rejected.is_empty());
+        let space = metta.space().borrow();
+        assert_eq!(space.query(&expr!("parent" x "Ann")), bind_set![{x: sym!("Bob")}]);
+    }
+
+    #[test]
+    fn load_bulk_defers_type_check() {
+        let metta = new_core_metta();
+        let stats = metta.load_bulk(SExprParser::new("
+            (foo a)
+            (foo b)
+            (: foo (-> A B))
+            (: a A)
+            (: b C)
+        "), true).unwrap();
+
+        assert_eq!(stats.atoms, 4);
+        assert_eq!(stats.rejected, vec![expr!("Error" ("foo" "b") ("BadArgType" ("foo" "b")
+            {Number::Integer(1)} "A" ("C") ((":" "foo" ("->" "A" "B")) (":" "b" "C"))))]);
+    }
+
+    #[test]
+    fn load_bulk_expands_data_declaration() {
+        let metta = new_core_metta();
+        let stats = metta.load_bulk(SExprParser::new("
+            (data Color Red Green)
+            (favourite Red)
+        "), true).unwrap();
+
+        assert!(stats.rejected.is_empty());
+        let space = metta.space().borrow();
+        assert_eq!(space.query(&expr!(":" "Green" t)), bind_set![{t: sym!("Color")}]);
+        assert!(space.query(&expr!("data" x y z)).is_empty());
+    }
+
+    #[test]
+    fn load_bulk_rejects_exec() {
+        let metta = new_core_metta();
+        assert!(metta.load_bulk(SExprParser::new("!(foo)"), false).is_err());
+    }
+
+    #[test]
+    fn load_bulk_keeps_space_on_parse_error() {
+        let metta = new_core_metta();
+        assert!(metta.load_bulk(SExprParser::new("(a) (b) !(c) (d)"), false).is_err());
+        assert!(metta.load_bulk(SExprParser::new("(a) (b) (c"), false).is_err());
+        assert_eq!(metta.space().borrow().atom_count(), Some(0));
+    }
+
+    #[test]
+    fn load_bulk_keeps_existing_duplicates_of_rejected_atoms() {
+        let metta = new_core_metta();
+        metta.space().borrow_mut().add(expr!("foo" "b"));
+        let stats = metta.load_bulk(SExprParser::new("
+            (foo b)
+            (: foo (-> A B))
+            (: b C)
+        "), true).unwrap();
+
+        assert_eq!(stats.atoms, 2);
+        assert_eq!(stats.rejected.len(), 1);
+        let space = metta.space().borrow();
+        assert_eq!(space.multiplicity(&expr!("foo" "b")), Some(1));
+        assert_eq!(space.atom_count(), Some(3));
+    }
+
+    #[test]
+    fn load_bulk_into_set_keeps_existing_atom() {
+        let metta = Metta::new_core(DynSpace::new(GroundingSpace::new_set()),
+            Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()));
+        metta.space().borrow_mut().add(expr!("foo" "b"));
+        let stats = metta.load_bulk(SExprParser::new("(foo b) (: foo (-> A B)) (: b C)"), true).unwrap();
+
+        assert_eq!(stats.rejected.len(), 1);
+        assert_eq!(metta.space().borrow().multiplicity(&expr!("foo" "b")), Some(1));
+    }
+}
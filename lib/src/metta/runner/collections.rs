@@ -0,0 +1,801 @@
+//! Grounded `Map` and `Set` collections. Both are persistent: operations
+//! return new collections which share the structure with the original ones,
+//! thus adding an element into a big collection is cheap.
+//!
+//! Collections are printed as `(Map (<key> <value>)...)` and
+//! `(Set <element>...)` and can be constructed back from such expressions
+//! using [TryFrom]. Printed map can also be passed to `new-map` in MeTTa
+//! code, for example `!(new-map (Map (a 1) (b 2)))`. The same expressions can be used as patterns: each
+//! entry of the pattern should match some entry of the collection, the
+//! collection can have other entries which are not mentioned in the pattern.
+//! For example `(Map (a $x))` matches a map which contains the key `a` and
+//! binds `$x` to its value.
+
+use crate::*;
+use crate::matcher::{BindingsSet, MatchResultIter, match_atoms};
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Number, Bool, ATOM_TYPE_BOOL, ATOM_TYPE_NUMBER};
+
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+pub const ATOM_TYPE_MAP : Atom = sym!("Map");
+pub const ATOM_TYPE_SET : Atom = sym!("Set");
+
+// Default hasher has fixed keys which makes the order of entries
+// deterministic.
+type FixedState = BuildHasherDefault<DefaultHasher>;
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn has_variables(atom: &Atom) -> bool {
+    atom.iter().filter_type::<&VariableAtom>().next().is_some()
+}
+
+/// Returns entries of the pattern `(<head> <entry>...)` or `None` when
+/// `atom` has different form.
+fn pattern_entries<'a>(atom: &'a Atom, head: &Atom) -> Option<&'a [Atom]> {
+    match atom {
+        Atom::Expression(expr) => match expr.children().split_first() {
+            Some((first, entries)) if first == head => Some(entries),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Matches each entry of the pattern using `match_entry` and merges results.
+fn match_entries<F>(entries: &[Atom], match_entry: F) -> MatchResultIter
+    where F: Fn(&Atom) -> BindingsSet
+{
+    let mut result = BindingsSet::single();
+    for entry in entries {
+        result = result.merge(&match_entry(entry));
+        if result.is_empty() {
+            break;
+        }
+    }
+    Box::new(result.into_iter())
+}
+
+/// Persistent map from atoms to atoms.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct MapAtom(im::HashMap<Atom, Atom, FixedState>);
+
+impl MapAtom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &Atom) -> Option<&Atom> {
+        self.0.get(key)
+    }
+
+    /// Returns new map with the `value` added by the `key`.
+    pub fn insert(&self, key: Atom, value: Atom) -> Self {
+        Self(self.0.update(key, value))
+    }
+
+    /// Returns new map without the `key`.
+    pub fn remove(&self, key: &Atom) -> Self {
+        Self(self.0.without(key))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns entries ordered by the textual representation of the key.
+    pub fn entries(&self) -> Vec<(&Atom, &Atom)> {
+        let mut entries: Vec<(String, (&Atom, &Atom))> = self.0.iter()
+            .map(|(k, v)| (k.to_string(), (k, v))).collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    fn match_entry(&self, entry: &Atom) -> BindingsSet {
+        match entry {
+            Atom::Expression(pair) if pair.children().len() == 2 => {
+                let (key, value) = (&pair.children()[0], &pair.children()[1]);
+                if has_variables(key) {
+                    self.0.iter()
+                        .flat_map(|(k, v)| match_atoms(&Atom::expr([k.clone(), v.clone()]), entry))
+                        .collect()
+                } else {
+                    self.0.get(key).map_or_else(BindingsSet::empty,
+                        |v| match_atoms(v, value).collect())
+                }
+            },
+            _ => BindingsSet::empty(),
+        }
+    }
+}
+
+impl FromIterator<(Atom, Atom)> for MapAtom {
+    fn from_iter<I: IntoIterator<Item=(Atom, Atom)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Converts the list of the `(<key> <value>)` pairs into the map.
+fn map_from_pairs(pairs: &[Atom]) -> Result<MapAtom, String> {
+    pairs.iter().map(|pair| match pair {
+        Atom::Expression(expr) if expr.children().len() == 2 =>
+            Ok((expr.children()[0].clone(), expr.children()[1].clone())),
+        _ => Err(format!("Map entry is expected to be (<key> <value>) pair, found: {}", pair)),
+    }).collect()
+}
+
+impl TryFrom<&Atom> for MapAtom {
+    type Error = String;
+
+    /// Constructs map from the `(Map (<key> <value>)...)` expression.
+    fn try_from(atom: &Atom) -> Result<Self, Self::Error> {
+        let pairs = pattern_entries(atom, &ATOM_TYPE_MAP)
+            .ok_or_else(|| format!("(Map (<key> <value>)...) expression is expected, found: {}", atom))?;
+        map_from_pairs(pairs)
+    }
+}
+
+impl Display for MapAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Map")?;
+        for (key, value) in self.entries() {
+            write!(f, " ({} {})", key, value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Grounded for MapAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_MAP
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match pattern_entries(other, &ATOM_TYPE_MAP) {
+            Some(entries) => match_entries(entries, |entry| self.match_entry(entry)),
+            None => match_by_equality(self, other),
+        }
+    }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        // Sum of the hashes doesn't depend on the order of entries
+        state.write_u64(self.0.iter().map(|entry| hash_of(&entry)).fold(0, u64::wrapping_add));
+    }
+}
+
+/// Persistent set of atoms.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SetAtom(im::HashSet<Atom, FixedState>);
+
+impl SetAtom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, atom: &Atom) -> bool {
+        self.0.contains(atom)
+    }
+
+    /// Returns new set with the `atom` added.
+    pub fn insert(&self, atom: Atom) -> Self {
+        Self(self.0.update(atom))
+    }
+
+    /// Returns new set without the `atom`.
+    pub fn remove(&self, atom: &Atom) -> Self {
+        Self(self.0.without(atom))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns elements ordered by their textual representation.
+    pub fn elements(&self) -> Vec<&Atom> {
+        let mut elements: Vec<(String, &Atom)> = self.0.iter()
+            .map(|atom| (atom.to_string(), atom)).collect();
+        elements.sort_by(|(a, _), (b, _)| a.cmp(b));
+        elements.into_iter().map(|(_, atom)| atom).collect()
+    }
+
+    fn match_element(&self, pattern: &Atom) -> BindingsSet {
+        if has_variables(pattern) {
+            self.0.iter().flat_map(|atom| match_atoms(atom, pattern)).collect()
+        } else if self.0.contains(pattern) {
+            BindingsSet::single()
+        } else {
+            BindingsSet::empty()
+        }
+    }
+}
+
+impl FromIterator<Atom> for SetAtom {
+    fn from_iter<I: IntoIterator<Item=Atom>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl TryFrom<&Atom> for SetAtom {
+    type Error = String;
+
+    /// Constructs set from the `(Set <element>...)` expression.
+    fn try_from(atom: &Atom) -> Result<Self, Self::Error> {
+        pattern_entries(atom, &ATOM_TYPE_SET)
+            .map(|elements| elements.iter().cloned().collect())
+            .ok_or_else(|| format!("(Set <element>...) expression is expected, found: {}", atom))
+    }
+}
+
+impl Display for SetAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(Set")?;
+        for atom in self.elements() {
+            write!(f, " {}", atom)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Grounded for SetAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_SET
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match pattern_entries(other, &ATOM_TYPE_SET) {
+            Some(elements) => match_entries(elements, |element| self.match_element(element)),
+            None => match_by_equality(self, other),
+        }
+    }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        state.write_u64(self.0.iter().map(|entry| hash_of(&entry)).fold(0, u64::wrapping_add));
+    }
+}
+
+fn expr_children(atom: &Atom) -> Option<&[Atom]> {
+    match atom {
+        Atom::Expression(expr) => Some(expr.children().as_slice()),
+        _ => None,
+    }
+}
+
+fn size_atom(size: usize) -> Atom {
+    Atom::gnd(Number::Integer(size as i64))
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NewMapOp {}
+
+impl Display for NewMapOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "new-map")
+    }
+}
+
+impl Grounded for NewMapOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_MAP])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("new-map expects expression of (<key> <value>) pairs or (Map (<key> <value>)...) expression as an argument");
+        let arg = args.first().ok_or_else(arg_error)?;
+        // accept the printed form of the map to make it round-trip
+        let pairs = pattern_entries(arg, &ATOM_TYPE_MAP)
+            .or_else(|| expr_children(arg)).ok_or_else(arg_error)?;
+        let map = map_from_pairs(pairs).map_err(ExecError::from)?;
+        Ok(vec![Atom::gnd(map)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapInsertOp {}
+
+impl Display for MapInsertOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-insert")
+    }
+}
+
+impl Grounded for MapInsertOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, expr!(k), expr!(v), ATOM_TYPE_MAP])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-insert expects map, key and value as arguments");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        let value = args.get(2).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(map.insert(key.clone(), value.clone()))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapRemoveOp {}
+
+impl Display for MapRemoveOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-remove")
+    }
+}
+
+impl Grounded for MapRemoveOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, expr!(k), ATOM_TYPE_MAP])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-remove expects map and key as arguments");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(map.remove(key))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapGetOp {}
+
+impl Display for MapGetOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-get")
+    }
+}
+
+impl Grounded for MapGetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, expr!(k), expr!(v)])
+    }
+
+    /// Returns no results when there is no such key in the map.
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-get expects map and key as arguments");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(map.get(key).into_iter().cloned().collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapContainsOp {}
+
+impl Display for MapContainsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-contains")
+    }
+}
+
+impl Grounded for MapContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, expr!(k), ATOM_TYPE_BOOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-contains expects map and key as arguments");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(map.get(key).is_some()))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapKeysOp {}
+
+impl Display for MapKeysOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-keys")
+    }
+}
+
+impl Grounded for MapKeysOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-keys expects map as an argument");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        Ok(vec![Atom::expr(map.entries().into_iter().map(|(k, _)| k.clone()).collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapValuesOp {}
+
+impl Display for MapValuesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-values")
+    }
+}
+
+impl Grounded for MapValuesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_EXPRESSION])
+    }
+
+    /// Values are returned in the order of the keys returned by `map-keys`.
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-values expects map as an argument");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        Ok(vec![Atom::expr(map.entries().into_iter().map(|(_, v)| v.clone()).collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapSizeOp {}
+
+impl Display for MapSizeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-size")
+    }
+}
+
+impl Grounded for MapSizeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-size expects map as an argument");
+        let map = args.first().and_then(Atom::as_gnd::<MapAtom>).ok_or_else(arg_error)?;
+        Ok(vec![size_atom(map.len())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NewSetOp {}
+
+impl Display for NewSetOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "new-set")
+    }
+}
+
+impl Grounded for NewSetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_SET])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("new-set expects expression of elements as an argument");
+        let elements = args.first().and_then(expr_children).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(elements.iter().cloned().collect::<SetAtom>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SetInsertOp {}
+
+impl Display for SetInsertOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "set-insert")
+    }
+}
+
+impl Grounded for SetInsertOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, expr!(t), ATOM_TYPE_SET])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-insert expects set and element as arguments");
+        let set = args.first().and_then(Atom::as_gnd::<SetAtom>).ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(set.insert(atom.clone()))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SetRemoveOp {}
+
+impl Display for SetRemoveOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "set-remove")
+    }
+}
+
+impl Grounded for SetRemoveOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, expr!(t), ATOM_TYPE_SET])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-remove expects set and element as arguments");
+        let set = args.first().and_then(Atom::as_gnd::<SetAtom>).ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(set.remove(atom))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SetContainsOp {}
+
+impl Display for SetContainsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "set-contains")
+    }
+}
+
+impl Grounded for SetContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, expr!(t), ATOM_TYPE_BOOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-contains expects set and element as arguments");
+        let set = args.first().and_then(Atom::as_gnd::<SetAtom>).ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(set.contains(atom)))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SetElementsOp {}
+
+impl Display for SetElementsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "set-elements")
+    }
+}
+
+impl Grounded for SetElementsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-elements expects set as an argument");
+        let set = args.first().and_then(Atom::as_gnd::<SetAtom>).ok_or_else(arg_error)?;
+        Ok(vec![Atom::expr(set.elements().into_iter().cloned().collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SetSizeOp {}
+
+impl Display for SetSizeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "set-size")
+    }
+}
+
+impl Grounded for SetSizeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-size expects set as an argument");
+        let set = args.first().and_then(Atom::as_gnd::<SetAtom>).ok_or_else(arg_error)?;
+        Ok(vec![size_atom(set.len())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::{SExprParser, Tokenizer};
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use regex::Regex;
+
+    fn metta_atom(text: &str) -> Atom {
+        let mut tokenizer = Tokenizer::new();
+        tokenizer.register_token(Regex::new(r"\d+").unwrap(),
+            |token| Atom::gnd(Number::from_int_str(token)));
+        SExprParser::new(text).parse(&tokenizer).unwrap().expect("Single atom is expected")
+    }
+
+    fn map(text: &str) -> MapAtom {
+        MapAtom::try_from(&metta_atom(text)).unwrap()
+    }
+
+    fn set(text: &str) -> SetAtom {
+        SetAtom::try_from(&metta_atom(text)).unwrap()
+    }
+
+    fn run_program(program: &str) -> Result<Vec<Vec<Atom>>, String> {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.run(SExprParser::new(program))
+    }
+
+    #[test]
+    fn map_is_persistent() {
+        let empty = MapAtom::new();
+        let one = empty.insert(sym!("a"), Atom::gnd(Number::Integer(1)));
+        let two = one.insert(sym!("b"), Atom::gnd(Number::Integer(2)));
+
+        assert!(empty.is_empty());
+        assert_eq!(one.len(), 1);
+        assert_eq!(two.len(), 2);
+        assert_eq!(two.remove(&sym!("b")), one);
+        assert_eq!(two.get(&sym!("b")), Some(&Atom::gnd(Number::Integer(2))));
+        assert_eq!(one.get(&sym!("b")), None);
+    }
+
+    #[test]
+    fn map_display_round_trip() {
+        let map = map("(Map (b 2) (a (x y)) (c \"s\"))");
+
+        assert_eq!(map.to_string(), "(Map (a (x y)) (b 2) (c \"s\"))");
+        assert_eq!(MapAtom::try_from(&metta_atom(&map.to_string())), Ok(map));
+        assert_eq!(MapAtom::new().to_string(), "(Map)");
+        assert!(MapAtom::try_from(&metta_atom("(Map a)")).is_err());
+        assert!(MapAtom::try_from(&metta_atom("(Set a)")).is_err());
+    }
+
+    #[test]
+    fn set_display_round_trip() {
+        let set = set("(Set c a b a)");
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.to_string(), "(Set a b c)");
+        assert_eq!(SetAtom::try_from(&metta_atom(&set.to_string())), Ok(set));
+    }
+
+    #[test]
+    fn map_hash_does_not_depend_on_insertion_order() {
+        use std::collections::HashSet;
+        let maps: HashSet<Atom> = [Atom::gnd(map("(Map (a 1) (b 2))")),
+            Atom::gnd(map("(Map (b 2) (a 1))"))].into_iter().collect();
+
+        assert_eq!(maps.len(), 1);
+    }
+
+    #[test]
+    fn map_match_pattern() {
+        let map = Atom::gnd(map("(Map (a 1) (b 2) (c 1))"));
+
+        assert_eq!(match_atoms(&map, &metta_atom("(Map (b $x))")).collect::<Vec<_>>(),
+            vec![bind!{x: Atom::gnd(Number::Integer(2))}]);
+        assert_eq!(match_atoms(&map, &metta_atom("(Map (d $x))")).count(), 0);
+        assert_eq!(match_atoms(&map, &metta_atom("(Map)")).count(), 1);
+        let mut keys: Vec<String> = match_atoms(&map, &metta_atom("(Map ($k 1))"))
+            .map(|b| b.resolve(&VariableAtom::new("k")).unwrap().to_string()).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "c"]);
+        assert_eq!(match_atoms(&map, &metta_atom("(Map (a $x) (c $x))")).count(), 1);
+        assert_eq!(match_atoms(&map, &metta_atom("(Map (a $x) (b $x))")).count(), 0);
+    }
+
+    #[test]
+    fn set_match_pattern() {
+        let set = Atom::gnd(set("(Set a (b 1) (c 2))"));
+
+        assert_eq!(match_atoms(&set, &metta_atom("(Set a (b $x))")).collect::<Vec<_>>(),
+            vec![bind!{x: Atom::gnd(Number::Integer(1))}]);
+        assert_eq!(match_atoms(&set, &metta_atom("(Set d)")).count(), 0);
+        assert_eq!(match_atoms(&set, &metta_atom("(Set ($k $v))")).count(), 2);
+    }
+
+    #[test]
+    fn map_ops() {
+        assert_eq!(run_program("
+            !(map-get (map-insert (new-map ((a 1))) b (+ 1 1)) b)
+            !(map-size (map-remove (new-map ((a 1) (b 2))) a))
+            !(map-keys (new-map ((b 2) (a 1))))
+            !(map-values (new-map ((b 2) (a 1))))
+            !(map-contains (new-map ((a 1))) b)
+            !(map-get (new-map ((a 1))) b)
+        "), Ok(vec![
+            vec![Atom::gnd(Number::Integer(2))],
+            vec![Atom::gnd(Number::Integer(1))],
+            vec![expr!("a" "b")],
+            vec![expr!({Number::Integer(1)} {Number::Integer(2)})],
+            vec![Atom::gnd(Bool(false))],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn set_ops() {
+        assert_eq!(run_program("
+            !(set-contains (set-insert (new-set (a b)) c) c)
+            !(set-elements (set-remove (new-set (c a b)) b))
+            !(set-size (new-set (a b a)))
+        "), Ok(vec![
+            vec![Atom::gnd(Bool(true))],
+            vec![expr!("a" "c")],
+            vec![Atom::gnd(Number::Integer(2))],
+        ]));
+    }
+
+    #[test]
+    fn map_printed_form_round_trip_in_program() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let map = metta.run(SExprParser::new("!(new-map ((b 2) (a (x y))))")).unwrap()[0][0].clone();
+        let program = format!("!(new-map {})", map);
+
+        assert_eq!(program, "!(new-map (Map (a (x y)) (b 2)))");
+        assert_eq!(metta.run(SExprParser::new(program.as_str())), Ok(vec![vec![map]]));
+        assert_eq!(metta.run(SExprParser::new("!(map-size (new-map (Map)))")),
+            Ok(vec![vec![Atom::gnd(Number::Integer(0))]]));
+    }
+
+    #[test]
+    fn map_pattern_in_program() {
+        assert_eq!(run_program("
+            !(let (Map (b $x)) (new-map ((a 1) (b 2))) $x)
+        "), Ok(vec![vec![Atom::gnd(Number::Integer(2))]]));
+    }
+}
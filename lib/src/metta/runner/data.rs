@@ -0,0 +1,453 @@
+//! Algebraic data type declarations. The runner expands the
+//! `(data <type> <constructor>...)` declaration of the loaded program into the
+//! type and constructor declarations, field accessors and comparison
+//! functions of the data type. The `declare-data` operation does the same at
+//! runtime: `!(declare-data <type> (<constructor>...))`.
+//!
+//! Type is either a symbol or an expression of the symbol and type
+//! parameters, for example `(List $t)`. Constructor is either a symbol or an
+//! expression `(<name> <field>...)` where each field is a type of the field or
+//! `(: <name> <type>)`. An accessor function is generated for each named
+//! field. For instance `(data Point (point (: x Number) (: y Number)))` adds:
+//!
+//! ```metta
+//! (: Point Type)
+//! (: point (-> Number Number Point))
+//! (: x (-> Point Number))
+//! (= (x (point $a1 $a2)) $a1)
+//! (: y (-> Point Number))
+//! (= (y (point $a1 $a2)) $a2)
+//! (: Point-eq (-> Point Point Bool))
+//! (= (Point-eq (point $a1 $a2) (point $b1 $b2)) (and (== $a1 $b1) (== $a2 $b2)))
+//! (: Point-lt (-> Point Point Bool))
+//! (= (Point-lt (point $a1 $a2) (point $b1 $b2))
+//!    (if (< $a1 $b1) True (if (== $a1 $b1) (if (< $a2 $b2) True (if (== $a2 $b2) False False)) False)))
+//! ```
+//!
+//! `<type>-lt` orders values by the position of the constructor in the
+//! declaration and then lexicographically by fields. Fields of the declared
+//! type itself are compared by `<type>-lt`, fields of `Number` type by `<`.
+//! Other fields are not ordered, values which differ only in such fields are
+//! neither less nor greater than each other.
+
+use crate::*;
+use crate::space::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use crate::matcher::MatchResultIter;
+use crate::metta::runner::arithmetics::{ATOM_TYPE_NUMBER, ATOM_TYPE_BOOL};
+use crate::common::shared::Shared;
+
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+/// Head of the data type declaration in the program
+pub const DATA_SYMBOL : Atom = sym!("data");
+
+/// Returns the type and the constructors of the `(data <type> <constructor>...)`
+/// declaration or `None` if `atom` is not a data type declaration.
+pub fn as_data_declaration(atom: &Atom) -> Option<(&Atom, &[Atom])> {
+    match atom {
+        Atom::Expression(expr) => match expr.children().as_slice() {
+            [head, typ, ctors @ ..] if *head == DATA_SYMBOL => Some((typ, ctors)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Expands the declaration of the data type `typ` with constructors `ctors`
+/// into the atoms which should be added into the space. Grounded operations
+/// used by the generated functions are taken from the `tokenizer`. Returns
+/// error message if the declaration is malformed.
+pub fn expand_data_declaration(tokenizer: &Tokenizer, typ: &Atom, ctors: &[Atom]) -> Result<Vec<Atom>, String> {
+    let data = DataType::parse(typ, ctors)?;
+    let mut atoms = vec![data.type_declaration()];
+    atoms.extend(data.ctors.iter().map(|ctor| data.ctor_declaration(ctor)));
+    atoms.extend(data.accessors()?);
+    atoms.extend(data.eq_function(tokenizer));
+    atoms.extend(data.lt_function(tokenizer));
+    Ok(atoms)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DeclareDataOp {
+    space: DynSpace,
+    tokenizer: Shared<Tokenizer>,
+}
+
+impl DeclareDataOp {
+    pub fn new(space: DynSpace, tokenizer: Shared<Tokenizer>) -> Self {
+        Self{ space, tokenizer }
+    }
+}
+
+impl Display for DeclareDataOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "declare-data")
+    }
+}
+
+impl Grounded for DeclareDataOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, UNIT_TYPE()])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("declare-data expects type and list of constructors as arguments");
+        let typ = args.first().ok_or_else(arg_error)?;
+        let ctors = <&[Atom]>::try_from(args.get(1).ok_or_else(arg_error)?).map_err(|_| arg_error())?;
+
+        let atoms = expand_data_declaration(&self.tokenizer.borrow(), typ, ctors)?;
+        let mut space = self.space.borrow_mut();
+        atoms.into_iter().for_each(|atom| space.add(atom));
+        Ok(vec![UNIT_ATOM()])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+struct Field {
+    name: Option<Atom>,
+    typ: Atom,
+}
+
+struct Constructor {
+    name: Atom,
+    fields: Vec<Field>,
+}
+
+struct DataType {
+    typ: Atom,
+    name: String,
+    params: usize,
+    ctors: Vec<Constructor>,
+}
+
+impl DataType {
+    fn parse(typ: &Atom, ctors: &[Atom]) -> Result<Self, String> {
+        if ctors.is_empty() {
+            return Err("declare-data expects at least one constructor".into());
+        }
+        let (name, params) = match typ {
+            Atom::Symbol(name) => (name.name().to_string(), 0),
+            Atom::Expression(expr) => match expr.children().as_slice() {
+                [Atom::Symbol(name), params @ ..] if params.iter().all(|p| matches!(p, Atom::Variable(_))) =>
+                    (name.name().to_string(), params.len()),
+                _ => return Err(format!("Type is expected to be a symbol or a symbol with type parameters: {}", typ)),
+            },
+            _ => return Err(format!("Type is expected to be a symbol or a symbol with type parameters: {}", typ)),
+        };
+        let ctors: Vec<Constructor> = ctors.iter()
+            .map(Constructor::parse)
+            .collect::<Result<_, _>>()?;
+        for (i, ctor) in ctors.iter().enumerate() {
+            if ctors[..i].iter().any(|other| other.name == ctor.name) {
+                return Err(format!("Constructor {} is declared twice", ctor.name));
+            }
+        }
+        Ok(Self{ typ: typ.clone(), name, params, ctors })
+    }
+
+    fn type_declaration(&self) -> Atom {
+        let typ = if self.params == 0 {
+            ATOM_TYPE_TYPE
+        } else {
+            let mut arrow = vec![ARROW_SYMBOL];
+            arrow.extend(std::iter::repeat_n(ATOM_TYPE_TYPE, self.params + 1));
+            Atom::expr(arrow)
+        };
+        Atom::expr([HAS_TYPE_SYMBOL, Atom::sym(&self.name), typ])
+    }
+
+    fn ctor_declaration(&self, ctor: &Constructor) -> Atom {
+        let typ = if ctor.fields.is_empty() {
+            self.typ.clone()
+        } else {
+            let mut arrow = vec![ARROW_SYMBOL];
+            arrow.extend(ctor.fields.iter().map(|field| field.typ.clone()));
+            arrow.push(self.typ.clone());
+            Atom::expr(arrow)
+        };
+        Atom::expr([HAS_TYPE_SYMBOL, ctor.name.clone(), typ])
+    }
+
+    fn accessors(&self) -> Result<Vec<Atom>, String> {
+        let mut declared: Vec<(&Atom, &Atom)> = Vec::new();
+        let mut atoms = Vec::new();
+        for ctor in &self.ctors {
+            for (i, field) in ctor.fields.iter().enumerate() {
+                let name = match &field.name {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if self.ctors.iter().any(|ctor| ctor.name == *name) {
+                    return Err(format!("Field {} has the same name as constructor", name));
+                }
+                match declared.iter().find(|(declared, _)| *declared == name) {
+                    Some((_, typ)) if **typ != field.typ =>
+                        return Err(format!("Field {} is declared with different types: {} and {}", name, typ, field.typ)),
+                    Some(_) => {},
+                    None => {
+                        declared.push((name, &field.typ));
+                        atoms.push(Atom::expr([HAS_TYPE_SYMBOL, name.clone(),
+                            Atom::expr([ARROW_SYMBOL, self.typ.clone(), field.typ.clone()])]));
+                    },
+                }
+                atoms.push(Atom::expr([EQUAL_SYMBOL,
+                    Atom::expr([name.clone(), ctor.pattern("a")]),
+                    field_var("a", i)]));
+            }
+        }
+        Ok(atoms)
+    }
+
+    fn function_declaration(&self, name: &Atom) -> Atom {
+        Atom::expr([HAS_TYPE_SYMBOL, name.clone(),
+            Atom::expr([ARROW_SYMBOL, self.typ.clone(), self.typ.clone(), ATOM_TYPE_BOOL])])
+    }
+
+    /// Generates rules for each pair of constructors using `body` to
+    /// construct the result for the pair of the constructors at the given
+    /// positions.
+    fn pairwise_rules<F>(&self, name: &Atom, body: F) -> Vec<Atom>
+        where F: Fn(usize, usize, &Constructor) -> Atom
+    {
+        let mut atoms = vec![self.function_declaration(name)];
+        for (i, left) in self.ctors.iter().enumerate() {
+            for (j, right) in self.ctors.iter().enumerate() {
+                atoms.push(Atom::expr([EQUAL_SYMBOL,
+                    Atom::expr([name.clone(), left.pattern("a"), right.pattern("b")]),
+                    body(i, j, left)]));
+            }
+        }
+        atoms
+    }
+
+    fn eq_function(&self, tokenizer: &Tokenizer) -> Vec<Atom> {
+        let name = Atom::sym(format!("{}-eq", self.name));
+        let (and, eq) = (token(tokenizer, "and"), token(tokenizer, "=="));
+        let (true_atom, false_atom) = (token(tokenizer, "True"), token(tokenizer, "False"));
+        self.pairwise_rules(&name, |i, j, ctor| {
+            if i != j {
+                return false_atom.clone();
+            }
+            let mut fields = ctor.fields.iter().enumerate().rev().map(|(k, field)| {
+                let op = if field.typ == self.typ { name.clone() } else { eq.clone() };
+                Atom::expr([op, field_var("a", k), field_var("b", k)])
+            });
+            match fields.next() {
+                None => true_atom.clone(),
+                Some(last) => fields.fold(last, |rest, field| Atom::expr([and.clone(), field, rest])),
+            }
+        })
+    }
+
+    fn lt_function(&self, tokenizer: &Tokenizer) -> Vec<Atom> {
+        let name = Atom::sym(format!("{}-lt", self.name));
+        let if_atom = Atom::sym("if");
+        let (lt, eq) = (token(tokenizer, "<"), token(tokenizer, "=="));
+        let (true_atom, false_atom) = (token(tokenizer, "True"), token(tokenizer, "False"));
+        self.pairwise_rules(&name, |i, j, ctor| {
+            if i != j {
+                return if i < j { true_atom.clone() } else { false_atom.clone() };
+            }
+            ctor.fields.iter().enumerate().rev().fold(false_atom.clone(), |rest, (k, field)| {
+                let (a, b) = (field_var("a", k), field_var("b", k));
+                let next = Atom::expr([if_atom.clone(), Atom::expr([eq.clone(), a.clone(), b.clone()]),
+                    rest, false_atom.clone()]);
+                let op = if field.typ == self.typ {
+                    name.clone()
+                } else if field.typ == ATOM_TYPE_NUMBER {
+                    lt.clone()
+                } else {
+                    return next;
+                };
+                Atom::expr([if_atom.clone(), Atom::expr([op, a, b]), true_atom.clone(), next])
+            })
+        })
+    }
+}
+
+impl Constructor {
+    fn parse(atom: &Atom) -> Result<Self, String> {
+        match atom {
+            Atom::Symbol(_) => Ok(Self{ name: atom.clone(), fields: Vec::new() }),
+            Atom::Expression(expr) => match expr.children().as_slice() {
+                [name @ Atom::Symbol(_), fields @ ..] => Ok(Self{
+                    name: name.clone(),
+                    fields: fields.iter().map(Field::parse).collect::<Result<_, _>>()?,
+                }),
+                _ => Err(format!("Constructor is expected to start from a symbol: {}", atom)),
+            },
+            _ => Err(format!("Constructor is expected to be a symbol or an expression: {}", atom)),
+        }
+    }
+
+    /// Returns the constructor applied to the variables with the given prefix.
+    fn pattern(&self, prefix: &str) -> Atom {
+        if self.fields.is_empty() {
+            self.name.clone()
+        } else {
+            let mut children = vec![self.name.clone()];
+            children.extend((0..self.fields.len()).map(|i| field_var(prefix, i)));
+            Atom::expr(children)
+        }
+    }
+}
+
+impl Field {
+    fn parse(atom: &Atom) -> Result<Self, String> {
+        match atom {
+            Atom::Expression(expr) if expr.children().first() == Some(&HAS_TYPE_SYMBOL) => {
+                match expr.children().as_slice() {
+                    [_, name @ Atom::Symbol(_), typ] => Ok(Self{ name: Some(name.clone()), typ: typ.clone() }),
+                    _ => Err(format!("Named field is expected to be (: <name> <type>): {}", atom)),
+                }
+            },
+            _ => Ok(Self{ name: None, typ: atom.clone() }),
+        }
+    }
+}
+
+fn field_var(prefix: &str, i: usize) -> Atom {
+    Atom::var(format!("{}{}", prefix, i + 1))
+}
+
+fn token(tokenizer: &Tokenizer, name: &str) -> Atom {
+    tokenizer.find_token(name).map_or_else(|| Atom::sym(name), |constr| constr(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::metta::runner::arithmetics::{Bool, Number};
+
+    fn parse(text: &str) -> Vec<Atom> {
+        let tokenizer = Tokenizer::new();
+        let mut parser = SExprParser::new(text);
+        let mut atoms = Vec::new();
+        while let Some(atom) = parser.parse(&tokenizer).unwrap() {
+            atoms.push(atom);
+        }
+        atoms
+    }
+
+    fn expand(typ: &str, ctors: &str) -> Result<Vec<Atom>, String> {
+        let typ = &parse(typ)[0];
+        let ctors = parse(ctors);
+        let ctors = <&[Atom]>::try_from(&ctors[0]).unwrap();
+        expand_data_declaration(&Tokenizer::new(), typ, ctors)
+    }
+
+    fn run(metta: &Metta, program: &str) -> Vec<Vec<Atom>> {
+        metta.run(SExprParser::new(program)).unwrap()
+    }
+
+    #[test]
+    fn expand_record() {
+        let atoms = expand("Point", "((point (: x Number) (: y Number)))").unwrap();
+
+        assert_eq!(atoms, parse("
+            (: Point Type)
+            (: point (-> Number Number Point))
+            (: x (-> Point Number))
+            (= (x (point $a1 $a2)) $a1)
+            (: y (-> Point Number))
+            (= (y (point $a1 $a2)) $a2)
+            (: Point-eq (-> Point Point Bool))
+            (= (Point-eq (point $a1 $a2) (point $b1 $b2)) (and (== $a1 $b1) (== $a2 $b2)))
+            (: Point-lt (-> Point Point Bool))
+            (= (Point-lt (point $a1 $a2) (point $b1 $b2))
+               (if (< $a1 $b1) True (if (== $a1 $b1) (if (< $a2 $b2) True (if (== $a2 $b2) False False)) False)))
+        "));
+    }
+
+    #[test]
+    fn expand_parameterized_type() {
+        let atoms = expand("(List $t)", "(Nil (Cons (: head $t) (: tail (List $t))))").unwrap();
+
+        assert_eq!(atoms[..6], parse("
+            (: List (-> Type Type))
+            (: Nil (List $t))
+            (: Cons (-> $t (List $t) (List $t)))
+            (: head (-> (List $t) $t))
+            (= (head (Cons $a1 $a2)) $a1)
+            (: tail (-> (List $t) (List $t)))
+        ")[..]);
+        assert!(atoms.contains(&parse("(= (List-eq (Cons $a1 $a2) (Cons $b1 $b2)) (and (== $a1 $b1) (List-eq $a2 $b2)))")[0]));
+        assert!(atoms.contains(&parse("(= (List-eq Nil (Cons $b1 $b2)) False)")[0]));
+        assert!(atoms.contains(&parse("(= (List-lt Nil (Cons $b1 $b2)) True)")[0]));
+        assert!(atoms.contains(&parse("(= (List-lt (Cons $a1 $a2) Nil) False)")[0]));
+    }
+
+    #[test]
+    fn expand_malformed_declaration() {
+        assert!(expand("Color", "()").is_err());
+        assert!(expand("(Pair A)", "((pair A A))").is_err());
+        assert!(expand("Color", "(Red Red)").is_err());
+        assert!(expand("Shape", "((circle (: size Number)) (square (: size Side)))").is_err());
+        assert!(expand("Shape", "((circle (: circle Number)))").is_err());
+        assert!(expand("Shape", "(((circle) Number))").is_err());
+    }
+
+    #[test]
+    fn data_declaration_in_runner() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        assert_eq!(run(&metta, "
+            !(declare-data Shape ((circle (: radius Number)) (rect (: width Number) (: height Number))))
+        "), vec![vec![UNIT_ATOM()]]);
+
+        assert_eq!(run(&metta, "!(radius (circle 5))"), vec![vec![Atom::gnd(Number::Integer(5))]]);
+        assert_eq!(run(&metta, "!(height (rect 2 3))"), vec![vec![Atom::gnd(Number::Integer(3))]]);
+        assert_eq!(run(&metta, "!(get-type (rect 2 3))"), vec![vec![Atom::sym("Shape")]]);
+        assert_eq!(run(&metta, "!(Shape-eq (rect 2 3) (rect 2 3))"), vec![vec![Atom::gnd(Bool(true))]]);
+        assert_eq!(run(&metta, "!(Shape-eq (rect 2 3) (rect 2 4))"), vec![vec![Atom::gnd(Bool(false))]]);
+        assert_eq!(run(&metta, "!(Shape-eq (circle 2) (rect 2 4))"), vec![vec![Atom::gnd(Bool(false))]]);
+        assert_eq!(run(&metta, "!(Shape-lt (circle 7) (rect 2 4))"), vec![vec![Atom::gnd(Bool(true))]]);
+        assert_eq!(run(&metta, "!(Shape-lt (rect 2 4) (rect 2 3))"), vec![vec![Atom::gnd(Bool(false))]]);
+        assert_eq!(run(&metta, "!(Shape-lt (rect 2 3) (rect 2 4))"), vec![vec![Atom::gnd(Bool(true))]]);
+        assert_eq!(run(&metta, "!(Shape-lt (rect 2 3) (rect 2 3))"), vec![vec![Atom::gnd(Bool(false))]]);
+    }
+
+    #[test]
+    fn data_declaration_in_program() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        assert_eq!(run(&metta, "
+            (data Shape (circle (: radius Number)) (rect (: width Number) (: height Number)))
+            (data (Tree $t) (leaf $t) (node (Tree $t) (Tree $t)))
+            !(radius (circle 5))
+            !(get-type (rect 2 3))
+            !(Shape-lt (circle 7) (rect 2 4))
+            !(get-type (node (leaf 1) (leaf 2)))
+        "), vec![
+            vec![Atom::gnd(Number::Integer(5))],
+            vec![Atom::sym("Shape")],
+            vec![Atom::gnd(Bool(true))],
+            vec![expr!("Tree" "Number")],
+        ]);
+        assert!(metta.space().borrow().query(&expr!("data" x y)).is_empty());
+    }
+
+    #[test]
+    fn data_declaration_in_program_malformed() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = run(&metta, "(data Color) !(get-type Red)");
+        assert_eq!(result.len(), 1);
+        assert!(atom_is_error(&result[0][0]), "Error is expected: {:?}", result);
+    }
+
+    #[test]
+    fn declare_data_op_malformed_declaration() {
+        let space = DynSpace::new(crate::space::grounding::GroundingSpace::new());
+        let op = DeclareDataOp::new(space.clone(), Shared::new(Tokenizer::new()));
+
+        assert!(op.execute(&parse("Color ()")).is_err());
+        assert!(op.execute(&parse("Color Red")).is_err());
+        assert_eq!(space.borrow().atom_count(), Some(0));
+    }
+}
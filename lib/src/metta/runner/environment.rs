@@ -17,7 +17,10 @@ pub struct Environment {
     init_metta_path: Option<PathBuf>,
     working_dir: Option<PathBuf>,
     extra_include_paths: Vec<PathBuf>,
+    allowed_paths: Vec<PathBuf>,
+    allowed_env_vars: Vec<String>,
     is_test: bool,
+    random_seed: Option<u64>,
 }
 
 const DEFAULT_INIT_METTA: &[u8] = include_bytes!("init.default.metta");
@@ -60,6 +63,29 @@ impl Environment {
         self.extra_include_paths.iter().map(|path| path.borrow())
     }
 
+    /// Returns the directories which MeTTa code is allowed to access using file system operations.
+    /// If no directories were added using [EnvBuilder::add_allowed_paths] the working_dir is returned.
+    /// If the working_dir is not set either, nothing is returned and file system operations
+    /// are restricted to the process working directory
+    pub fn allowed_paths<'a>(&'a self) -> impl Iterator<Item=&'a Path> + 'a {
+        let working_dir = match self.allowed_paths.is_empty() {
+            true => self.working_dir.as_deref(),
+            false => None,
+        };
+        self.allowed_paths.iter().map(|path| path.borrow()).chain(working_dir)
+    }
+
+    /// Returns the names of the environment variables which MeTTa code is allowed to read
+    pub fn allowed_env_vars<'a>(&'a self) -> impl Iterator<Item=&'a str> + 'a {
+        self.allowed_env_vars.iter().map(String::as_str)
+    }
+
+    /// Returns the seed used to initialize the random generator of each runner, or `None`
+    /// if generators are initialized from the system entropy
+    pub fn random_seed(&self) -> Option<u64> {
+        self.random_seed
+    }
+
     /// Private "default" function
     fn new() -> Self {
         Self {
@@ -67,7 +93,10 @@ impl Environment {
             init_metta_path: None,
             working_dir: None,
             extra_include_paths: vec![],
+            allowed_paths: vec![],
+            allowed_env_vars: vec![],
             is_test: false,
+            random_seed: None,
         }
     }
 }
@@ -158,6 +187,29 @@ impl EnvBuilder {
         self
     }
 
+    /// Adds directories which MeTTa code is allowed to read and write using file system operations,
+    /// including their subdirectories. When no directories are added only the working_dir is allowed,
+    /// or the process working directory if the working_dir is not set
+    pub fn add_allowed_paths<P: AsRef<Path>, I: IntoIterator<Item=P>>(mut self, paths: I) -> Self {
+        self.env.allowed_paths.extend(paths.into_iter().map(|path| path.as_ref().into()));
+        self
+    }
+
+    /// Adds names of the environment variables which MeTTa code is allowed to read. When no names
+    /// are added no environment variables can be read
+    pub fn add_allowed_env_vars<S: AsRef<str>, I: IntoIterator<Item=S>>(mut self, names: I) -> Self {
+        self.env.allowed_env_vars.extend(names.into_iter().map(|name| name.as_ref().into()));
+        self
+    }
+
+    /// Sets the seed for the random generators of the runners created within the environment
+    ///
+    /// NOTE: The seed can be changed for a specific runner by `!(pragma! random-seed <seed>)`
+    pub fn set_random_seed(mut self, seed: u64) -> Self {
+        self.env.random_seed = Some(seed);
+        self
+    }
+
     /// Adds additional include paths to search for MeTTa modules
     ///
     /// NOTE: The most recently added paths will have the highest search priority, save for the `working_dir`,
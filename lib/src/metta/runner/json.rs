@@ -0,0 +1,250 @@
+//! Conversion between JSON values and atoms. The mapping is the following:
+//!
+//! | JSON    | Atom                                        |
+//! |---------|---------------------------------------------|
+//! | object  | grounded [MapAtom] with string literal keys |
+//! | array   | expression                                  |
+//! | number  | grounded [Number]                           |
+//! | boolean | grounded [Bool]                             |
+//! | string  | string literal, i.e. symbol `"text"`        |
+//! | null    | symbol `null`                               |
+//!
+//! Atoms are converted back to JSON using the same mapping, in addition
+//! symbols which are not string literals are converted into JSON strings.
+//! Variables and grounded atoms which have no JSON representation cannot be
+//! converted.
+
+use crate::*;
+use crate::matcher::MatchResultIter;
+use crate::space::grounding::GroundingSpace;
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Bool, Number};
+use crate::metta::runner::collections::MapAtom;
+use crate::metta::runner::stdlib::{atom_as_str, str_atom};
+
+use std::fmt::Display;
+use std::io::BufRead;
+use std::path::Path;
+use num_bigint::BigInt;
+use serde_json::Value;
+
+pub const NULL_SYMBOL : Atom = sym!("null");
+
+/// Converts JSON value into atom
+pub fn json_to_atom(value: &Value) -> Atom {
+    match value {
+        Value::Null => NULL_SYMBOL,
+        Value::Bool(b) => Atom::gnd(Bool(*b)),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(n), _) => Atom::gnd(Number::Integer(n)),
+            (None, Some(n)) => Atom::gnd(Number::BigInteger(BigInt::from(n))),
+            (None, None) => match n.as_str().parse::<BigInt>() {
+                Ok(n) => Atom::gnd(Number::BigInteger(n)),
+                Err(_) => Atom::gnd(Number::Float(n.as_f64().unwrap_or(f64::NAN))),
+            },
+        },
+        Value::String(s) => str_atom(s),
+        Value::Array(items) => Atom::expr(items.iter().map(json_to_atom).collect::<Vec<_>>()),
+        Value::Object(entries) => Atom::gnd(entries.iter()
+            .map(|(key, value)| (str_atom(key), json_to_atom(value)))
+            .collect::<MapAtom>()),
+    }
+}
+
+/// Converts atom into JSON value, returns error if atom or one of its
+/// sub-atoms has no JSON representation
+pub fn atom_to_json(atom: &Atom) -> Result<Value, String> {
+    let no_json = || format!("{} cannot be represented in JSON", atom);
+    match atom {
+        Atom::Symbol(_) if *atom == NULL_SYMBOL => Ok(Value::Null),
+        Atom::Symbol(_) => Ok(Value::String(atom_as_str(atom).unwrap().into())),
+        Atom::Expression(expr) => expr.children().iter()
+            .map(atom_to_json).collect::<Result<_, _>>().map(Value::Array),
+        Atom::Variable(_) => Err(no_json()),
+        Atom::Grounded(gnd) => {
+            let any = gnd.as_any_ref();
+            if let Some(Bool(b)) = any.downcast_ref::<Bool>() {
+                Ok(Value::Bool(*b))
+            } else if let Some(n) = any.downcast_ref::<Number>() {
+                match n {
+                    Number::Integer(n) => Ok(Value::from(*n)),
+                    Number::BigInteger(n) => match u64::try_from(n) {
+                        Ok(n) => Ok(Value::from(n)),
+                        Err(_) => n.to_string().parse().map(Value::Number).map_err(|_| no_json()),
+                    },
+                    Number::Float(n) => serde_json::Number::from_f64(*n).map(Value::Number).ok_or_else(no_json),
+                    _ => Err(no_json()),
+                }
+            } else if let Some(map) = any.downcast_ref::<MapAtom>() {
+                map.entries().into_iter()
+                    .map(|(key, value)| match key {
+                        Atom::Symbol(_) => Ok((atom_as_str(key).unwrap().into(), atom_to_json(value)?)),
+                        _ => Err(format!("JSON object key is expected to be a string, found: {}", key)),
+                    })
+                    .collect::<Result<_, _>>().map(Value::Object)
+            } else {
+                Err(no_json())
+            }
+        },
+    }
+}
+
+/// Parses JSON text into atom
+pub fn parse_json(text: &str) -> Result<Atom, String> {
+    serde_json::from_str(text).map(|value| json_to_atom(&value))
+        .map_err(|e| format!("Cannot parse JSON: {}", e))
+}
+
+/// Converts atom into JSON text
+pub fn dump_json(atom: &Atom) -> Result<String, String> {
+    atom_to_json(atom).map(|value| value.to_string())
+}
+
+/// Loads JSON-lines text into the space: each non-empty line is parsed as
+/// a JSON value and converted into an atom. Atoms are added using
+/// [GroundingSpace::add_bulk]. Returns the number of atoms loaded.
+pub fn load_json_lines<R: BufRead>(space: &mut GroundingSpace, reader: R) -> Result<usize, String> {
+    let mut atoms = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        atoms.push(parse_json(&line).map_err(|e| format!("Line {}: {}", i + 1, e))?);
+    }
+    let count = atoms.len();
+    space.add_bulk(atoms);
+    Ok(count)
+}
+
+/// Loads JSON-lines file into the space, see [load_json_lines]
+pub fn load_json_lines_file<P: AsRef<Path>>(space: &mut GroundingSpace, path: P) -> Result<usize, String> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Cannot open file {}: {}", path.display(), e))?;
+    load_json_lines(space, std::io::BufReader::new(file))
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct JsonParseOp {}
+
+impl Display for JsonParseOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "json-parse")
+    }
+}
+
+impl Grounded for JsonParseOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let text = args.first().and_then(atom_as_str).ok_or("json-parse expects string as an argument")?;
+        Ok(vec![parse_json(text)?])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct JsonDumpOp {}
+
+impl Display for JsonDumpOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "json-dump")
+    }
+}
+
+impl Grounded for JsonDumpOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SYMBOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("json-dump expects single atom as an argument")?;
+        Ok(vec![str_atom(&dump_json(atom)?)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+
+    fn int(n: i64) -> Atom {
+        Atom::gnd(Number::Integer(n))
+    }
+
+    #[test]
+    fn parse_json_values() {
+        assert_eq!(parse_json("null"), Ok(NULL_SYMBOL));
+        assert_eq!(parse_json("true"), Ok(Atom::gnd(Bool(true))));
+        assert_eq!(parse_json("-42"), Ok(int(-42)));
+        assert_eq!(parse_json("18446744073709551615"), Ok(Atom::gnd(Number::BigInteger(BigInt::from(u64::MAX)))));
+        assert_eq!(parse_json("1.5"), Ok(Atom::gnd(Number::Float(1.5))));
+        assert_eq!(parse_json("\"text\""), Ok(str_atom("text")));
+        assert_eq!(parse_json("[1, [\"a\"], []]"), Ok(Atom::expr([int(1), Atom::expr([str_atom("a")]), Atom::expr([])])));
+        assert_eq!(parse_json("{\"a\": 1, \"b\": null}"),
+            Ok(Atom::gnd([(str_atom("a"), int(1)), (str_atom("b"), NULL_SYMBOL)].into_iter().collect::<MapAtom>())));
+        assert!(parse_json("{\"a\": ").is_err());
+    }
+
+    #[test]
+    fn dump_json_values() {
+        assert_eq!(dump_json(&expr!("null")), Ok("null".into()));
+        assert_eq!(dump_json(&expr!("symbol")), Ok("\"symbol\"".into()));
+        assert_eq!(dump_json(&Atom::expr([int(1), Atom::gnd(Number::Float(0.5)), Atom::gnd(Bool(false))])), Ok("[1,0.5,false]".into()));
+        let map: MapAtom = [(str_atom("b"), Atom::expr([])), (str_atom("a"), str_atom("x"))].into_iter().collect();
+        assert_eq!(dump_json(&Atom::gnd(map)), Ok("{\"a\":\"x\",\"b\":[]}".into()));
+        assert!(dump_json(&expr!(x)).is_err());
+        assert!(dump_json(&Atom::gnd(Number::Float(f64::NAN))).is_err());
+        assert!(dump_json(&Atom::gnd([(int(1), int(2))].into_iter().collect::<MapAtom>())).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        // Keys of the objects are dumped in sorted order
+        let text = "{\"active\":true,\"id\":7,\"name\":\"bob\",\"parent\":null,\"score\":2.5,\"tags\":[\"a\",\"b\"]}";
+        assert_eq!(dump_json(&parse_json(text).unwrap()), Ok(text.into()));
+    }
+
+    #[test]
+    fn json_round_trip_big_integers() {
+        let text = "[18446744073709551615,-123456789012345678901234567890]";
+        let atom = parse_json(text).unwrap();
+        assert_eq!(atom, Atom::expr([Atom::gnd(Number::BigInteger(BigInt::from(u64::MAX))),
+            Atom::gnd(Number::from_int_str("-123456789012345678901234567890"))]));
+        assert_eq!(dump_json(&atom), Ok(text.into()));
+    }
+
+    #[test]
+    fn load_json_lines_into_space() {
+        let mut space = GroundingSpace::new();
+        let text = "[\"parent\", \"Tom\", \"Bob\"]\n\n[\"parent\", \"Bob\", \"Ann\"]\n";
+        assert_eq!(load_json_lines(&mut space, text.as_bytes()), Ok(2));
+        assert_eq!(space.query(&Atom::expr([str_atom("parent"), Atom::var("x"), str_atom("Ann")])),
+            bind_set![{x: str_atom("Bob")}]);
+        assert_eq!(load_json_lines(&mut space, "[1]\n{".as_bytes()).map_err(|e| e.starts_with("Line 2")), Err(true));
+    }
+
+    #[test]
+    fn json_ops_in_runner() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("
+            !(json-parse \"[1, \\\"a\\\", null]\")
+            !(json-dump (1 \"a\" True))
+        "));
+        assert_eq!(result, Ok(vec![
+            vec![Atom::expr([int(1), str_atom("a"), NULL_SYMBOL])],
+            vec![str_atom("[1,\"a\",true]")],
+        ]));
+    }
+}
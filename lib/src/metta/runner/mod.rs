@@ -3,20 +3,25 @@ use crate::common::shared::Shared;
 
 use super::*;
 use super::space::*;
-use super::text::{Tokenizer, Parser, SExprParser};
-use super::types::validate_atom;
+use super::text::{Tokenizer, Parser, SExprParser, StreamParser};
+use super::types::{validate_atom, get_type_error, get_definition_error};
 
 use std::rc::Rc;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::io::BufReader;
 
 mod environment;
 pub use environment::{Environment, EnvBuilder};
 
+mod bulk;
+pub use bulk::BulkLoadStats;
+
 pub mod stdlib;
 #[cfg(not(feature = "minimal"))]
-use super::interpreter::{interpret, interpret_init, interpret_step, InterpreterState};
+use super::interpreter::{interpret, interpret_init, interpret_step, InterpreterState,
+    interpret_gradual, interpret_init_gradual};
 #[cfg(not(feature = "minimal"))]
 use stdlib::*;
 
@@ -28,6 +33,15 @@ use super::interpreter2::{interpret, interpret_init, interpret_step, Interpreter
 use stdlib2::*;
 
 pub mod arithmetics;
+pub mod collections;
+pub mod aggregate;
+pub mod random;
+pub mod system;
+pub mod json;
+pub mod regexp;
+pub mod time;
+pub mod data;
+use random::RandomSource;
 
 const EXEC_SYMBOL : Atom = sym!("!");
 
@@ -45,6 +59,7 @@ pub struct MettaContents {
     space: DynSpace,
     tokenizer: Shared<Tokenizer>,
     settings: Shared<HashMap<String, Atom>>,
+    random: RandomSource,
     modules: Shared<HashMap<PathBuf, DynSpace>>,
     working_dir: Option<PathBuf>,
     environment: Arc<Environment>,
@@ -133,10 +148,12 @@ impl Metta {
             Some(env_builder) => Arc::new(env_builder.build()),
             None => Environment::common_env_arc()
         };
+        let random = RandomSource::new(environment.random_seed());
         let contents = MettaContents{
             space,
             tokenizer,
             settings,
+            random,
             modules,
             working_dir: environment.working_dir().map(|path| path.into()),
             environment,
@@ -153,12 +170,13 @@ impl Metta {
         let tokenizer = metta.tokenizer().clone_inner();
         let environment = metta.0.environment.clone();
         let settings = metta.0.settings.clone();
+        let random = metta.0.random.clone();
         let modules = metta.0.modules.clone();
 
         //Start search for sub-modules in the parent directory of the module we're loading
         let working_dir = path.parent().map(|path| path.into());
 
-        let metta = Self(Rc::new(MettaContents { space, tokenizer, settings, modules, environment, working_dir }));
+        let metta = Self(Rc::new(MettaContents { space, tokenizer, settings, random, modules, environment, working_dir }));
         register_runner_tokens(&metta);
         metta
     }
@@ -182,15 +200,20 @@ impl Metta {
 
                 // Load the module to the new space
                 let runner = Metta::new_loading_runner(self, &path);
-                let program = match path.to_str() {
-                    Some("stdlib") => METTA_CODE.to_string(),
-                    _ => std::fs::read_to_string(&path).map_err(
-                        |err| format!("Could not read file, path: {}, error: {}", path.display(), err))?,
+                // Module files are parsed as a stream to not keep the whole
+                // text of a large knowledge base in memory
+                let parser: Box<dyn Parser> = match path.to_str() {
+                    Some("stdlib") => Box::new(SExprParser::new(METTA_CODE)),
+                    _ => {
+                        let file = std::fs::File::open(&path).map_err(
+                            |err| format!("Could not read file, path: {}, error: {}", path.display(), err))?;
+                        Box::new(StreamParser::new(BufReader::new(file)))
+                    },
                 };
                 // Make the imported module be immediately available to itself
                 // to mitigate circular imports
                 self.0.modules.borrow_mut().insert(path.clone(), runner.space().clone());
-                runner.run(SExprParser::new(program.as_str()))
+                RunnerState::new_with_parser(&runner, parser).run_to_completion()
                     .map_err(|err| format!("Cannot import module, path: {}, error: {}", path.display(), err))?;
 
                 Ok(runner.space().clone())
@@ -233,6 +256,14 @@ impl Metta {
         &self.0.settings
     }
 
+    pub fn environment(&self) -> &Environment {
+        &self.0.environment
+    }
+
+    pub fn random(&self) -> &RandomSource {
+        &self.0.random
+    }
+
     pub fn set_setting(&self, key: String, value: Atom) {
         self.0.settings.borrow_mut().insert(key, value);
     }
@@ -257,20 +288,52 @@ impl Metta {
         let atom = wrap_atom_by_metta_interpreter(self, atom);
         match self.type_check(atom) {
             Err(atom) => Ok(vec![atom]),
+            #[cfg(not(feature = "minimal"))]
+            Ok(atom) if self.is_gradual_typing() => interpret_gradual(self.space(), &atom),
             Ok(atom) => interpret(self.space(), &atom),
         }
     }
 
+    /// Adds the atom of the loaded program into the space. The data type
+    /// declaration `(data <type> <constructor>...)` is replaced by the
+    /// declarations it is expanded to, see [data].
+    fn load_atom(&self, atom: Atom) -> Result<(), Atom> {
+        match data::as_data_declaration(&atom) {
+            Some((typ, ctors)) => {
+                let atoms = data::expand_data_declaration(&self.0.tokenizer.borrow(), typ, ctors)
+                    .map_err(|err| Atom::expr([ERROR_SYMBOL, atom.clone(), Atom::sym(err)]))?;
+                atoms.into_iter().try_for_each(|atom| self.add_atom(atom))
+            },
+            None => self.add_atom(atom),
+        }
+    }
+
     fn add_atom(&self, atom: Atom) -> Result<(), Atom>{
         let atom = self.type_check(atom)?;
         self.0.space.borrow_mut().add(atom);
         Ok(())
     }
 
+    /// Returns true when `type-check` setting is `gradual`. In this mode atoms
+    /// are checked statically as in `auto` mode and values returned by the
+    /// untyped calls are checked at runtime when passed to typed functions.
+    /// Minimal MeTTa interpreter checks the values at runtime in any mode,
+    /// thus `pragma!` doesn't accept `gradual` mode when it is used.
+    #[cfg(not(feature = "minimal"))]
+    fn is_gradual_typing(&self) -> bool {
+        self.get_setting_string("type-check").as_deref() == Some("gradual")
+    }
+
+    /// Returns true when `type-check` setting turns on the static type
+    /// checking of the atoms added into the space, i.e. it is either `auto`
+    /// or `gradual`.
+    pub fn is_type_check_enabled(&self) -> bool {
+        matches!(self.get_setting_string("type-check").as_deref(), Some("auto") | Some("gradual"))
+    }
+
     fn type_check(&self, atom: Atom) -> Result<Atom, Atom> {
-        let is_type_check_enabled = self.get_setting_string("type-check").map_or(false, |val| val == "auto");
-        if  is_type_check_enabled && !validate_atom(self.0.space.borrow().as_space(), &atom) {
-            Err(Atom::expr([ERROR_SYMBOL, atom, BAD_TYPE_SYMBOL]))
+        if self.is_type_check_enabled() {
+            type_check_atom(self.0.space.borrow().as_space(), atom)
         } else {
             Ok(atom)
         }
@@ -278,6 +341,27 @@ impl Metta {
 
 }
 
+/// Checks `atom` is typed correctly and if it is an `=` definition checks
+/// its body against the function type. Returns `(Error <atom> <reason>)`
+/// for the ill-typed `atom`, where the reason explains which part of the
+/// atom failed the check.
+pub(crate) fn type_check_atom(space: &dyn Space, atom: Atom) -> Result<Atom, Atom> {
+    match atom_type_error(space, &atom) {
+        Some(reason) => Err(Atom::expr([ERROR_SYMBOL, atom, reason])),
+        None => Ok(atom),
+    }
+}
+
+/// Returns the reason why `atom` doesn't pass [type_check_atom] or `None`
+/// if it is typed correctly.
+pub(crate) fn atom_type_error(space: &dyn Space, atom: &Atom) -> Option<Atom> {
+    if !validate_atom(space, atom) {
+        Some(get_type_error(space, atom).map_or(BAD_TYPE_SYMBOL, |err| err.to_atom()))
+    } else {
+        get_definition_error(space, atom).map(|err| err.to_atom())
+    }
+}
+
 #[cfg(feature = "minimal")]
 fn wrap_atom_by_metta_interpreter(runner: &Metta, atom: Atom) -> Atom {
     let space = Atom::gnd(runner.space().clone());
@@ -372,7 +456,7 @@ impl<'m, 'i> RunnerState<'m, 'i> {
                 }
                 match self.mode {
                     MettaRunnerMode::ADD => {
-                        if let Err(atom) = self.metta.add_atom(atom) {
+                        if let Err(atom) = self.metta.load_atom(atom) {
                             self.results.push(vec![atom]);
                             self.mode = MettaRunnerMode::TERMINATE;
                             return Ok(());
@@ -384,11 +468,19 @@ impl<'m, 'i> RunnerState<'m, 'i> {
                             Err(atom) => {
                                 InterpreterState::new_finished(self.metta.space().clone(), vec![atom])
                             },
+                            #[cfg(feature = "minimal")]
                             Ok(atom) => {
-                                #[cfg(feature = "minimal")]
                                 let atom = wrap_atom_by_metta_interpreter(&self.metta, atom);
                                 interpret_init(self.metta.space().clone(), &atom)
                             },
+                            #[cfg(not(feature = "minimal"))]
+                            Ok(atom) => {
+                                if self.metta.is_gradual_typing() {
+                                    interpret_init_gradual(self.metta.space().clone(), &atom)
+                                } else {
+                                    interpret_init(self.metta.space().clone(), &atom)
+                                }
+                            },
                         });
                     },
                     MettaRunnerMode::TERMINATE => {
@@ -420,6 +512,7 @@ impl<'m, 'i> RunnerState<'m, 'i> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::arithmetics::Number;
 
     #[test]
     fn test_space() {
@@ -450,7 +543,8 @@ mod tests {
         let metta = Metta::new_core(DynSpace::new(GroundingSpace::new()), Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()));
         metta.set_setting("type-check".into(), sym!("auto"));
         let result = metta.run(SExprParser::new(program));
-        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") "BadType")]]));
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") ("BadArgType" ("foo" "b") {Number::Integer(1)} "A" ("B")
+            ((":" "foo" ("->" "A" "B")) (":" "b" "B"))))]]));
     }
 
     #[test]
@@ -464,7 +558,98 @@ mod tests {
         let metta = Metta::new_core(DynSpace::new(GroundingSpace::new()), Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()));
         metta.set_setting("type-check".into(), sym!("auto"));
         let result = metta.run(SExprParser::new(program));
-        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") "BadType")]]));
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") ("BadArgType" ("foo" "b") {Number::Integer(1)} "A" ("B")
+            ((":" "foo" ("->" "A" "B")) (":" "b" "B"))))]]));
+    }
+
+    #[test]
+    fn metta_add_type_check_definition() {
+        let program = "
+            (: foo (-> A B))
+            (: bar (-> A A))
+            (= (bar $x) $x)
+            (= (foo $x) (bar $x))
+        ";
+
+        let metta = Metta::new_core(DynSpace::new(GroundingSpace::new()), Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()));
+        metta.set_setting("type-check".into(), sym!("auto"));
+        let result = metta.run(SExprParser::new(program));
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("=" ("foo" x) ("bar" x)) ("BadReturnType" ("bar" x) "B" ("A")
+            ((":" "foo" ("->" "A" "B")))))]]));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn metta_gradual_type_check_untyped_call() {
+        let program = "
+            (: foo (-> Number Number))
+            (= (foo $x) $x)
+            (: abc String)
+            (= (untyped-str) abc)
+            (= (untyped-num) 42)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.set_setting("type-check".into(), sym!("gradual"));
+        metta.run(SExprParser::new(program)).unwrap();
+        assert_eq!(metta.run(SExprParser::new("!(foo (untyped-num))")),
+            Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(metta.run(SExprParser::new("!(foo (untyped-undefined))")),
+            Ok(vec![vec![expr!(("untyped-undefined"))]]));
+        assert_eq!(metta.run(SExprParser::new("!(foo (untyped-str))")),
+            Ok(vec![vec![expr!("Error" ("untyped-str") ("BadArgType" ("foo" "abc") {Number::Integer(1)} "Number" ("String")
+                ((":" "foo" ("->" "Number" "Number")) (":" "abc" "String"))))]]));
+    }
+
+    #[cfg(feature = "minimal")]
+    #[test]
+    fn metta_gradual_type_check_is_not_supported() {
+        let program = "
+            (: foo (-> Number Number))
+            (= (foo $x) $x)
+            !(pragma! type-check gradual)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new(program)).unwrap();
+        assert_eq!(result[0][0].to_string(), "(Error (pragma! type-check gradual) gradual type-check mode is not supported by minimal MeTTa interpreter)");
+        assert_eq!(metta.get_setting_string("type-check"), None);
+        assert_eq!(metta.run(SExprParser::new("!(foo 42)")), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn metta_gradual_type_check_keeps_static_checks() {
+        let program = "
+            (: foo (-> Number Number))
+            (: abc String)
+            !(foo abc)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.set_setting("type-check".into(), sym!("gradual"));
+        let result = metta.run(SExprParser::new(program));
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "abc") ("BadArgType" ("foo" "abc") {Number::Integer(1)} "Number" ("String")
+            ((":" "foo" ("->" "Number" "Number")) (":" "abc" "String"))))]]));
+    }
+
+    // minimal interpreter checks types of the returned values at runtime
+    // regardless of the type-check mode
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn metta_no_runtime_type_check_without_gradual_typing() {
+        let program = "
+            (: foo (-> Number Number))
+            (= (foo $x) $x)
+            (: abc String)
+            (= (untyped-str) abc)
+            !(foo (untyped-str))
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.set_setting("type-check".into(), sym!("auto"));
+        let result = metta.run(SExprParser::new(program));
+        assert_eq!(result, Ok(vec![vec![expr!("abc")]]));
     }
 
     #[derive(Clone, PartialEq, Debug)]
@@ -519,7 +704,8 @@ mod tests {
         let metta = Metta::new_core(DynSpace::new(GroundingSpace::new()), Shared::new(Tokenizer::new()), Some(EnvBuilder::test_env()));
         metta.set_setting("type-check".into(), sym!("auto"));
         let result = metta.run(SExprParser::new(program));
-        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") "BadType")]]));
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("foo" "b") ("BadArgType" ("foo" "b") {Number::Integer(1)} "A" ("B")
+            ((":" "foo" ("->" "A" "B")) (":" "b" "B"))))]]));
     }
 
     #[derive(Clone, PartialEq, Debug)]
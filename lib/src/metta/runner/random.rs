@@ -0,0 +1,311 @@
+//! Random numbers and sampling operations. All operations of the runner use
+//! the single [RandomSource] so the sequence of the results is reproducible
+//! when the seed is fixed. The seed can be set using [super::EnvBuilder::set_random_seed]
+//! or by `!(pragma! random-seed <seed>)` from the MeTTa code.
+
+use crate::*;
+use crate::matcher::MatchResultIter;
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Number, ATOM_TYPE_NUMBER};
+use crate::metta::runner::stdlib::{compare_atoms, list_arg};
+use crate::common::shared::Shared;
+
+use std::fmt::Display;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::distributions::WeightedIndex;
+
+/// Name of the setting which sets the seed of the runner's random generator
+pub const RANDOM_SEED_SETTING: &str = "random-seed";
+
+/// Random generator of the runner. It is shared by all random operations
+/// of the runner and by the runners which load modules.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomSource(Shared<StdRng>);
+
+impl RandomSource {
+    /// Returns new generator initialized by `seed`, when `seed` is `None`
+    /// the generator is initialized from the system entropy source
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self(Shared::new(rng))
+    }
+
+    /// Restarts the generator from the `seed`
+    pub fn set_seed(&self, seed: u64) {
+        **self.0.borrow_mut() = StdRng::seed_from_u64(seed);
+    }
+
+    fn with_rng<R, F: FnOnce(&mut StdRng) -> R>(&self, f: F) -> R {
+        f(&mut self.0.borrow_mut())
+    }
+
+    /// Returns one of the `atoms` chosen uniformly. Atoms are sorted before
+    /// choosing to make result independent of the order in which they were
+    /// produced.
+    pub fn choose(&self, mut atoms: Vec<Atom>) -> Vec<Atom> {
+        if atoms.is_empty() {
+            return vec![]
+        }
+        atoms.sort_by(compare_atoms);
+        let i = self.with_rng(|rng| rng.gen_range(0..atoms.len()));
+        vec![atoms.swap_remove(i)]
+    }
+}
+
+fn integer_arg(args: &[Atom], n: usize, usage: &'static str) -> Result<i64, ExecError> {
+    match args.get(n).and_then(Atom::as_gnd::<Number>) {
+        Some(Number::Integer(n)) => Ok(*n),
+        _ => Err(usage.into()),
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomIntOp {
+    random: RandomSource,
+}
+
+impl RandomIntOp {
+    pub fn new(random: RandomSource) -> Self {
+        Self{ random }
+    }
+}
+
+impl Display for RandomIntOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "random-int")
+    }
+}
+
+impl Grounded for RandomIntOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "random-int expects two integer numbers as arguments";
+        let low = integer_arg(args, 0, usage)?;
+        let high = integer_arg(args, 1, usage)?;
+        if low >= high {
+            return Err(format!("random-int expects range [{}, {}) to be non-empty", low, high).into());
+        }
+        let n = self.random.with_rng(|rng| rng.gen_range(low..high));
+        Ok(vec![Atom::gnd(Number::Integer(n))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomFloatOp {
+    random: RandomSource,
+}
+
+impl RandomFloatOp {
+    pub fn new(random: RandomSource) -> Self {
+        Self{ random }
+    }
+}
+
+impl Display for RandomFloatOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "random-float")
+    }
+}
+
+impl Grounded for RandomFloatOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let n: f64 = self.random.with_rng(|rng| rng.gen());
+        Ok(vec![Atom::gnd(Number::Float(n))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShuffleOp {
+    random: RandomSource,
+}
+
+impl ShuffleOp {
+    pub fn new(random: RandomSource) -> Self {
+        Self{ random }
+    }
+}
+
+impl Display for ShuffleOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "shuffle")
+    }
+}
+
+impl Grounded for ShuffleOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let mut list = list_arg(args, 0, "shuffle expects expression as an argument")?.to_vec();
+        self.random.with_rng(|rng| list.shuffle(rng));
+        Ok(vec![Atom::expr(list)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SampleWeightedOp {
+    random: RandomSource,
+}
+
+impl SampleWeightedOp {
+    pub fn new(random: RandomSource) -> Self {
+        Self{ random }
+    }
+}
+
+impl Display for SampleWeightedOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sample-weighted")
+    }
+}
+
+impl Grounded for SampleWeightedOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "sample-weighted expects expression of (<weight> <atom>) pairs as an argument";
+        let (weights, atoms): (Vec<f64>, Vec<&Atom>) = list_arg(args, 0, usage)?.iter()
+            .map(|pair| match pair {
+                Atom::Expression(pair) => match pair.children().as_slice() {
+                    [weight, atom] => weight.as_gnd::<Number>().map(|w| (w.to_f64(), atom)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>().ok_or(usage)?
+            .into_iter().unzip();
+        if atoms.is_empty() {
+            return Ok(vec![])
+        }
+        let index = WeightedIndex::new(&weights)
+            .map_err(|e| format!("sample-weighted expects non-negative weights with positive sum: {}", e))?;
+        let i = self.random.with_rng(|rng| rng.sample(&index));
+        Ok(vec![atoms[i].clone()])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+
+    fn source(seed: u64) -> RandomSource {
+        RandomSource::new(Some(seed))
+    }
+
+    fn numbers(n: i64) -> Atom {
+        Atom::expr((0..n).map(|i| Atom::gnd(Number::Integer(i))).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn random_ops_are_reproducible_with_same_seed() {
+        let draw = |random: RandomSource| {
+            let int = RandomIntOp::new(random.clone()).execute(&[Atom::gnd(Number::Integer(0)), Atom::gnd(Number::Integer(1000))]).unwrap();
+            let float = RandomFloatOp::new(random.clone()).execute(&[]).unwrap();
+            let list = ShuffleOp::new(random).execute(&[numbers(20)]).unwrap();
+            (int, float, list)
+        };
+        assert_eq!(draw(source(42)), draw(source(42)));
+    }
+
+    #[test]
+    fn random_int_op() {
+        let op = RandomIntOp::new(source(1));
+        for _ in 0..100 {
+            let result = op.execute(&[Atom::gnd(Number::Integer(-2)), Atom::gnd(Number::Integer(3))]).unwrap();
+            match result[0].as_gnd::<Number>() {
+                Some(Number::Integer(n)) => assert!((-2..3).contains(n)),
+                _ => panic!("Integer number is expected: {:?}", result),
+            }
+        }
+        assert!(op.execute(&[Atom::gnd(Number::Integer(3)), Atom::gnd(Number::Integer(3))]).is_err());
+        assert!(op.execute(&[Atom::gnd(Number::Float(0.5)), Atom::gnd(Number::Integer(3))]).is_err());
+    }
+
+    #[test]
+    fn shuffle_op_keeps_atoms() {
+        let result = ShuffleOp::new(source(1)).execute(&[numbers(10)]).unwrap();
+        match &result[0] {
+            Atom::Expression(list) => assert_eq_no_order!(list.children(), <&ExpressionAtom>::try_from(&numbers(10)).unwrap().children()),
+            _ => panic!("Expression is expected: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn sample_weighted_op() {
+        let op = SampleWeightedOp::new(source(1));
+        for _ in 0..20 {
+            assert_eq!(op.execute(&[expr!(({Number::Integer(0)} "a") ({Number::Float(2.5)} "b"))]), Ok(vec![expr!("b")]));
+        }
+        assert_eq!(op.execute(&[expr!()]), Ok(vec![]));
+        assert!(op.execute(&[expr!(({Number::Integer(0)} "a"))]).is_err());
+        assert!(op.execute(&[expr!(("a" "b"))]).is_err());
+    }
+
+    #[test]
+    fn choose_ignores_order_of_atoms() {
+        let atoms = vec![expr!("a"), expr!("b"), expr!("c"), expr!("d")];
+        let reversed = atoms.iter().rev().cloned().collect();
+        assert_eq!(source(7).choose(atoms), source(7).choose(reversed));
+        assert_eq!(source(7).choose(vec![]), vec![]);
+    }
+
+    #[test]
+    fn random_seed_pragma_restarts_generator() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("
+            !(pragma! random-seed 42)
+            !(random-int 0 99)
+            !(random-choice (superpose (a b c d e f g h)))
+            !(pragma! random-seed 42)
+            !(random-int 0 99)
+            !(random-choice (superpose (a b c d e f g h)))
+        ")).unwrap();
+        assert_eq!(result[1], result[4]);
+        assert_eq!(result[2], result[5]);
+    }
+
+    #[test]
+    fn random_seed_from_environment() {
+        let program = "
+            !(random-int 0 99)
+            !(shuffle (1 2 3 4 5 6 7 8 9))
+            !(sample-weighted ((1 a) (1 b) (1 c) (1 d)))
+        ";
+        let run = || Metta::new(Some(EnvBuilder::test_env().set_random_seed(7)))
+            .run(SExprParser::new(program)).unwrap();
+        assert_eq!(run(), run());
+    }
+}
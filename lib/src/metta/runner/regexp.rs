@@ -0,0 +1,250 @@
+//! Regular expressions operations. Patterns are passed either as string
+//! literals or as compiled [RegexAtom] which is constructed by
+//! `(regex <pattern>)`. The syntax of the patterns is described in the
+//! documentation of the [regex] crate.
+//!
+//! [RegexAtom] can be used as a pattern: it matches string literals and
+//! symbols which contain a match of the regular expression. For example
+//! `(let $r (regex "@example$") (match &self (email $name $r) $name))`
+//! returns names of all persons which have an address in `example` domain.
+
+use crate::*;
+use crate::matcher::{Bindings, MatchResultIter};
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Bool, ATOM_TYPE_BOOL};
+use crate::metta::runner::stdlib::{atom_as_str, str_atom};
+
+use std::fmt::Display;
+use std::hash::Hasher;
+use regex::Regex;
+
+pub const ATOM_TYPE_REGEX : Atom = sym!("Regex");
+
+/// Compiled regular expression
+#[derive(Clone, Debug)]
+pub struct RegexAtom(Regex);
+
+impl RegexAtom {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern).map(Self).map_err(|e| format!("Incorrect regular expression: {}", e))
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+
+}
+
+impl PartialEq for RegexAtom {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Display for RegexAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(regex {})", str_atom(self.0.as_str()))
+    }
+}
+
+impl Grounded for RegexAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_REGEX
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match other {
+            Atom::Symbol(_) if atom_as_str(other).is_some_and(|text| self.0.is_match(text)) =>
+                Box::new(std::iter::once(Bindings::new())),
+            Atom::Symbol(_) => Box::new(std::iter::empty()),
+            _ => match_by_equality(self, other),
+        }
+    }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        state.write(self.0.as_str().as_bytes());
+    }
+}
+
+/// Returns the regular expression passed as the `n`-th argument, string
+/// literals are compiled on each call
+fn regex_arg(args: &[Atom], n: usize, usage: &'static str) -> Result<RegexAtom, ExecError> {
+    let arg = args.get(n).ok_or(usage)?;
+    match arg.as_gnd::<RegexAtom>() {
+        Some(regex) => Ok(regex.clone()),
+        None => Ok(RegexAtom::new(atom_as_str(arg).ok_or(usage)?)?),
+    }
+}
+
+fn text_arg<'a>(args: &'a [Atom], n: usize, usage: &'static str) -> Result<&'a str, ExecError> {
+    Ok(args.get(n).and_then(atom_as_str).ok_or(usage)?)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegexOp {}
+
+impl Display for RegexOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "regex")
+    }
+}
+
+impl Grounded for RegexOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_REGEX])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let pattern = text_arg(args, 0, "regex expects string pattern as an argument")?;
+        Ok(vec![Atom::gnd(RegexAtom::new(pattern)?)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegexMatchOp {}
+
+impl Display for RegexMatchOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "regex-match")
+    }
+}
+
+impl Grounded for RegexMatchOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SYMBOL, ATOM_TYPE_BOOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "regex-match expects pattern and string as arguments";
+        let regex = regex_arg(args, 0, usage)?;
+        let text = text_arg(args, 1, usage)?;
+        Ok(vec![Atom::gnd(Bool(regex.regex().is_match(text)))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+/// Returns an expression for each match of the pattern in the text. The
+/// expression contains the whole match followed by the capture groups,
+/// groups which didn't participate in the match are returned as `()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegexCapturesOp {}
+
+impl Display for RegexCapturesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "regex-captures")
+    }
+}
+
+impl Grounded for RegexCapturesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SYMBOL, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "regex-captures expects pattern and string as arguments";
+        let regex = regex_arg(args, 0, usage)?;
+        let text = text_arg(args, 1, usage)?;
+        Ok(regex.regex().captures_iter(text)
+            .map(|captures| Atom::expr(captures.iter()
+                .map(|group| group.map_or_else(|| Atom::expr([]), |group| str_atom(group.as_str())))
+                .collect::<Vec<_>>()))
+            .collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegexReplaceOp {}
+
+impl Display for RegexReplaceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "regex-replace")
+    }
+}
+
+impl Grounded for RegexReplaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_SYMBOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "regex-replace expects pattern, string and replacement as arguments";
+        let regex = regex_arg(args, 0, usage)?;
+        let text = text_arg(args, 1, usage)?;
+        let replacement = text_arg(args, 2, usage)?;
+        Ok(vec![str_atom(&regex.regex().replace_all(text, replacement))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::matcher::match_atoms;
+
+    fn regex(pattern: &str) -> Atom {
+        Atom::gnd(RegexAtom::new(pattern).unwrap())
+    }
+
+    #[test]
+    fn regex_match_op() {
+        assert_eq!(RegexMatchOp{}.execute(&[str_atom("b+"), str_atom("abbc")]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(RegexMatchOp{}.execute(&[regex("^b+$"), str_atom("abbc")]), Ok(vec![Atom::gnd(Bool(false))]));
+        assert!(RegexMatchOp{}.execute(&[str_atom("(b"), str_atom("abbc")]).is_err());
+    }
+
+    #[test]
+    fn regex_captures_op() {
+        assert_eq!(RegexCapturesOp{}.execute(&[str_atom(r"(\w)=(\d)?"), str_atom("a=1 b=")]), Ok(vec![
+            Atom::expr([str_atom("a=1"), str_atom("a"), str_atom("1")]),
+            Atom::expr([str_atom("b="), str_atom("b"), Atom::expr([])]),
+        ]));
+        assert_eq!(RegexCapturesOp{}.execute(&[str_atom(r"\d"), str_atom("abc")]), Ok(vec![]));
+    }
+
+    #[test]
+    fn regex_replace_op() {
+        assert_eq!(RegexReplaceOp{}.execute(&[str_atom(r"(\w+)@(\w+)"), str_atom("bob@host, ann@site"), str_atom("$2:$1")]),
+            Ok(vec![str_atom("host:bob, site:ann")]));
+    }
+
+    #[test]
+    fn regex_atom_match() {
+        let regex = regex(r"\w+@\w+");
+        assert_eq!(match_atoms(&regex, &str_atom("mail bob@example")).collect::<Vec<_>>(), vec![Bindings::new()]);
+        assert_eq!(match_atoms(&regex, &expr!("bob")).count(), 0);
+        assert_eq!(match_atoms(&regex, &expr!(("bob@example"))).count(), 0);
+        assert_eq!(match_atoms(&regex, &regex.clone()).count(), 1);
+    }
+
+    #[test]
+    fn regex_in_match_query() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new(r#"
+            (email bob "bob@example")
+            (email ann "ann@test")
+            (email tom "none")
+            !(let $r (regex "@example$") (match &self (email $name $r) $name))
+        "#));
+        assert_eq!(result, Ok(vec![vec![expr!("bob")]]));
+    }
+}
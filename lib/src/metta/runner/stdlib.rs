@@ -3,9 +3,11 @@ use crate::matcher::MatchResultIter;
 use crate::space::*;
 use crate::metta::*;
 use crate::metta::text::Tokenizer;
-use crate::metta::interpreter::interpret;
+use crate::metta::interpreter::{interpret, interpret_limited};
 use crate::metta::runner::Metta;
-use crate::metta::types::{get_atom_types, get_meta_type};
+use crate::metta::lint::{check_cases, LintWarning};
+use crate::metta::types::{get_atom_types, get_meta_type, get_super_types,
+    get_sub_types, get_least_upper_bounds};
 use crate::common::shared::Shared;
 use crate::common::assert::vec_eq_no_order;
 use crate::common::ReplacingMapper;
@@ -14,19 +16,47 @@ use std::convert::TryFrom;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::collections::HashMap;
 use std::iter::FromIterator;
 use std::path::PathBuf;
+use std::cmp::Ordering;
 use regex::Regex;
 
 use super::arithmetics::*;
+use super::collections::*;
+use super::aggregate::*;
+use super::random::*;
+use super::system::*;
+use super::json::*;
+use super::regexp::*;
+use super::time::*;
+use super::data::*;
 
 pub const VOID_SYMBOL : Atom = sym!("%void%");
 
-fn unit_result() -> Result<Vec<Atom>, ExecError> {
+pub(crate) fn unit_result() -> Result<Vec<Atom>, ExecError> {
     Ok(vec![UNIT_ATOM()])
 }
 
+/// Returns text of the string literal. Parser keeps string literals as
+/// symbols which names include the surrounding double quotes, symbols without
+/// quotes are returned as is.
+pub(crate) fn atom_as_str(atom: &Atom) -> Option<&str> {
+    match atom {
+        Atom::Symbol(sym) => {
+            let name = sym.name();
+            Some(name.strip_prefix('"').and_then(|name| name.strip_suffix('"')).unwrap_or(name))
+        },
+        _ => None,
+    }
+}
+
+/// Returns string literal atom containing the `text`
+pub(crate) fn str_atom(text: &str) -> Atom {
+    Atom::sym(format!("\"{}\"", text))
+}
+
 // TODO: remove hiding errors completely after making it possible passing
 // them to the user
 fn interpret_no_error(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String> {
@@ -38,6 +68,15 @@ fn interpret_no_error(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String>
     }
 }
 
+fn interpret_limited_no_error(space: DynSpace, expr: &Atom, limit: usize) -> Result<Vec<Atom>, String> {
+    let result = interpret_limited(space, expr, limit);
+    log::debug!("interpret_limited_no_error: interpretation expr: {}, limit: {}, result {:?}", expr, limit, result);
+    match result {
+        Ok(result) => Ok(result),
+        Err(_) => Ok(vec![]),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ImportOp {
     metta: Metta,
@@ -287,6 +326,93 @@ impl Grounded for RemoveAtomOp {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct NewSetSpaceOp {}
+
+impl Display for NewSetSpaceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "new-set-space")
+    }
+}
+
+impl Grounded for NewSetSpaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>()])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        if args.is_empty() {
+            let space = Atom::gnd(DynSpace::new(GroundingSpace::new_set()));
+            Ok(vec![space])
+        } else {
+            Err("new-set-space doesn't expect arguments".into())
+        }
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AddAtomUniqueOp {}
+
+impl Display for AddAtomUniqueOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "add-atom-unique")
+    }
+}
+
+impl Grounded for AddAtomUniqueOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            ATOM_TYPE_ATOM, ATOM_TYPE_BOOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("add-atom-unique expects two arguments: space and atom");
+        let space = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("add-atom-unique expects a space as the first argument")?;
+        let is_added = space.borrow_mut().add_unique(atom.clone());
+        Ok(vec![Atom::gnd(Bool(is_added))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AtomMultiplicityOp {}
+
+impl Display for AtomMultiplicityOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "atom-multiplicity")
+    }
+}
+
+impl Grounded for AtomMultiplicityOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(),
+            ATOM_TYPE_ATOM, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("atom-multiplicity expects two arguments: space and atom");
+        let space = args.first().ok_or_else(arg_error)?;
+        let atom = args.get(1).ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("atom-multiplicity expects a space as the first argument")?;
+        let count = space.borrow().multiplicity(atom)
+            .ok_or("atom-multiplicity is not supported by the space")?;
+        Ok(vec![Atom::gnd(Number::Integer(count as i64))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct GetAtomsOp {}
 
@@ -401,6 +527,251 @@ impl Grounded for ConsAtomOp {
     }
 }
 
+macro_rules! def_list_op {
+    ($name:ident, $disp:literal, [$($arg:expr),*], $ret:expr, |$args:ident| $body:expr) => {
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct $name {}
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, $disp)
+            }
+        }
+
+        impl Grounded for $name {
+            fn type_(&self) -> Atom {
+                Atom::expr([ARROW_SYMBOL, $($arg,)* $ret])
+            }
+
+            fn execute(&self, $args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+                $body
+            }
+
+            fn match_(&self, other: &Atom) -> MatchResultIter {
+                match_by_equality(self, other)
+            }
+        }
+    }
+}
+
+/// Returns children of the `n`-th argument if it is an expression.
+pub(crate) fn list_arg<'a>(args: &'a [Atom], n: usize, usage: &'static str) -> Result<&'a [Atom], ExecError> {
+    args.get(n).and_then(atom_as_expr).map(|expr| expr.children().as_slice())
+        .ok_or_else(|| ExecError::from(usage))
+}
+
+/// Order used by `sort-atom`: numbers are ordered by value and go first,
+/// other atoms are ordered by their textual representation.
+pub(crate) fn compare_atoms(a: &Atom, b: &Atom) -> Ordering {
+    match (a.as_gnd::<Number>(), b.as_gnd::<Number>()) {
+        (Some(a), Some(b)) => a.partial_cmp(b)
+            .unwrap_or_else(|| a.to_f64().is_nan().cmp(&b.to_f64().is_nan())),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+def_list_op!(LengthAtomOp, "length-atom", [ATOM_TYPE_EXPRESSION], ATOM_TYPE_NUMBER, |args| {
+    let list = list_arg(args, 0, "length-atom expects expression as an argument")?;
+    Ok(vec![Atom::gnd(Number::Integer(list.len() as i64))])
+});
+
+def_list_op!(ReverseAtomOp, "reverse-atom", [ATOM_TYPE_EXPRESSION], ATOM_TYPE_EXPRESSION, |args| {
+    let list = list_arg(args, 0, "reverse-atom expects expression as an argument")?;
+    Ok(vec![Atom::expr(list.iter().rev().cloned().collect::<Vec<_>>())])
+});
+
+def_list_op!(AppendAtomOp, "append-atom", [ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION], ATOM_TYPE_EXPRESSION, |args| {
+    let usage = "append-atom expects two expressions as arguments";
+    let (first, second) = (list_arg(args, 0, usage)?, list_arg(args, 1, usage)?);
+    Ok(vec![Atom::expr([first, second].concat())])
+});
+
+def_list_op!(NthAtomOp, "nth-atom", [ATOM_TYPE_EXPRESSION, ATOM_TYPE_NUMBER], ATOM_TYPE_ATOM, |args| {
+    let usage = "nth-atom expects expression and index as arguments";
+    let list = list_arg(args, 0, usage)?;
+    let index = match args.get(1).and_then(Atom::as_gnd::<Number>) {
+        Some(Number::Integer(n)) => *n,
+        _ => return Err(usage.into()),
+    };
+    usize::try_from(index).ok().and_then(|n| list.get(n))
+        .map(|atom| vec![atom.clone()])
+        .ok_or_else(|| ExecError::from(format!("nth-atom index {} is out of bounds, expression length is {}", index, list.len())))
+});
+
+def_list_op!(ZipAtomOp, "zip-atom", [ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION], ATOM_TYPE_EXPRESSION, |args| {
+    let usage = "zip-atom expects two expressions as arguments";
+    let (first, second) = (list_arg(args, 0, usage)?, list_arg(args, 1, usage)?);
+    Ok(vec![Atom::expr(first.iter().zip(second.iter())
+        .map(|(a, b)| Atom::expr([a.clone(), b.clone()])).collect::<Vec<_>>())])
+});
+
+def_list_op!(SortAtomOp, "sort-atom", [ATOM_TYPE_EXPRESSION], ATOM_TYPE_EXPRESSION, |args| {
+    let mut list = list_arg(args, 0, "sort-atom expects expression as an argument")?.to_vec();
+    list.sort_by(compare_atoms);
+    Ok(vec![Atom::expr(list)])
+});
+
+def_list_op!(UniqueAtomOp, "unique-atom", [ATOM_TYPE_EXPRESSION], ATOM_TYPE_EXPRESSION, |args| {
+    let list = list_arg(args, 0, "unique-atom expects expression as an argument")?;
+    let mut seen = HashSet::new();
+    Ok(vec![Atom::expr(list.iter().filter(|atom| seen.insert(*atom)).cloned().collect::<Vec<_>>())])
+});
+
+/// Applies `func` to each element of the `list` using `eval` to evaluate
+/// `(<func> <element>)`. Each combination of the results of the evaluations
+/// is returned as a separate expression.
+pub(crate) fn map_list<E>(list: &[Atom], func: &Atom, eval: E) -> Result<Vec<Atom>, ExecError>
+    where E: Fn(&Atom) -> Result<Vec<Atom>, String>
+{
+    let mut results = vec![Vec::new()];
+    for atom in list {
+        let values = eval(&Atom::expr([func.clone(), atom.clone()]))?;
+        results = results.iter().flat_map(|prefix| values.iter().map(move |value| {
+            let mut result = prefix.clone();
+            result.push(value.clone());
+            result
+        })).collect();
+    }
+    Ok(results.into_iter().map(Atom::expr).collect())
+}
+
+/// Keeps elements of the `list` for which `(<pred> <element>)` evaluated by
+/// `eval` returns `True`.
+pub(crate) fn filter_list<E>(list: &[Atom], pred: &Atom, eval: E) -> Result<Vec<Atom>, ExecError>
+    where E: Fn(&Atom) -> Result<Vec<Atom>, String>
+{
+    let true_atom = Atom::gnd(Bool(true));
+    let mut result = Vec::new();
+    for atom in list {
+        if eval(&Atom::expr([pred.clone(), atom.clone()]))?.contains(&true_atom) {
+            result.push(atom.clone());
+        }
+    }
+    Ok(vec![Atom::expr(result)])
+}
+
+/// Folds the `list` from the left evaluating `(<func> <acc> <element>)` by
+/// `eval`. When `func` returns more than one result each of them is folded
+/// further.
+pub(crate) fn foldl_list<E>(list: &[Atom], init: &Atom, func: &Atom, eval: E) -> Result<Vec<Atom>, ExecError>
+    where E: Fn(&Atom) -> Result<Vec<Atom>, String>
+{
+    let mut accs = vec![init.clone()];
+    for atom in list {
+        let mut next = Vec::new();
+        for acc in accs {
+            next.extend(eval(&Atom::expr([func.clone(), acc, atom.clone()]))?);
+        }
+        accs = next;
+    }
+    Ok(accs)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapFnOp {
+    space: DynSpace,
+}
+
+impl MapFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for MapFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-fn")
+    }
+}
+
+impl Grounded for MapFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "map-fn expects expression and function as arguments";
+        let list = list_arg(args, 0, usage)?;
+        let func = args.get(1).ok_or(usage)?;
+        map_list(list, func, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FilterFnOp {
+    space: DynSpace,
+}
+
+impl FilterFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for FilterFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "filter-fn")
+    }
+}
+
+impl Grounded for FilterFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "filter-fn expects expression and predicate as arguments";
+        let list = list_arg(args, 0, usage)?;
+        let pred = args.get(1).ok_or(usage)?;
+        filter_list(list, pred, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FoldlFnOp {
+    space: DynSpace,
+}
+
+impl FoldlFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for FoldlFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "foldl-fn")
+    }
+}
+
+impl Grounded for FoldlFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, expr!(a), ATOM_TYPE_ATOM, expr!(a)])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "foldl-fn expects expression, initial value and function as arguments";
+        let list = list_arg(args, 0, usage)?;
+        let init = args.get(1).ok_or(usage)?;
+        let func = args.get(2).ok_or(usage)?;
+        foldl_list(list, init, func, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct CaseOp {
     space: DynSpace,
@@ -678,14 +1049,204 @@ impl Grounded for SuperposeOp {
     }
 }
 
+pub(crate) fn atom_as_count(atom: &Atom) -> Option<usize> {
+    match atom.as_gnd::<Number>() {
+        Some(Number::Integer(count)) => usize::try_from(*count).ok(),
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnceOp {
+    space: DynSpace,
+}
+
+impl OnceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for OnceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "once")
+    }
+}
+
+impl Grounded for OnceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("once expects single executable atom as an argument")?;
+        Ok(interpret_limited_no_error(self.space.clone(), atom, 1)?)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LimitOp {
+    space: DynSpace,
+}
+
+impl LimitOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for LimitOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "limit")
+    }
+}
+
+impl Grounded for LimitOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "limit expects non-negative integer and executable atom as arguments";
+        let limit = args.first().and_then(atom_as_count).ok_or(usage)?;
+        let atom = args.get(1).ok_or(usage)?;
+        // (limit N (offset M expr)) needs only N + M first results of expr,
+        // offset is recognized only when it is written inside limit explicitly
+        match atom_as_expr(atom).map(|expr| expr.children().as_slice()) {
+            Some([op, offset, atom]) if op.as_gnd::<OffsetOp>().is_some() => {
+                let offset = atom_as_count(offset).ok_or(OFFSET_USAGE)?;
+                let results = interpret_limited_no_error(self.space.clone(), atom, limit.saturating_add(offset))?;
+                Ok(results.into_iter().skip(offset).collect())
+            },
+            _ => Ok(interpret_limited_no_error(self.space.clone(), atom, limit)?),
+        }
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+const OFFSET_USAGE: &str = "offset expects non-negative integer and executable atom as arguments";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OffsetOp {
+    space: DynSpace,
+}
+
+impl OffsetOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for OffsetOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "offset")
+    }
+}
+
+impl Grounded for OffsetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let offset = args.first().and_then(atom_as_count).ok_or(OFFSET_USAGE)?;
+        let atom = args.get(1).ok_or(OFFSET_USAGE)?;
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        Ok(results.into_iter().skip(offset).collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomChoiceOp {
+    space: DynSpace,
+    random: RandomSource,
+}
+
+impl RandomChoiceOp {
+    pub fn new(space: DynSpace, random: RandomSource) -> Self {
+        Self{ space, random }
+    }
+}
+
+impl Display for RandomChoiceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "random-choice")
+    }
+}
+
+impl Grounded for RandomChoiceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("random-choice expects single executable atom as an argument")?;
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        Ok(self.random.choose(results))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+/// Evaluates the argument and returns the results together with the time
+/// spent on evaluation: `((<result>...) <duration>)`
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeItOp {
+    space: DynSpace,
+}
+
+impl TimeItOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for TimeItOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time-it")
+    }
+}
+
+impl Grounded for TimeItOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("time-it expects single executable atom as an argument")?;
+        let start = std::time::Instant::now();
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        let elapsed = chrono::TimeDelta::from_std(start.elapsed()).map_err(|e| e.to_string())?;
+        Ok(vec![Atom::expr([Atom::expr(results), Atom::gnd(DurationAtom(elapsed))])])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct PragmaOp {
     settings: Shared<HashMap<String, Atom>>,
+    random: RandomSource,
 }
 
 impl PragmaOp {
-    pub fn new(settings: Shared<HashMap<String, Atom>>) -> Self {
-        Self{ settings }
+    pub fn new(settings: Shared<HashMap<String, Atom>>, random: RandomSource) -> Self {
+        Self{ settings, random }
     }
 }
 
@@ -704,6 +1265,18 @@ impl Grounded for PragmaOp {
         let arg_error = || ExecError::from("pragma! expects key and value as arguments");
         let key = <&SymbolAtom>::try_from(args.get(0).ok_or_else(arg_error)?).map_err(|_| "pragma! expects symbol atom as a key")?.name();
         let value = args.get(1).ok_or_else(arg_error)?;
+        if key == RANDOM_SEED_SETTING {
+            match value.as_gnd::<Number>() {
+                Some(Number::Integer(seed)) => self.random.set_seed(*seed as u64),
+                _ => return Err(format!("pragma! expects integer number as a value of {}", RANDOM_SEED_SETTING).into()),
+            }
+        }
+        // TODO: minimal interpreter checks types of the values returned by
+        // untyped calls at runtime regardless of the type-check mode
+        #[cfg(feature = "minimal")]
+        if key == "type-check" && *value == Atom::sym("gradual") {
+            return Err("gradual type-check mode is not supported by minimal MeTTa interpreter".into());
+        }
         self.settings.borrow_mut().insert(key.into(), value.clone());
         unit_result()
     }
@@ -747,6 +1320,146 @@ impl Grounded for GetTypeOp {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct SuperTypesOp {
+    space: DynSpace,
+}
+
+impl SuperTypesOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for SuperTypesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "supertypes")
+    }
+}
+
+impl Grounded for SuperTypesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("supertypes expects single type as an argument");
+        let typ = args.first().ok_or_else(arg_error)?;
+
+        Ok(get_super_types(self.space.borrow().as_space(), typ))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SubTypesOp {
+    space: DynSpace,
+}
+
+impl SubTypesOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for SubTypesOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "subtypes")
+    }
+}
+
+impl Grounded for SubTypesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("subtypes expects single type as an argument");
+        let typ = args.first().ok_or_else(arg_error)?;
+
+        Ok(get_sub_types(self.space.borrow().as_space(), typ))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LubOp {
+    space: DynSpace,
+}
+
+impl LubOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for LubOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lub")
+    }
+}
+
+impl Grounded for LubOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("lub expects two types as arguments");
+        let a = args.first().ok_or_else(arg_error)?;
+        let b = args.get(1).ok_or_else(arg_error)?;
+
+        Ok(get_least_upper_bounds(self.space.borrow().as_space(), a, b))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+/// Runs [check_cases] on the atom passed and returns the warnings found as
+/// an expression, see [LintWarning::to_atom] for the format of the warning.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LintOp {
+    space: DynSpace,
+}
+
+impl LintOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for LintOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lint")
+    }
+}
+
+impl Grounded for LintOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("lint expects single atom as an argument");
+        let atom = args.first().ok_or_else(arg_error)?;
+
+        let warnings = check_cases(self.space.borrow().as_space(), atom);
+        Ok(vec![Atom::expr(warnings.iter().map(LintWarning::to_atom).collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct GetMetaTypeOp { }
 
@@ -1029,6 +1742,10 @@ impl Grounded for StateAtom {
         // Different state atoms with equal states are equal
         match_by_equality(self, other)
     }
+
+    fn hash_gnd(&self, mut state: &mut dyn Hasher) {
+        self.state.borrow().hash(&mut state);
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -1147,14 +1864,82 @@ pub fn register_common_tokens(metta: &Metta) {
 
     let match_op = Atom::gnd(MatchOp{});
     tref.register_token(regex(r"match"), move |_| { match_op.clone() });
+    let count_matches_op = Atom::gnd(CountMatchesOp{});
+    tref.register_token(regex(r"count-matches"), move |_| { count_matches_op.clone() });
+    let sum_matches_op = Atom::gnd(SumMatchesOp{});
+    tref.register_token(regex(r"sum"), move |_| { sum_matches_op.clone() });
+    let min_by_op = Atom::gnd(MinByOp{});
+    tref.register_token(regex(r"min-by"), move |_| { min_by_op.clone() });
+    let group_by_op = Atom::gnd(GroupByOp{});
+    tref.register_token(regex(r"group-by"), move |_| { group_by_op.clone() });
+    let random = metta.random().clone();
+    let random_int_op = Atom::gnd(RandomIntOp::new(random.clone()));
+    tref.register_token(regex(r"random-int"), move |_| { random_int_op.clone() });
+    let random_float_op = Atom::gnd(RandomFloatOp::new(random.clone()));
+    tref.register_token(regex(r"random-float"), move |_| { random_float_op.clone() });
+    let shuffle_op = Atom::gnd(ShuffleOp::new(random.clone()));
+    tref.register_token(regex(r"shuffle"), move |_| { shuffle_op.clone() });
+    let sample_weighted_op = Atom::gnd(SampleWeightedOp::new(random));
+    tref.register_token(regex(r"sample-weighted"), move |_| { sample_weighted_op.clone() });
+    let sandbox = FileSandbox::new(metta.environment());
+    let read_file_op = Atom::gnd(ReadFileOp::new(sandbox.clone()));
+    tref.register_token(regex(r"read-file"), move |_| { read_file_op.clone() });
+    let write_file_op = Atom::gnd(WriteFileOp::new(sandbox.clone()));
+    tref.register_token(regex(r"write-file"), move |_| { write_file_op.clone() });
+    let list_dir_op = Atom::gnd(ListDirOp::new(sandbox.clone()));
+    tref.register_token(regex(r"list-dir"), move |_| { list_dir_op.clone() });
+    let file_exists_op = Atom::gnd(FileExistsOp::new(sandbox));
+    tref.register_token(regex(r"file-exists"), move |_| { file_exists_op.clone() });
+    let get_env_op = Atom::gnd(GetEnvOp::new(metta.environment()));
+    tref.register_token(regex(r"get-env"), move |_| { get_env_op.clone() });
+    let json_parse_op = Atom::gnd(JsonParseOp{});
+    tref.register_token(regex(r"json-parse"), move |_| { json_parse_op.clone() });
+    let json_dump_op = Atom::gnd(JsonDumpOp{});
+    tref.register_token(regex(r"json-dump"), move |_| { json_dump_op.clone() });
+    let regex_op = Atom::gnd(RegexOp{});
+    tref.register_token(regex(r"regex"), move |_| { regex_op.clone() });
+    let regex_match_op = Atom::gnd(RegexMatchOp{});
+    tref.register_token(regex(r"regex-match"), move |_| { regex_match_op.clone() });
+    let regex_captures_op = Atom::gnd(RegexCapturesOp{});
+    tref.register_token(regex(r"regex-captures"), move |_| { regex_captures_op.clone() });
+    let regex_replace_op = Atom::gnd(RegexReplaceOp{});
+    tref.register_token(regex(r"regex-replace"), move |_| { regex_replace_op.clone() });
+    let now_op = Atom::gnd(NowOp{});
+    tref.register_token(regex(r"now"), move |_| { now_op.clone() });
+    let monotonic_ms_op = Atom::gnd(MonotonicMsOp{});
+    tref.register_token(regex(r"monotonic-ms"), move |_| { monotonic_ms_op.clone() });
+    let time_op = Atom::gnd(TimeOp{});
+    tref.register_token(regex(r"time"), move |_| { time_op.clone() });
+    let time_format_op = Atom::gnd(TimeFormatOp{});
+    tref.register_token(regex(r"time-format"), move |_| { time_format_op.clone() });
+    let duration_ms_op = Atom::gnd(DurationMsOp{});
+    tref.register_token(regex(r"duration-ms"), move |_| { duration_ms_op.clone() });
+    let duration_to_ms_op = Atom::gnd(DurationToMsOp{});
+    tref.register_token(regex(r"duration-to-ms"), move |_| { duration_to_ms_op.clone() });
+    let time_add_op = Atom::gnd(TimeAddOp{});
+    tref.register_token(regex(r"time-add"), move |_| { time_add_op.clone() });
+    let time_sub_op = Atom::gnd(TimeSubOp{});
+    tref.register_token(regex(r"time-sub"), move |_| { time_sub_op.clone() });
+    let time_lt_op = Atom::gnd(TimeLessOp{});
+    tref.register_token(regex(r"time-lt"), move |_| { time_lt_op.clone() });
+    let time_gt_op = Atom::gnd(TimeGreaterOp{});
+    tref.register_token(regex(r"time-gt"), move |_| { time_gt_op.clone() });
+    let time_eq_op = Atom::gnd(TimeEqOp{});
+    tref.register_token(regex(r"time-eq"), move |_| { time_eq_op.clone() });
     let bind_op = Atom::gnd(BindOp::new(tokenizer.clone()));
     tref.register_token(regex(r"bind!"), move |_| { bind_op.clone() });
     let new_space_op = Atom::gnd(NewSpaceOp{});
     tref.register_token(regex(r"new-space"), move |_| { new_space_op.clone() });
+    let new_set_space_op = Atom::gnd(NewSetSpaceOp{});
+    tref.register_token(regex(r"new-set-space"), move |_| { new_set_space_op.clone() });
     let add_atom_op = Atom::gnd(AddAtomOp{});
     tref.register_token(regex(r"add-atom"), move |_| { add_atom_op.clone() });
+    let add_atom_unique_op = Atom::gnd(AddAtomUniqueOp{});
+    tref.register_token(regex(r"add-atom-unique"), move |_| { add_atom_unique_op.clone() });
     let remove_atom_op = Atom::gnd(RemoveAtomOp{});
     tref.register_token(regex(r"remove-atom"), move |_| { remove_atom_op.clone() });
+    let atom_multiplicity_op = Atom::gnd(AtomMultiplicityOp{});
+    tref.register_token(regex(r"atom-multiplicity"), move |_| { atom_multiplicity_op.clone() });
     let get_atoms_op = Atom::gnd(GetAtomsOp{});
     tref.register_token(regex(r"get-atoms"), move |_| { get_atoms_op.clone() });
     let car_atom_op = Atom::gnd(CarAtomOp{});
@@ -1163,6 +1948,20 @@ pub fn register_common_tokens(metta: &Metta) {
     tref.register_token(regex(r"cdr-atom"), move |_| { cdr_atom_op.clone() });
     let cons_atom_op = Atom::gnd(ConsAtomOp{});
     tref.register_token(regex(r"cons-atom"), move |_| { cons_atom_op.clone() });
+    let length_atom_op = Atom::gnd(LengthAtomOp{});
+    tref.register_token(regex(r"length-atom"), move |_| { length_atom_op.clone() });
+    let reverse_atom_op = Atom::gnd(ReverseAtomOp{});
+    tref.register_token(regex(r"reverse-atom"), move |_| { reverse_atom_op.clone() });
+    let append_atom_op = Atom::gnd(AppendAtomOp{});
+    tref.register_token(regex(r"append-atom"), move |_| { append_atom_op.clone() });
+    let nth_atom_op = Atom::gnd(NthAtomOp{});
+    tref.register_token(regex(r"nth-atom"), move |_| { nth_atom_op.clone() });
+    let zip_atom_op = Atom::gnd(ZipAtomOp{});
+    tref.register_token(regex(r"zip-atom"), move |_| { zip_atom_op.clone() });
+    let sort_atom_op = Atom::gnd(SortAtomOp{});
+    tref.register_token(regex(r"sort-atom"), move |_| { sort_atom_op.clone() });
+    let unique_atom_op = Atom::gnd(UniqueAtomOp{});
+    tref.register_token(regex(r"unique-atom"), move |_| { unique_atom_op.clone() });
     let println_op = Atom::gnd(PrintlnOp{});
     tref.register_token(regex(r"println!"), move |_| { println_op.clone() });
     let trace_op = Atom::gnd(TraceOp{});
@@ -1181,6 +1980,34 @@ pub fn register_common_tokens(metta: &Metta) {
     tref.register_token(regex(r"get-state"), move |_| { get_state_op.clone() });
     let get_meta_type_op = Atom::gnd(GetMetaTypeOp{});
     tref.register_token(regex(r"get-metatype"), move |_| { get_meta_type_op.clone() });
+    let new_map_op = Atom::gnd(NewMapOp{});
+    tref.register_token(regex(r"new-map"), move |_| { new_map_op.clone() });
+    let map_insert_op = Atom::gnd(MapInsertOp{});
+    tref.register_token(regex(r"map-insert"), move |_| { map_insert_op.clone() });
+    let map_remove_op = Atom::gnd(MapRemoveOp{});
+    tref.register_token(regex(r"map-remove"), move |_| { map_remove_op.clone() });
+    let map_get_op = Atom::gnd(MapGetOp{});
+    tref.register_token(regex(r"map-get"), move |_| { map_get_op.clone() });
+    let map_contains_op = Atom::gnd(MapContainsOp{});
+    tref.register_token(regex(r"map-contains"), move |_| { map_contains_op.clone() });
+    let map_keys_op = Atom::gnd(MapKeysOp{});
+    tref.register_token(regex(r"map-keys"), move |_| { map_keys_op.clone() });
+    let map_values_op = Atom::gnd(MapValuesOp{});
+    tref.register_token(regex(r"map-values"), move |_| { map_values_op.clone() });
+    let map_size_op = Atom::gnd(MapSizeOp{});
+    tref.register_token(regex(r"map-size"), move |_| { map_size_op.clone() });
+    let new_set_op = Atom::gnd(NewSetOp{});
+    tref.register_token(regex(r"new-set"), move |_| { new_set_op.clone() });
+    let set_insert_op = Atom::gnd(SetInsertOp{});
+    tref.register_token(regex(r"set-insert"), move |_| { set_insert_op.clone() });
+    let set_remove_op = Atom::gnd(SetRemoveOp{});
+    tref.register_token(regex(r"set-remove"), move |_| { set_remove_op.clone() });
+    let set_contains_op = Atom::gnd(SetContainsOp{});
+    tref.register_token(regex(r"set-contains"), move |_| { set_contains_op.clone() });
+    let set_elements_op = Atom::gnd(SetElementsOp{});
+    tref.register_token(regex(r"set-elements"), move |_| { set_elements_op.clone() });
+    let set_size_op = Atom::gnd(SetSizeOp{});
+    tref.register_token(regex(r"set-size"), move |_| { set_size_op.clone() });
 }
 
 pub fn register_runner_tokens(metta: &Metta) {
@@ -1191,6 +2018,8 @@ pub fn register_runner_tokens(metta: &Metta) {
 
     let case_op = Atom::gnd(CaseOp::new(space.clone()));
     tref.register_token(regex(r"case"), move |_| { case_op.clone() });
+    let declare_data_op = Atom::gnd(DeclareDataOp::new(space.clone(), tokenizer.clone()));
+    tref.register_token(regex(r"declare-data"), move |_| { declare_data_op.clone() });
     let assert_equal_op = Atom::gnd(AssertEqualOp::new(space.clone()));
     tref.register_token(regex(r"assertEqual"), move |_| { assert_equal_op.clone() });
     let assert_equal_to_result_op = Atom::gnd(AssertEqualToResultOp::new(space.clone()));
@@ -1199,11 +2028,35 @@ pub fn register_runner_tokens(metta: &Metta) {
     tref.register_token(regex(r"collapse"), move |_| { collapse_op.clone() });
     let superpose_op = Atom::gnd(SuperposeOp::new(space.clone()));
     tref.register_token(regex(r"superpose"), move |_| { superpose_op.clone() });
+    let once_op = Atom::gnd(OnceOp::new(space.clone()));
+    tref.register_token(regex(r"once"), move |_| { once_op.clone() });
+    let limit_op = Atom::gnd(LimitOp::new(space.clone()));
+    tref.register_token(regex(r"limit"), move |_| { limit_op.clone() });
+    let offset_op = Atom::gnd(OffsetOp::new(space.clone()));
+    tref.register_token(regex(r"offset"), move |_| { offset_op.clone() });
+    let random_choice_op = Atom::gnd(RandomChoiceOp::new(space.clone(), metta.random().clone()));
+    tref.register_token(regex(r"random-choice"), move |_| { random_choice_op.clone() });
+    let time_it_op = Atom::gnd(TimeItOp::new(space.clone()));
+    tref.register_token(regex(r"time-it"), move |_| { time_it_op.clone() });
+    let map_fn_op = Atom::gnd(MapFnOp::new(space.clone()));
+    tref.register_token(regex(r"map-fn"), move |_| { map_fn_op.clone() });
+    let filter_fn_op = Atom::gnd(FilterFnOp::new(space.clone()));
+    tref.register_token(regex(r"filter-fn"), move |_| { filter_fn_op.clone() });
+    let foldl_fn_op = Atom::gnd(FoldlFnOp::new(space.clone()));
+    tref.register_token(regex(r"foldl-fn"), move |_| { foldl_fn_op.clone() });
     let get_type_op = Atom::gnd(GetTypeOp::new(space.clone()));
     tref.register_token(regex(r"get-type"), move |_| { get_type_op.clone() });
+    let super_types_op = Atom::gnd(SuperTypesOp::new(space.clone()));
+    tref.register_token(regex(r"supertypes"), move |_| { super_types_op.clone() });
+    let sub_types_op = Atom::gnd(SubTypesOp::new(space.clone()));
+    tref.register_token(regex(r"subtypes"), move |_| { sub_types_op.clone() });
+    let lub_op = Atom::gnd(LubOp::new(space.clone()));
+    tref.register_token(regex(r"lub"), move |_| { lub_op.clone() });
+    let lint_op = Atom::gnd(LintOp::new(space.clone()));
+    tref.register_token(regex(r"lint"), move |_| { lint_op.clone() });
     let import_op = Atom::gnd(ImportOp::new(metta.clone()));
     tref.register_token(regex(r"import!"), move |_| { import_op.clone() });
-    let pragma_op = Atom::gnd(PragmaOp::new(metta.settings().clone()));
+    let pragma_op = Atom::gnd(PragmaOp::new(metta.settings().clone(), metta.random().clone()));
     tref.register_token(regex(r"pragma!"), move |_| { pragma_op.clone() });
 
     // &self should be updated
@@ -1225,6 +2078,8 @@ pub fn register_rust_tokens(metta: &Metta) {
         |token| { Atom::gnd(Number::from_int_str(token)) });
     tref.register_token(regex(r"\d+(.\d+)([eE][\-\+]?\d+)?"),
         |token| { Atom::gnd(Number::from_float_str(token)) });
+    tref.register_token(regex(r"\d+/0*[1-9]\d*"),
+        |token| { Atom::gnd(Number::from_rational_str(token)) });
     tref.register_token(regex(r"True|False"),
         |token| { Atom::gnd(Bool::from_str(token)) });
     let sum_op = Atom::gnd(SumOp{});
@@ -1237,6 +2092,60 @@ pub fn register_rust_tokens(metta: &Metta) {
     tref.register_token(regex(r"/"), move |_| { div_op.clone() });
     let mod_op = Atom::gnd(ModOp{});
     tref.register_token(regex(r"%"), move |_| { mod_op.clone() });
+    let abs_op = Atom::gnd(AbsOp{});
+    tref.register_token(regex(r"abs-math"), move |_| { abs_op.clone() });
+    let min_op = Atom::gnd(MinOp{});
+    tref.register_token(regex(r"min-math"), move |_| { min_op.clone() });
+    let max_op = Atom::gnd(MaxOp{});
+    tref.register_token(regex(r"max-math"), move |_| { max_op.clone() });
+    let pow_op = Atom::gnd(PowOp{});
+    tref.register_token(regex(r"pow-math"), move |_| { pow_op.clone() });
+    let sqrt_op = Atom::gnd(SqrtOp{});
+    tref.register_token(regex(r"sqrt-math"), move |_| { sqrt_op.clone() });
+    let exp_op = Atom::gnd(ExpOp{});
+    tref.register_token(regex(r"exp-math"), move |_| { exp_op.clone() });
+    let log_op = Atom::gnd(LogOp{});
+    tref.register_token(regex(r"log-math"), move |_| { log_op.clone() });
+    let sin_op = Atom::gnd(SinOp{});
+    tref.register_token(regex(r"sin-math"), move |_| { sin_op.clone() });
+    let cos_op = Atom::gnd(CosOp{});
+    tref.register_token(regex(r"cos-math"), move |_| { cos_op.clone() });
+    let tan_op = Atom::gnd(TanOp{});
+    tref.register_token(regex(r"tan-math"), move |_| { tan_op.clone() });
+    let asin_op = Atom::gnd(AsinOp{});
+    tref.register_token(regex(r"asin-math"), move |_| { asin_op.clone() });
+    let acos_op = Atom::gnd(AcosOp{});
+    tref.register_token(regex(r"acos-math"), move |_| { acos_op.clone() });
+    let atan_op = Atom::gnd(AtanOp{});
+    tref.register_token(regex(r"atan-math"), move |_| { atan_op.clone() });
+    let atan2_op = Atom::gnd(Atan2Op{});
+    tref.register_token(regex(r"atan2-math"), move |_| { atan2_op.clone() });
+    let floor_op = Atom::gnd(FloorOp{});
+    tref.register_token(regex(r"floor-math"), move |_| { floor_op.clone() });
+    let ceil_op = Atom::gnd(CeilOp{});
+    tref.register_token(regex(r"ceil-math"), move |_| { ceil_op.clone() });
+    let round_op = Atom::gnd(RoundOp{});
+    tref.register_token(regex(r"round-math"), move |_| { round_op.clone() });
+    let trunc_op = Atom::gnd(TruncOp{});
+    tref.register_token(regex(r"trunc-math"), move |_| { trunc_op.clone() });
+    let int_div_op = Atom::gnd(IntDivOp{});
+    tref.register_token(regex(r"int-div"), move |_| { int_div_op.clone() });
+    let bit_and_op = Atom::gnd(BitAndOp{});
+    tref.register_token(regex(r"bit-and"), move |_| { bit_and_op.clone() });
+    let bit_or_op = Atom::gnd(BitOrOp{});
+    tref.register_token(regex(r"bit-or"), move |_| { bit_or_op.clone() });
+    let bit_xor_op = Atom::gnd(BitXorOp{});
+    tref.register_token(regex(r"bit-xor"), move |_| { bit_xor_op.clone() });
+    let bit_not_op = Atom::gnd(BitNotOp{});
+    tref.register_token(regex(r"bit-not"), move |_| { bit_not_op.clone() });
+    let shl_op = Atom::gnd(ShlOp{});
+    tref.register_token(regex(r"bit-shl"), move |_| { shl_op.clone() });
+    let shr_op = Atom::gnd(ShrOp{});
+    tref.register_token(regex(r"bit-shr"), move |_| { shr_op.clone() });
+    let to_int_op = Atom::gnd(ToIntOp{});
+    tref.register_token(regex(r"to-int"), move |_| { to_int_op.clone() });
+    let to_float_op = Atom::gnd(ToFloatOp{});
+    tref.register_token(regex(r"to-float"), move |_| { to_float_op.clone() });
     let lt_op = Atom::gnd(LessOp{});
     tref.register_token(regex(r"<"), move |_| { lt_op.clone() });
     let gt_op = Atom::gnd(GreaterOp{});
@@ -1357,6 +2266,29 @@ mod tests {
         assert_eq_no_order!(space_atoms, vec![expr!(("bar" "foo"))]);
     }
 
+    #[test]
+    fn add_atom_unique_op() {
+        let space = DynSpace::new(metta_space("(= (foo $x) $x)"));
+        let satom = Atom::gnd(space.clone());
+        let res = AddAtomUniqueOp{}.execute(&mut vec![satom.clone(), expr!("=" ("foo" y) y)]).expect("No result returned");
+        assert_eq!(res, vec![Atom::gnd(Bool(false))]);
+        let res = AddAtomUniqueOp{}.execute(&mut vec![satom, expr!("=" ("foo" y) "y")]).expect("No result returned");
+        assert_eq!(res, vec![Atom::gnd(Bool(true))]);
+        assert_eq!(space.borrow().as_space().atom_count(), Some(2));
+    }
+
+    #[test]
+    fn atom_multiplicity_op() {
+        let space = DynSpace::new(GroundingSpace::new_set());
+        space.borrow_mut().add(expr!("foo" x));
+        space.borrow_mut().add(expr!("foo" y));
+        let satom = Atom::gnd(space.clone());
+        let res = AtomMultiplicityOp{}.execute(&mut vec![satom.clone(), expr!("foo" z)]).expect("No result returned");
+        assert_eq!(res, vec![Atom::gnd(Number::Integer(2))]);
+        let res = AtomMultiplicityOp{}.execute(&mut vec![satom, expr!("foo" "bar")]).expect("No result returned");
+        assert_eq!(res, vec![Atom::gnd(Number::Integer(0))]);
+    }
+
     #[test]
     fn get_atoms_op() {
         let space = DynSpace::new(metta_space("
@@ -1394,6 +2326,56 @@ mod tests {
         assert_eq!(res, vec![expr!(("A" "F") ("B" "C") "D")]);
     }
 
+    #[test]
+    fn list_ops() {
+        let list = expr!({Number::Integer(3)} "b" {Number::Integer(1)} "a" {Number::Integer(3)});
+        assert_eq!(LengthAtomOp{}.execute(&mut vec![list.clone()]), Ok(vec![expr!({Number::Integer(5)})]));
+        assert_eq!(LengthAtomOp{}.execute(&mut vec![expr!()]), Ok(vec![expr!({Number::Integer(0)})]));
+        assert_eq!(ReverseAtomOp{}.execute(&mut vec![expr!("a" ("b" "c"))]), Ok(vec![expr!(("b" "c") "a")]));
+        assert_eq!(AppendAtomOp{}.execute(&mut vec![expr!("a" "b"), expr!(("c"))]), Ok(vec![expr!("a" "b" "c")]));
+        assert_eq!(NthAtomOp{}.execute(&mut vec![expr!("a" "b"), expr!({Number::Integer(1)})]), Ok(vec![expr!("b")]));
+        assert!(NthAtomOp{}.execute(&mut vec![expr!("a" "b"), expr!({Number::Integer(2)})]).is_err());
+        assert!(NthAtomOp{}.execute(&mut vec![expr!("a" "b"), expr!({Number::Integer(-1)})]).is_err());
+        assert_eq!(ZipAtomOp{}.execute(&mut vec![expr!("a" "b" "c"), expr!("x" "y")]), Ok(vec![expr!(("a" "x") ("b" "y"))]));
+        assert_eq!(SortAtomOp{}.execute(&mut vec![list.clone()]),
+            Ok(vec![expr!({Number::Integer(1)} {Number::Integer(3)} {Number::Integer(3)} "a" "b")]));
+        assert_eq!(UniqueAtomOp{}.execute(&mut vec![list]),
+            Ok(vec![expr!({Number::Integer(3)} "b" {Number::Integer(1)} "a")]));
+        assert!(LengthAtomOp{}.execute(&mut vec![expr!("a")]).is_err());
+    }
+
+    #[test]
+    fn higher_order_list_ops() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let parser = SExprParser::new("
+            (= (inc $x) (+ $x 1))
+            (= (big $x) (> $x 2))
+            (= (either $x) $x)
+            (= (either $x) (* $x 10))
+
+            !(map-fn (1 2 3) inc)
+            !(filter-fn (1 2 3 4 5) big)
+            !(foldl-fn (1 2 3) 0 +)
+            !(foldl-fn (1 2 3) 10 -)
+            !(map-fn () inc)
+            !(map-fn (1 2) either)
+        ");
+
+        let mut result = metta.run(parser).unwrap();
+        let alternatives = result.pop().unwrap();
+        assert_eq!(result, vec![
+            vec![expr!({Number::Integer(2)} {Number::Integer(3)} {Number::Integer(4)})],
+            vec![expr!({Number::Integer(3)} {Number::Integer(4)} {Number::Integer(5)})],
+            vec![expr!({Number::Integer(6)})],
+            vec![expr!({Number::Integer(4)})],
+            vec![expr!()],
+        ]);
+        assert_eq_no_order!(alternatives, vec![
+            expr!({Number::Integer(1)} {Number::Integer(2)}), expr!({Number::Integer(1)} {Number::Integer(20)}),
+            expr!({Number::Integer(10)} {Number::Integer(2)}), expr!({Number::Integer(10)} {Number::Integer(20)}),
+        ]);
+    }
+
     #[test]
     fn bind_new_space_op() {
         let tokenizer = Shared::new(Tokenizer::new());
@@ -1548,6 +2530,54 @@ mod tests {
             Ok(vec![vec![expr!("A"), expr!("B")]]));
     }
 
+    #[test]
+    fn once_limit_offset_ops_stop_on_infinite_branch() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("
+            (= (f $x) $x)
+            (= (f $x) (f (S $x)))
+            !(once (f Z))
+            !(limit 3 (f Z))
+            !(limit 2 (offset 1 (f Z)))
+            !(limit 0 (f Z))
+            !(once (superpose ()))
+        "));
+        assert_eq!(result, Ok(vec![
+            vec![expr!("Z")],
+            vec![expr!("Z"), expr!("S" "Z"), expr!("S" ("S" "Z"))],
+            vec![expr!("S" "Z"), expr!("S" ("S" "Z"))],
+            vec![],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn time_it_op() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let mut result = metta.run(SExprParser::new("
+            (= (color) red)
+            (= (color) green)
+            !(time-it (color))
+        ")).unwrap().pop().unwrap();
+        assert_eq!(result.len(), 1);
+        match result.pop().unwrap() {
+            Atom::Expression(expr) => {
+                assert_eq_no_order!(atom_as_expr(&expr.children()[0]).unwrap().children(), vec![expr!("red"), expr!("green")]);
+                assert!(expr.children()[1].as_gnd::<DurationAtom>().is_some_and(|d| d.0 >= chrono::TimeDelta::zero()));
+            },
+            atom => panic!("Expression is expected: {}", atom),
+        }
+    }
+
+    #[test]
+    fn limit_op_incorrect_arguments() {
+        let space = DynSpace::new(GroundingSpace::new());
+        let limit_op = LimitOp::new(space.clone());
+        assert!(limit_op.execute(&mut vec![expr!({Number::Integer(-1)}), expr!(("foo"))]).is_err());
+        assert!(limit_op.execute(&mut vec![expr!({Number::Float(1.5)}), expr!(("foo"))]).is_err());
+        assert!(OffsetOp::new(space).execute(&mut vec![expr!("foo")]).is_err());
+    }
+
     #[test]
     fn superpose_op_consumes_interpreter_errors() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
@@ -1595,6 +2625,41 @@ mod tests {
             Vec::<Atom>::new());
     }
 
+    #[test]
+    fn super_types_sub_types_lub_op() {
+        let space = DynSpace::new(metta_space("
+            (:< Cat Mammal)
+            (:< Dog Mammal)
+            (:< Mammal Animal)
+            (:< Frog Animal)
+        "));
+
+        let super_types_op = SuperTypesOp::new(space.clone());
+        assert_eq!(super_types_op.execute(&mut vec![sym!("Cat")]).unwrap(),
+            vec![sym!("Mammal"), sym!("Animal")]);
+        let sub_types_op = SubTypesOp::new(space.clone());
+        assert_eq_no_order!(sub_types_op.execute(&mut vec![sym!("Animal")]).unwrap(),
+            vec![sym!("Mammal"), sym!("Frog"), sym!("Cat"), sym!("Dog")]);
+        let lub_op = LubOp::new(space.clone());
+        assert_eq!(lub_op.execute(&mut vec![sym!("Cat"), sym!("Dog")]).unwrap(), vec![sym!("Mammal")]);
+        assert_eq!(lub_op.execute(&mut vec![sym!("Dog"), sym!("Frog")]).unwrap(), vec![sym!("Animal")]);
+        assert_eq!(lub_op.execute(&mut vec![sym!("Dog"), sym!("Stone")]).unwrap(), Vec::<Atom>::new());
+    }
+
+    #[test]
+    fn lint_op() {
+        let space = DynSpace::new(metta_space("
+            (: Nil (List $t))
+            (: Cons (-> $t (List $t) (List $t)))
+        "));
+
+        let lint_op = LintOp::new(space.clone());
+        assert_eq!(lint_op.execute(&mut vec![expr!("case" l ((("Cons" h t) h)))]).unwrap(),
+            vec![expr!((("NonExhaustiveCase" ("case" l ((("Cons" h t) h))) ("Nil"))))]);
+        assert_eq!(lint_op.execute(&mut vec![expr!("case" l (("Nil" "A") (("Cons" h t) h)))]).unwrap(),
+            vec![expr!()]);
+    }
+
     #[test]
     fn println_op() {
         assert_eq!(PrintlnOp{}.execute(&mut vec![sym!("A")]), unit_result());
@@ -1694,6 +2759,23 @@ mod tests {
         assert_eq!(run_program("!(if True ok nok)"), Ok(vec![vec![Atom::sym("ok")]]));
     }
 
+    #[test]
+    fn test_big_integer_and_rational_tokens() {
+        assert_eq!(run_program("!(+ 9223372036854775807 1)"),
+            Ok(vec![vec![expr!({Number::from_int_str("9223372036854775808")})]]));
+        assert_eq!(run_program("!(+ 1/3 1/6)"),
+            Ok(vec![vec![expr!({Number::from_rational_str("1/2")})]]));
+        assert_eq!(run_program("!(* 2/3 3/2)"), Ok(vec![vec![expr!({Number::Integer(1)})]]));
+    }
+
+    #[test]
+    fn test_math_functions() {
+        assert_eq!(run_program("!(pow-math 2 100)"),
+            Ok(vec![vec![expr!({Number::from_int_str("1267650600228229401496703205376")})]]));
+        assert_eq!(run_program("!(floor-math (sqrt-math 17))"), Ok(vec![vec![expr!({Number::Integer(4)})]]));
+        assert_eq!(run_program("!(to-float (int-div 7 2))"), Ok(vec![vec![expr!({Number::Float(3.0)})]]));
+    }
+
     #[test]
     fn test_let_op_inside_other_operation() {
         assert_eq!(run_program("!(and True (let $x False $x))"), Ok(vec![vec![expr!({Bool(false)})]]));
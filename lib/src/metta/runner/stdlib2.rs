@@ -13,6 +13,14 @@ use regex::Regex;
 use std::convert::TryInto;
 
 use super::arithmetics::*;
+use super::collections::*;
+use super::aggregate::*;
+use super::random::*;
+use super::system::*;
+use super::json::*;
+use super::regexp::*;
+use super::time::*;
+use super::data::*;
 
 pub const VOID_SYMBOL : Atom = sym!("%void%");
 
@@ -114,6 +122,37 @@ fn interpret(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String> {
     crate::metta::interpreter2::interpret(space, &expr)
 }
 
+// Unlike `interpret` alternatives of the expression are not collapsed to
+// filter out errors. Errors are filtered here while the interpreter returns
+// results one by one, thus interpretation stops as soon as `limit` results
+// are found.
+fn interpret_limited_no_error(space: DynSpace, expr: &Atom, limit: usize) -> Result<Vec<Atom>, String> {
+    let interpret = match expr {
+        Atom::Expression(_) => INTERPRET_EXPRESSION_SYMBOL,
+        _ => INTERPRET_SYMBOL,
+    };
+    let wrapped = Atom::expr([EVAL_SYMBOL, Atom::expr([interpret, expr.clone(), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())])]);
+    let mut state = crate::metta::interpreter2::interpret_init(space, &wrapped);
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    while state.has_next() && results.len() < limit {
+        state = crate::metta::interpreter2::interpret_step(state);
+        for atom in state.take_results() {
+            if atom_is_error(&atom) {
+                errors.push(atom);
+            } else {
+                results.push(atom);
+            }
+        }
+    }
+    if results.is_empty() {
+        results = errors;
+    }
+    results.truncate(limit);
+    log::debug!("interpret_limited_no_error: interpretation expr: {}, limit: {}, result {:?}", expr, limit, results);
+    Ok(results)
+}
+
 fn assert_results_equal(actual: &Vec<Atom>, expected: &Vec<Atom>, atom: &Atom) -> Result<Vec<Atom>, ExecError> {
     log::debug!("assert_results_equal: actual: {:?}, expected: {:?}, actual atom: {:?}", actual, expected, atom);
     let report = format!("\nExpected: {:?}\nGot: {:?}", expected, actual);
@@ -286,6 +325,291 @@ impl Grounded for CollapseOp {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct MapFnOp {
+    space: DynSpace,
+}
+
+impl MapFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for MapFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map-fn")
+    }
+}
+
+impl Grounded for MapFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "map-fn expects expression and function as arguments";
+        let list = stdlib::list_arg(args, 0, usage)?;
+        let func = args.get(1).ok_or(usage)?;
+        stdlib::map_list(list, func, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FilterFnOp {
+    space: DynSpace,
+}
+
+impl FilterFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for FilterFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "filter-fn")
+    }
+}
+
+impl Grounded for FilterFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "filter-fn expects expression and predicate as arguments";
+        let list = stdlib::list_arg(args, 0, usage)?;
+        let pred = args.get(1).ok_or(usage)?;
+        stdlib::filter_list(list, pred, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FoldlFnOp {
+    space: DynSpace,
+}
+
+impl FoldlFnOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for FoldlFnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "foldl-fn")
+    }
+}
+
+impl Grounded for FoldlFnOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, expr!(a), ATOM_TYPE_ATOM, expr!(a)])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "foldl-fn expects expression, initial value and function as arguments";
+        let list = stdlib::list_arg(args, 0, usage)?;
+        let init = args.get(1).ok_or(usage)?;
+        let func = args.get(2).ok_or(usage)?;
+        stdlib::foldl_list(list, init, func, |atom| interpret_no_error(self.space.clone(), atom))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OnceOp {
+    space: DynSpace,
+}
+
+impl OnceOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for OnceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "once")
+    }
+}
+
+impl Grounded for OnceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("once expects single executable atom as an argument")?;
+        Ok(interpret_limited_no_error(self.space.clone(), atom, 1)?)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LimitOp {
+    space: DynSpace,
+}
+
+impl LimitOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for LimitOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "limit")
+    }
+}
+
+impl Grounded for LimitOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "limit expects non-negative integer and executable atom as arguments";
+        let limit = args.first().and_then(stdlib::atom_as_count).ok_or(usage)?;
+        let atom = args.get(1).ok_or(usage)?;
+        // (limit N (offset M expr)) needs only N + M first results of expr,
+        // offset is recognized only when it is written inside limit explicitly
+        match <&ExpressionAtom>::try_from(atom).map(|expr| expr.children().as_slice()) {
+            Ok([op, offset, atom]) if op.as_gnd::<OffsetOp>().is_some() => {
+                let offset = stdlib::atom_as_count(offset).ok_or(OFFSET_USAGE)?;
+                let results = interpret_limited_no_error(self.space.clone(), atom, limit.saturating_add(offset))?;
+                Ok(results.into_iter().skip(offset).collect())
+            },
+            _ => Ok(interpret_limited_no_error(self.space.clone(), atom, limit)?),
+        }
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+const OFFSET_USAGE: &str = "offset expects non-negative integer and executable atom as arguments";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct OffsetOp {
+    space: DynSpace,
+}
+
+impl OffsetOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for OffsetOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "offset")
+    }
+}
+
+impl Grounded for OffsetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let offset = args.first().and_then(stdlib::atom_as_count).ok_or(OFFSET_USAGE)?;
+        let atom = args.get(1).ok_or(OFFSET_USAGE)?;
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        Ok(results.into_iter().skip(offset).collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RandomChoiceOp {
+    space: DynSpace,
+    random: RandomSource,
+}
+
+impl RandomChoiceOp {
+    pub fn new(space: DynSpace, random: RandomSource) -> Self {
+        Self{ space, random }
+    }
+}
+
+impl Display for RandomChoiceOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "random-choice")
+    }
+}
+
+impl Grounded for RandomChoiceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("random-choice expects single executable atom as an argument")?;
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        Ok(self.random.choose(results))
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+/// Evaluates the argument and returns the results together with the time
+/// spent on evaluation: `((<result>...) <duration>)`
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeItOp {
+    space: DynSpace,
+}
+
+impl TimeItOp {
+    pub fn new(space: DynSpace) -> Self {
+        Self{ space }
+    }
+}
+
+impl Display for TimeItOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time-it")
+    }
+}
+
+impl Grounded for TimeItOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let atom = args.first().ok_or("time-it expects single executable atom as an argument")?;
+        let start = std::time::Instant::now();
+        let results = interpret_no_error(self.space.clone(), atom)?;
+        let elapsed = chrono::TimeDelta::from_std(start.elapsed()).map_err(|e| e.to_string())?;
+        Ok(vec![Atom::expr([Atom::expr(results), Atom::gnd(DurationAtom(elapsed))])])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct CaseOp {
     space: DynSpace,
@@ -359,16 +683,30 @@ pub fn register_common_tokens(metta: &Metta) {
 
     let get_type_op = Atom::gnd(GetTypeOp::new(space.clone()));
     tref.register_token(regex(r"get-type"), move |_| { get_type_op.clone() });
+    let super_types_op = Atom::gnd(stdlib::SuperTypesOp::new(space.clone()));
+    tref.register_token(regex(r"supertypes"), move |_| { super_types_op.clone() });
+    let sub_types_op = Atom::gnd(stdlib::SubTypesOp::new(space.clone()));
+    tref.register_token(regex(r"subtypes"), move |_| { sub_types_op.clone() });
+    let lub_op = Atom::gnd(stdlib::LubOp::new(space.clone()));
+    tref.register_token(regex(r"lub"), move |_| { lub_op.clone() });
+    let lint_op = Atom::gnd(stdlib::LintOp::new(space.clone()));
+    tref.register_token(regex(r"lint"), move |_| { lint_op.clone() });
     let get_meta_type_op = Atom::gnd(stdlib::GetMetaTypeOp{});
     tref.register_token(regex(r"get-metatype"), move |_| { get_meta_type_op.clone() });
     let is_equivalent = Atom::gnd(IfEqualOp{});
     tref.register_token(regex(r"if-equal"), move |_| { is_equivalent.clone() });
     let new_space_op = Atom::gnd(stdlib::NewSpaceOp{});
     tref.register_token(regex(r"new-space"), move |_| { new_space_op.clone() });
+    let new_set_space_op = Atom::gnd(stdlib::NewSetSpaceOp{});
+    tref.register_token(regex(r"new-set-space"), move |_| { new_set_space_op.clone() });
     let add_atom_op = Atom::gnd(stdlib::AddAtomOp{});
     tref.register_token(regex(r"add-atom"), move |_| { add_atom_op.clone() });
+    let add_atom_unique_op = Atom::gnd(stdlib::AddAtomUniqueOp{});
+    tref.register_token(regex(r"add-atom-unique"), move |_| { add_atom_unique_op.clone() });
     let remove_atom_op = Atom::gnd(stdlib::RemoveAtomOp{});
     tref.register_token(regex(r"remove-atom"), move |_| { remove_atom_op.clone() });
+    let atom_multiplicity_op = Atom::gnd(stdlib::AtomMultiplicityOp{});
+    tref.register_token(regex(r"atom-multiplicity"), move |_| { atom_multiplicity_op.clone() });
     let get_atoms_op = Atom::gnd(stdlib::GetAtomsOp{});
     tref.register_token(regex(r"get-atoms"), move |_| { get_atoms_op.clone() });
     let new_state_op = Atom::gnd(stdlib::NewStateOp{});
@@ -379,6 +717,110 @@ pub fn register_common_tokens(metta: &Metta) {
     tref.register_token(regex(r"get-state"), move |_| { get_state_op.clone() });
     let nop_op = Atom::gnd(stdlib::NopOp{});
     tref.register_token(regex(r"nop"), move |_| { nop_op.clone() });
+    let length_atom_op = Atom::gnd(stdlib::LengthAtomOp{});
+    tref.register_token(regex(r"length-atom"), move |_| { length_atom_op.clone() });
+    let reverse_atom_op = Atom::gnd(stdlib::ReverseAtomOp{});
+    tref.register_token(regex(r"reverse-atom"), move |_| { reverse_atom_op.clone() });
+    let append_atom_op = Atom::gnd(stdlib::AppendAtomOp{});
+    tref.register_token(regex(r"append-atom"), move |_| { append_atom_op.clone() });
+    let nth_atom_op = Atom::gnd(stdlib::NthAtomOp{});
+    tref.register_token(regex(r"nth-atom"), move |_| { nth_atom_op.clone() });
+    let zip_atom_op = Atom::gnd(stdlib::ZipAtomOp{});
+    tref.register_token(regex(r"zip-atom"), move |_| { zip_atom_op.clone() });
+    let sort_atom_op = Atom::gnd(stdlib::SortAtomOp{});
+    tref.register_token(regex(r"sort-atom"), move |_| { sort_atom_op.clone() });
+    let unique_atom_op = Atom::gnd(stdlib::UniqueAtomOp{});
+    tref.register_token(regex(r"unique-atom"), move |_| { unique_atom_op.clone() });
+    let new_map_op = Atom::gnd(NewMapOp{});
+    tref.register_token(regex(r"new-map"), move |_| { new_map_op.clone() });
+    let map_insert_op = Atom::gnd(MapInsertOp{});
+    tref.register_token(regex(r"map-insert"), move |_| { map_insert_op.clone() });
+    let map_remove_op = Atom::gnd(MapRemoveOp{});
+    tref.register_token(regex(r"map-remove"), move |_| { map_remove_op.clone() });
+    let map_get_op = Atom::gnd(MapGetOp{});
+    tref.register_token(regex(r"map-get"), move |_| { map_get_op.clone() });
+    let map_contains_op = Atom::gnd(MapContainsOp{});
+    tref.register_token(regex(r"map-contains"), move |_| { map_contains_op.clone() });
+    let map_keys_op = Atom::gnd(MapKeysOp{});
+    tref.register_token(regex(r"map-keys"), move |_| { map_keys_op.clone() });
+    let map_values_op = Atom::gnd(MapValuesOp{});
+    tref.register_token(regex(r"map-values"), move |_| { map_values_op.clone() });
+    let map_size_op = Atom::gnd(MapSizeOp{});
+    tref.register_token(regex(r"map-size"), move |_| { map_size_op.clone() });
+    let new_set_op = Atom::gnd(NewSetOp{});
+    tref.register_token(regex(r"new-set"), move |_| { new_set_op.clone() });
+    let set_insert_op = Atom::gnd(SetInsertOp{});
+    tref.register_token(regex(r"set-insert"), move |_| { set_insert_op.clone() });
+    let set_remove_op = Atom::gnd(SetRemoveOp{});
+    tref.register_token(regex(r"set-remove"), move |_| { set_remove_op.clone() });
+    let set_contains_op = Atom::gnd(SetContainsOp{});
+    tref.register_token(regex(r"set-contains"), move |_| { set_contains_op.clone() });
+    let set_elements_op = Atom::gnd(SetElementsOp{});
+    tref.register_token(regex(r"set-elements"), move |_| { set_elements_op.clone() });
+    let set_size_op = Atom::gnd(SetSizeOp{});
+    tref.register_token(regex(r"set-size"), move |_| { set_size_op.clone() });
+    let count_matches_op = Atom::gnd(CountMatchesOp{});
+    tref.register_token(regex(r"count-matches"), move |_| { count_matches_op.clone() });
+    let sum_matches_op = Atom::gnd(SumMatchesOp{});
+    tref.register_token(regex(r"sum"), move |_| { sum_matches_op.clone() });
+    let min_by_op = Atom::gnd(MinByOp{});
+    tref.register_token(regex(r"min-by"), move |_| { min_by_op.clone() });
+    let group_by_op = Atom::gnd(GroupByOp{});
+    tref.register_token(regex(r"group-by"), move |_| { group_by_op.clone() });
+    let random = metta.random().clone();
+    let random_int_op = Atom::gnd(RandomIntOp::new(random.clone()));
+    tref.register_token(regex(r"random-int"), move |_| { random_int_op.clone() });
+    let random_float_op = Atom::gnd(RandomFloatOp::new(random.clone()));
+    tref.register_token(regex(r"random-float"), move |_| { random_float_op.clone() });
+    let shuffle_op = Atom::gnd(ShuffleOp::new(random.clone()));
+    tref.register_token(regex(r"shuffle"), move |_| { shuffle_op.clone() });
+    let sample_weighted_op = Atom::gnd(SampleWeightedOp::new(random));
+    tref.register_token(regex(r"sample-weighted"), move |_| { sample_weighted_op.clone() });
+    let sandbox = FileSandbox::new(metta.environment());
+    let read_file_op = Atom::gnd(ReadFileOp::new(sandbox.clone()));
+    tref.register_token(regex(r"read-file"), move |_| { read_file_op.clone() });
+    let write_file_op = Atom::gnd(WriteFileOp::new(sandbox.clone()));
+    tref.register_token(regex(r"write-file"), move |_| { write_file_op.clone() });
+    let list_dir_op = Atom::gnd(ListDirOp::new(sandbox.clone()));
+    tref.register_token(regex(r"list-dir"), move |_| { list_dir_op.clone() });
+    let file_exists_op = Atom::gnd(FileExistsOp::new(sandbox));
+    tref.register_token(regex(r"file-exists"), move |_| { file_exists_op.clone() });
+    let get_env_op = Atom::gnd(GetEnvOp::new(metta.environment()));
+    tref.register_token(regex(r"get-env"), move |_| { get_env_op.clone() });
+    let json_parse_op = Atom::gnd(JsonParseOp{});
+    tref.register_token(regex(r"json-parse"), move |_| { json_parse_op.clone() });
+    let json_dump_op = Atom::gnd(JsonDumpOp{});
+    tref.register_token(regex(r"json-dump"), move |_| { json_dump_op.clone() });
+    let regex_op = Atom::gnd(RegexOp{});
+    tref.register_token(regex(r"regex"), move |_| { regex_op.clone() });
+    let regex_match_op = Atom::gnd(RegexMatchOp{});
+    tref.register_token(regex(r"regex-match"), move |_| { regex_match_op.clone() });
+    let regex_captures_op = Atom::gnd(RegexCapturesOp{});
+    tref.register_token(regex(r"regex-captures"), move |_| { regex_captures_op.clone() });
+    let regex_replace_op = Atom::gnd(RegexReplaceOp{});
+    tref.register_token(regex(r"regex-replace"), move |_| { regex_replace_op.clone() });
+    let now_op = Atom::gnd(NowOp{});
+    tref.register_token(regex(r"now"), move |_| { now_op.clone() });
+    let monotonic_ms_op = Atom::gnd(MonotonicMsOp{});
+    tref.register_token(regex(r"monotonic-ms"), move |_| { monotonic_ms_op.clone() });
+    let time_op = Atom::gnd(TimeOp{});
+    tref.register_token(regex(r"time"), move |_| { time_op.clone() });
+    let time_format_op = Atom::gnd(TimeFormatOp{});
+    tref.register_token(regex(r"time-format"), move |_| { time_format_op.clone() });
+    let duration_ms_op = Atom::gnd(DurationMsOp{});
+    tref.register_token(regex(r"duration-ms"), move |_| { duration_ms_op.clone() });
+    let duration_to_ms_op = Atom::gnd(DurationToMsOp{});
+    tref.register_token(regex(r"duration-to-ms"), move |_| { duration_to_ms_op.clone() });
+    let time_add_op = Atom::gnd(TimeAddOp{});
+    tref.register_token(regex(r"time-add"), move |_| { time_add_op.clone() });
+    let time_sub_op = Atom::gnd(TimeSubOp{});
+    tref.register_token(regex(r"time-sub"), move |_| { time_sub_op.clone() });
+    let time_lt_op = Atom::gnd(TimeLessOp{});
+    tref.register_token(regex(r"time-lt"), move |_| { time_lt_op.clone() });
+    let time_gt_op = Atom::gnd(TimeGreaterOp{});
+    tref.register_token(regex(r"time-gt"), move |_| { time_gt_op.clone() });
+    let time_eq_op = Atom::gnd(TimeEqOp{});
+    tref.register_token(regex(r"time-eq"), move |_| { time_eq_op.clone() });
 }
 
 pub fn register_runner_tokens(metta: &Metta) {
@@ -395,9 +837,27 @@ pub fn register_runner_tokens(metta: &Metta) {
     tref.register_token(regex(r"superpose"), move |_| { superpose_op.clone() });
     let collapse_op = Atom::gnd(CollapseOp::new(space.clone()));
     tref.register_token(regex(r"collapse"), move |_| { collapse_op.clone() });
+    let map_fn_op = Atom::gnd(MapFnOp::new(space.clone()));
+    tref.register_token(regex(r"map-fn"), move |_| { map_fn_op.clone() });
+    let filter_fn_op = Atom::gnd(FilterFnOp::new(space.clone()));
+    tref.register_token(regex(r"filter-fn"), move |_| { filter_fn_op.clone() });
+    let foldl_fn_op = Atom::gnd(FoldlFnOp::new(space.clone()));
+    tref.register_token(regex(r"foldl-fn"), move |_| { foldl_fn_op.clone() });
     let case_op = Atom::gnd(CaseOp::new(space.clone()));
     tref.register_token(regex(r"case"), move |_| { case_op.clone() });
-    let pragma_op = Atom::gnd(stdlib::PragmaOp::new(metta.settings().clone()));
+    let declare_data_op = Atom::gnd(DeclareDataOp::new(space.clone(), tokenizer.clone()));
+    tref.register_token(regex(r"declare-data"), move |_| { declare_data_op.clone() });
+    let once_op = Atom::gnd(OnceOp::new(space.clone()));
+    tref.register_token(regex(r"once"), move |_| { once_op.clone() });
+    let limit_op = Atom::gnd(LimitOp::new(space.clone()));
+    tref.register_token(regex(r"limit"), move |_| { limit_op.clone() });
+    let offset_op = Atom::gnd(OffsetOp::new(space.clone()));
+    tref.register_token(regex(r"offset"), move |_| { offset_op.clone() });
+    let random_choice_op = Atom::gnd(RandomChoiceOp::new(space.clone(), metta.random().clone()));
+    tref.register_token(regex(r"random-choice"), move |_| { random_choice_op.clone() });
+    let time_it_op = Atom::gnd(TimeItOp::new(space.clone()));
+    tref.register_token(regex(r"time-it"), move |_| { time_it_op.clone() });
+    let pragma_op = Atom::gnd(stdlib::PragmaOp::new(metta.settings().clone(), metta.random().clone()));
     tref.register_token(regex(r"pragma!"), move |_| { pragma_op.clone() });
     let import_op = Atom::gnd(stdlib::ImportOp::new(metta.clone()));
     tref.register_token(regex(r"import!"), move |_| { import_op.clone() });
@@ -424,6 +884,8 @@ pub fn register_rust_tokens(metta: &Metta) {
         |token| { Atom::gnd(Number::from_int_str(token)) });
     tref.register_token(regex(r"\d+(.\d+)([eE][\-\+]?\d+)?"),
         |token| { Atom::gnd(Number::from_float_str(token)) });
+    tref.register_token(regex(r"\d+/0*[1-9]\d*"),
+        |token| { Atom::gnd(Number::from_rational_str(token)) });
     tref.register_token(regex(r"True|False"),
         |token| { Atom::gnd(Bool::from_str(token)) });
     let sum_op = Atom::gnd(SumOp{});
@@ -436,6 +898,68 @@ pub fn register_rust_tokens(metta: &Metta) {
     tref.register_token(regex(r"/"), move |_| { div_op.clone() });
     let mod_op = Atom::gnd(ModOp{});
     tref.register_token(regex(r"%"), move |_| { mod_op.clone() });
+    let abs_op = Atom::gnd(AbsOp{});
+    tref.register_token(regex(r"abs-math"), move |_| { abs_op.clone() });
+    let min_op = Atom::gnd(MinOp{});
+    tref.register_token(regex(r"min-math"), move |_| { min_op.clone() });
+    let max_op = Atom::gnd(MaxOp{});
+    tref.register_token(regex(r"max-math"), move |_| { max_op.clone() });
+    let pow_op = Atom::gnd(PowOp{});
+    tref.register_token(regex(r"pow-math"), move |_| { pow_op.clone() });
+    let sqrt_op = Atom::gnd(SqrtOp{});
+    tref.register_token(regex(r"sqrt-math"), move |_| { sqrt_op.clone() });
+    let exp_op = Atom::gnd(ExpOp{});
+    tref.register_token(regex(r"exp-math"), move |_| { exp_op.clone() });
+    let log_op = Atom::gnd(LogOp{});
+    tref.register_token(regex(r"log-math"), move |_| { log_op.clone() });
+    let sin_op = Atom::gnd(SinOp{});
+    tref.register_token(regex(r"sin-math"), move |_| { sin_op.clone() });
+    let cos_op = Atom::gnd(CosOp{});
+    tref.register_token(regex(r"cos-math"), move |_| { cos_op.clone() });
+    let tan_op = Atom::gnd(TanOp{});
+    tref.register_token(regex(r"tan-math"), move |_| { tan_op.clone() });
+    let asin_op = Atom::gnd(AsinOp{});
+    tref.register_token(regex(r"asin-math"), move |_| { asin_op.clone() });
+    let acos_op = Atom::gnd(AcosOp{});
+    tref.register_token(regex(r"acos-math"), move |_| { acos_op.clone() });
+    let atan_op = Atom::gnd(AtanOp{});
+    tref.register_token(regex(r"atan-math"), move |_| { atan_op.clone() });
+    let atan2_op = Atom::gnd(Atan2Op{});
+    tref.register_token(regex(r"atan2-math"), move |_| { atan2_op.clone() });
+    let floor_op = Atom::gnd(FloorOp{});
+    tref.register_token(regex(r"floor-math"), move |_| { floor_op.clone() });
+    let ceil_op = Atom::gnd(CeilOp{});
+    tref.register_token(regex(r"ceil-math"), move |_| { ceil_op.clone() });
+    let round_op = Atom::gnd(RoundOp{});
+    tref.register_token(regex(r"round-math"), move |_| { round_op.clone() });
+    let trunc_op = Atom::gnd(TruncOp{});
+    tref.register_token(regex(r"trunc-math"), move |_| { trunc_op.clone() });
+    let int_div_op = Atom::gnd(IntDivOp{});
+    tref.register_token(regex(r"int-div"), move |_| { int_div_op.clone() });
+    let bit_and_op = Atom::gnd(BitAndOp{});
+    tref.register_token(regex(r"bit-and"), move |_| { bit_and_op.clone() });
+    let bit_or_op = Atom::gnd(BitOrOp{});
+    tref.register_token(regex(r"bit-or"), move |_| { bit_or_op.clone() });
+    let bit_xor_op = Atom::gnd(BitXorOp{});
+    tref.register_token(regex(r"bit-xor"), move |_| { bit_xor_op.clone() });
+    let bit_not_op = Atom::gnd(BitNotOp{});
+    tref.register_token(regex(r"bit-not"), move |_| { bit_not_op.clone() });
+    let shl_op = Atom::gnd(ShlOp{});
+    tref.register_token(regex(r"bit-shl"), move |_| { shl_op.clone() });
+    let shr_op = Atom::gnd(ShrOp{});
+    tref.register_token(regex(r"bit-shr"), move |_| { shr_op.clone() });
+    let to_int_op = Atom::gnd(ToIntOp{});
+    tref.register_token(regex(r"to-int"), move |_| { to_int_op.clone() });
+    let to_float_op = Atom::gnd(ToFloatOp{});
+    tref.register_token(regex(r"to-float"), move |_| { to_float_op.clone() });
+    let lt_op = Atom::gnd(LessOp{});
+    tref.register_token(regex(r"<"), move |_| { lt_op.clone() });
+    let gt_op = Atom::gnd(GreaterOp{});
+    tref.register_token(regex(r">"), move |_| { gt_op.clone() });
+    let le_op = Atom::gnd(LessEqOp{});
+    tref.register_token(regex(r"<="), move |_| { le_op.clone() });
+    let ge_op = Atom::gnd(GreaterEqOp{});
+    tref.register_token(regex(r">="), move |_| { ge_op.clone() });
     let eq_op = Atom::gnd(stdlib::EqualOp{});
     tref.register_token(regex(r"=="), move |_| { eq_op.clone() });
 
@@ -460,6 +984,23 @@ mod tests {
         metta.run(SExprParser::new(program))
     }
 
+    #[test]
+    fn metta_lint() {
+        let program = "
+            (: Red Color)
+            (: Green Color)
+            (: Blue Color)
+            !(chain (eval (lint (case $c ((Red 0) (Green 1))))) $warnings
+                (unify $warnings (($kind $case $missing)) ($kind $missing) Empty))
+            !(lint (case $c ((Red 0) (Green 1) (Blue 2))))
+        ";
+
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("NonExhaustiveCase" ("Blue"))],
+            vec![expr!()],
+        ]));
+    }
+
     #[test]
     fn get_type_op() {
         let space = DynSpace::new(metta_space("
@@ -561,6 +1102,24 @@ mod tests {
         assert_eq!(run_program("!(eval (filter-atom (a (Error (b) \"Test error\") $c) $x (eval (if-error $x False True))))"), Ok(vec![vec![expr!("a" c)]]));
     }
 
+    #[test]
+    fn metta_catch_throw() {
+        let program = "
+            (= (safe-div $a $b) (function (eval (if-equal $b 0
+              (throw (Error (safe-div $a $b) \"Division by zero\"))
+              (return (/ $a $b)) ))))
+
+            !(catch (eval (safe-div 4 0)) (Error $e $msg) (recovered $msg))
+            !(catch (eval (safe-div 4 2)) (Error $e $msg) (recovered $msg))
+            !(eval (safe-div 4 0))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("recovered" "\"Division by zero\"")],
+            vec![Atom::gnd(Number::Integer(2))],
+            vec![expr!("Error" ("safe-div" {Number::Integer(4)} {Number::Integer(0)}) "\"Division by zero\"")],
+        ]));
+    }
+
     #[test]
     fn metta_map_atom() {
         assert_eq!(run_program("!(eval (map-atom () $x ($x mapped)))"), Ok(vec![vec![expr!()]]));
@@ -573,6 +1132,23 @@ mod tests {
         assert_eq!(run_program("!(eval (foldl-atom (1 2 3) 0 $a $b (eval (+ $a $b))))"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
     }
 
+    #[test]
+    fn metta_map_filter_foldl_fn() {
+        let program = "
+            (= (inc $x) (+ $x 1))
+            (= (big $x) (> $x 2))
+
+            !(map-fn (1 2 3) inc)
+            !(filter-fn (1 2 3 4 5) big)
+            !(foldl-fn (1 2 3) 0 +)
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(2)} {Number::Integer(3)} {Number::Integer(4)})],
+            vec![expr!({Number::Integer(3)} {Number::Integer(4)} {Number::Integer(5)})],
+            vec![expr!({Number::Integer(6)})],
+        ]));
+    }
+
     #[test]
     fn metta_interpret_single_atom_as_atom() {
         let result = run_program("!(eval (interpret A Atom &self))");
@@ -724,6 +1300,29 @@ mod tests {
             Ok(vec![vec![expr!("FOO"), expr!("BAR"), expr!("BAZ")]]));
     }
 
+    #[test]
+    fn metta_once_limit_offset_stop_on_first_results() {
+        let program = "
+            (= (d) 0)
+            (= (d) 1)
+            (= (f $x) $x)
+            (= (f $x) (f (S $x)))
+            !(once ((d) (d) (d) (d) (d) (d) (d) (d) (d)))
+            !(limit 1 (offset 3 ((d) (d) (d) (d) (d) (d) (d) (d) (d))))
+            !(once (f Z))
+            !(limit 0 (f Z))
+            !(once (superpose ()))
+        ";
+        let result = run_program(program).expect("Successful result is expected");
+        assert_eq!(result.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 1, 1, 0, 0]);
+        let digits = [expr!({Number::Integer(0)}), expr!({Number::Integer(1)})];
+        for tuple in &result[0..2] {
+            let tuple = <&ExpressionAtom>::try_from(&tuple[0]).expect("Expression atom is expected");
+            assert!(tuple.children().iter().all(|atom| digits.contains(atom)), "Unexpected result: {}", tuple);
+        }
+        assert_eq!(result[2], vec![expr!("Z")]);
+    }
+
     #[test]
     fn metta_collapse() {
         let program = "
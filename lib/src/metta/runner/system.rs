@@ -0,0 +1,377 @@
+//! Operations which give MeTTa code access to the host file system and
+//! environment variables. File system access is restricted by the
+//! [FileSandbox]: only paths inside [Environment::allowed_paths] can be read
+//! or written. Only environment variables listed in
+//! [Environment::allowed_env_vars] can be read.
+
+use crate::*;
+use crate::matcher::MatchResultIter;
+use crate::metta::*;
+use crate::metta::runner::Environment;
+use crate::metta::runner::arithmetics::{Bool, ATOM_TYPE_BOOL};
+use crate::metta::runner::stdlib::{atom_as_str, str_atom, unit_result};
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Checks that paths passed by MeTTa code are inside of the allowed
+/// directories. Relative paths are resolved against the environment's
+/// working_dir or against the process working directory when the former is
+/// not set. When no allowed directories are configured in the environment
+/// the directory which relative paths are resolved against is allowed. Paths
+/// are canonicalized before checking, thus neither `..` nor symbolic links
+/// allow escaping the allowed directories.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileSandbox {
+    allowed_paths: Vec<PathBuf>,
+    working_dir: Option<PathBuf>,
+}
+
+impl FileSandbox {
+    pub fn new(env: &Environment) -> Self {
+        let mut allowed_paths: Vec<PathBuf> = env.allowed_paths()
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        if env.allowed_paths().next().is_none() {
+            allowed_paths.extend(std::env::current_dir().and_then(|dir| dir.canonicalize()).ok());
+        }
+        let working_dir = env.working_dir().map(|path| path.into());
+        Self{ allowed_paths, working_dir }
+    }
+
+    fn resolve(&self, path: &Atom, usage: &'static str) -> Result<PathBuf, ExecError> {
+        let path = Path::new(atom_as_str(path).ok_or(usage)?);
+        let path = match &self.working_dir {
+            Some(working_dir) => working_dir.join(path),
+            None => std::env::current_dir().map_err(|e| e.to_string())?.join(path),
+        };
+        match canonicalize(&path) {
+            Some(canonical) if self.allowed_paths.iter().any(|allowed| canonical.starts_with(allowed)) => Ok(canonical),
+            _ => Err(format!("Access to {} is not allowed", path.display()).into()),
+        }
+    }
+}
+
+/// Canonicalizes the longest existing prefix of the `path` and appends the
+/// rest of the path to it. Returns `None` when non-existing part of the path
+/// contains `..` components or dangling symbolic links, which would be
+/// followed by the file operations.
+fn canonicalize(path: &Path) -> Option<PathBuf> {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        match existing.canonicalize() {
+            Ok(canonical) => return Some(missing.into_iter().rev().fold(canonical, |path, name| path.join(name))),
+            Err(_) => {
+                if existing.symlink_metadata().is_ok() {
+                    return None;
+                }
+                missing.push(existing.file_name()?);
+                existing = existing.parent()?;
+            },
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReadFileOp {
+    sandbox: FileSandbox,
+}
+
+impl ReadFileOp {
+    pub fn new(sandbox: FileSandbox) -> Self {
+        Self{ sandbox }
+    }
+}
+
+impl Display for ReadFileOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "read-file")
+    }
+}
+
+impl Grounded for ReadFileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_SYMBOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "read-file expects file path as an argument";
+        let path = self.sandbox.resolve(args.first().ok_or(usage)?, usage)?;
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read file {}: {}", path.display(), e))?;
+        Ok(vec![str_atom(&text)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct WriteFileOp {
+    sandbox: FileSandbox,
+}
+
+impl WriteFileOp {
+    pub fn new(sandbox: FileSandbox) -> Self {
+        Self{ sandbox }
+    }
+}
+
+impl Display for WriteFileOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "write-file")
+    }
+}
+
+impl Grounded for WriteFileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_UNDEFINED, UNIT_TYPE()])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "write-file expects file path and text as arguments";
+        let path = self.sandbox.resolve(args.first().ok_or(usage)?, usage)?;
+        // Atoms which are not string literals are written using their text representation
+        let text = args.get(1).ok_or(usage)?;
+        let text = atom_as_str(text).map_or_else(|| text.to_string(), str::to_string);
+        std::fs::write(&path, text)
+            .map_err(|e| format!("Cannot write file {}: {}", path.display(), e))?;
+        unit_result()
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ListDirOp {
+    sandbox: FileSandbox,
+}
+
+impl ListDirOp {
+    pub fn new(sandbox: FileSandbox) -> Self {
+        Self{ sandbox }
+    }
+}
+
+impl Display for ListDirOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "list-dir")
+    }
+}
+
+impl Grounded for ListDirOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "list-dir expects directory path as an argument";
+        let path = self.sandbox.resolve(args.first().ok_or(usage)?, usage)?;
+        let list_error = |e: std::io::Error| format!("Cannot list directory {}: {}", path.display(), e);
+        let mut names = std::fs::read_dir(&path).map_err(list_error)?
+            .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<Vec<_>, _>>().map_err(list_error)?;
+        names.sort();
+        Ok(vec![Atom::expr(names.iter().map(|name| str_atom(name)).collect::<Vec<_>>())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileExistsOp {
+    sandbox: FileSandbox,
+}
+
+impl FileExistsOp {
+    pub fn new(sandbox: FileSandbox) -> Self {
+        Self{ sandbox }
+    }
+}
+
+impl Display for FileExistsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "file-exists")
+    }
+}
+
+impl Grounded for FileExistsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_BOOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "file-exists expects file path as an argument";
+        let path = self.sandbox.resolve(args.first().ok_or(usage)?, usage)?;
+        Ok(vec![Atom::gnd(Bool(path.exists()))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GetEnvOp {
+    allowed_vars: Vec<String>,
+}
+
+impl GetEnvOp {
+    pub fn new(env: &Environment) -> Self {
+        Self{ allowed_vars: env.allowed_env_vars().map(str::to_string).collect() }
+    }
+}
+
+impl Display for GetEnvOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "get-env")
+    }
+}
+
+impl Grounded for GetEnvOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_SYMBOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let name = args.first().and_then(atom_as_str).ok_or("get-env expects variable name as an argument")?;
+        if !self.allowed_vars.iter().any(|allowed| allowed == name) {
+            return Err(format!("Access to environment variable {} is not allowed", name).into());
+        }
+        Ok(std::env::var(name).ok().map(|value| str_atom(&value)).into_iter().collect())
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("metta-system-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(path.join("data")).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn sandbox(dir: &TempDir) -> FileSandbox {
+        FileSandbox::new(&EnvBuilder::test_env().set_working_dir(Some(&dir.0.join("data"))).build())
+    }
+
+    #[test]
+    fn file_ops_inside_sandbox() {
+        let dir = TempDir::new("inside");
+        let sandbox = sandbox(&dir);
+
+        assert_eq!(WriteFileOp::new(sandbox.clone()).execute(&[str_atom("a.txt"), str_atom("text")]), unit_result());
+        assert_eq!(ReadFileOp::new(sandbox.clone()).execute(&[str_atom("a.txt")]), Ok(vec![str_atom("text")]));
+        assert_eq!(FileExistsOp::new(sandbox.clone()).execute(&[str_atom("a.txt")]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(FileExistsOp::new(sandbox.clone()).execute(&[str_atom("b.txt")]), Ok(vec![Atom::gnd(Bool(false))]));
+        assert_eq!(FileExistsOp::new(sandbox.clone()).execute(&[str_atom("c/b.txt")]), Ok(vec![Atom::gnd(Bool(false))]));
+        assert_eq!(WriteFileOp::new(sandbox.clone()).execute(&[str_atom("b.txt"), expr!("x" "y")]), unit_result());
+        assert_eq!(ListDirOp::new(sandbox).execute(&[str_atom(".")]), Ok(vec![Atom::expr([str_atom("a.txt"), str_atom("b.txt")])]));
+    }
+
+    #[test]
+    fn file_ops_outside_sandbox_are_not_allowed() {
+        let dir = TempDir::new("outside");
+        std::fs::write(dir.0.join("secret.txt"), "secret").unwrap();
+        let sandbox = sandbox(&dir);
+        let secret = dir.0.join("secret.txt");
+
+        assert!(ReadFileOp::new(sandbox.clone()).execute(&[str_atom("../secret.txt")]).is_err());
+        assert!(ReadFileOp::new(sandbox.clone()).execute(&[str_atom(secret.to_str().unwrap())]).is_err());
+        assert!(WriteFileOp::new(sandbox.clone()).execute(&[str_atom("../new.txt"), str_atom("text")]).is_err());
+        assert!(FileExistsOp::new(sandbox.clone()).execute(&[str_atom("../secret.txt")]).is_err());
+        assert!(ListDirOp::new(sandbox).execute(&[str_atom("..")]).is_err());
+        assert!(!dir.0.join("new.txt").exists());
+    }
+
+    #[test]
+    fn allowed_paths_replace_working_dir() {
+        let dir = TempDir::new("allowed");
+        std::fs::write(dir.0.join("shared.txt"), "shared").unwrap();
+        let env = EnvBuilder::test_env()
+            .set_working_dir(Some(&dir.0.join("data")))
+            .add_allowed_paths([&dir.0])
+            .build();
+        let read = ReadFileOp::new(FileSandbox::new(&env));
+
+        assert_eq!(read.execute(&[str_atom("../shared.txt")]), Ok(vec![str_atom("shared")]));
+        assert!(ReadFileOp::new(FileSandbox::new(&EnvBuilder::test_env().build()))
+            .execute(&[str_atom(dir.0.join("shared.txt").to_str().unwrap())]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_symlink_does_not_escape_sandbox() {
+        let dir = TempDir::new("symlink");
+        std::os::unix::fs::symlink(dir.0.join("escaped.txt"), dir.0.join("data").join("link.txt")).unwrap();
+        let sandbox = sandbox(&dir);
+
+        assert!(WriteFileOp::new(sandbox.clone()).execute(&[str_atom("link.txt"), str_atom("text")]).is_err());
+        assert!(WriteFileOp::new(sandbox).execute(&[str_atom("link.txt/a.txt"), str_atom("text")]).is_err());
+        assert!(!dir.0.join("escaped.txt").exists());
+    }
+
+    #[test]
+    fn process_working_dir_is_allowed_by_default() {
+        let sandbox = FileSandbox::new(&EnvBuilder::test_env().build());
+        let exists = FileExistsOp::new(sandbox);
+
+        assert_eq!(exists.execute(&[str_atom("Cargo.toml")]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert!(exists.execute(&[str_atom("../Cargo.toml")]).is_err());
+    }
+
+    #[test]
+    fn get_env_op() {
+        std::env::set_var("METTA_SYSTEM_TEST_VAR", "value");
+        std::env::set_var("METTA_SYSTEM_TEST_SECRET_VAR", "secret");
+        let get_env = GetEnvOp::new(&EnvBuilder::test_env()
+            .add_allowed_env_vars(["METTA_SYSTEM_TEST_VAR", "METTA_SYSTEM_TEST_UNDEFINED_VAR"])
+            .build());
+
+        assert_eq!(get_env.execute(&[str_atom("METTA_SYSTEM_TEST_VAR")]), Ok(vec![str_atom("value")]));
+        assert_eq!(get_env.execute(&[str_atom("METTA_SYSTEM_TEST_UNDEFINED_VAR")]), Ok(vec![]));
+        assert!(get_env.execute(&[str_atom("METTA_SYSTEM_TEST_SECRET_VAR")]).is_err());
+    }
+
+    #[test]
+    fn file_ops_in_runner() {
+        let dir = TempDir::new("runner");
+        let metta = Metta::new(Some(EnvBuilder::test_env().set_working_dir(Some(&dir.0.join("data")))));
+        let result = metta.run(SExprParser::new("
+            !(write-file \"report.txt\" \"done\")
+            !(read-file \"report.txt\")
+            !(file-exists \"report.txt\")
+            !(list-dir \".\")
+        "));
+        assert_eq!(result, Ok(vec![
+            vec![UNIT_ATOM()],
+            vec![str_atom("done")],
+            vec![Atom::gnd(Bool(true))],
+            vec![Atom::expr([str_atom("report.txt")])],
+        ]));
+    }
+}
@@ -0,0 +1,476 @@
+//! Date, time and duration values and operations on them. [TimeAtom] is a
+//! point of time in UTC, [DurationAtom] is a signed time span. Both are
+//! printed as expressions which construct them back: `(time "<ISO-8601>")`
+//! and `(duration-ms <milliseconds>)`.
+
+use crate::*;
+use crate::matcher::MatchResultIter;
+use crate::metta::*;
+use crate::metta::runner::arithmetics::{Bool, Number, ATOM_TYPE_BOOL, ATOM_TYPE_NUMBER};
+use crate::metta::runner::stdlib::{atom_as_str, str_atom};
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::hash::Hasher;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeDelta, Utc};
+
+pub const ATOM_TYPE_TIME : Atom = sym!("Time");
+pub const ATOM_TYPE_DURATION : Atom = sym!("Duration");
+
+/// Point of time in UTC
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct TimeAtom(pub DateTime<Utc>);
+
+impl TimeAtom {
+    pub fn now() -> Self {
+        Self(SystemTime::now().into())
+    }
+
+    /// Parses ISO-8601 date and time. Time without offset is considered to
+    /// be in UTC, date without time means the midnight.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        DateTime::parse_from_rfc3339(text).map(|time| time.with_timezone(&Utc))
+            .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f").map(|time| time.and_utc()))
+            .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d").map(|date| date.and_time(Default::default()).and_utc()))
+            .map(Self)
+            .map_err(|_| format!("Incorrect ISO-8601 date and time: {}", text))
+    }
+
+    /// Returns ISO-8601 representation of the time
+    pub fn format(&self) -> String {
+        self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+impl Display for TimeAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(time {})", str_atom(&self.format()))
+    }
+}
+
+impl Grounded for TimeAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_TIME
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        state.write_i64(self.0.timestamp());
+        state.write_u32(self.0.timestamp_subsec_nanos());
+    }
+}
+
+/// Signed time span
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct DurationAtom(pub TimeDelta);
+
+impl DurationAtom {
+    pub fn from_ms(ms: &Number) -> Option<Self> {
+        match ms {
+            Number::Integer(ms) => TimeDelta::try_milliseconds(*ms),
+            _ => {
+                let nanos = ms.to_f64() * 1_000_000.0;
+                match nanos.is_finite() && nanos.abs() < i64::MAX as f64 {
+                    true => Some(TimeDelta::nanoseconds(nanos as i64)),
+                    false => None,
+                }
+            },
+        }.map(Self)
+    }
+
+    /// Returns the duration in milliseconds, fractional part is kept when
+    /// the duration is not a whole number of milliseconds
+    pub fn to_ms(&self) -> Number {
+        let ms = self.0.num_milliseconds();
+        match self.0.subsec_nanos() % 1_000_000 {
+            0 => Number::Integer(ms),
+            _ => Number::Float(self.0.num_nanoseconds().map_or(ms as f64, |nanos| nanos as f64 / 1_000_000.0)),
+        }
+    }
+}
+
+impl Display for DurationAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(duration-ms {})", self.to_ms())
+    }
+}
+
+impl Grounded for DurationAtom {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_DURATION
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+
+    fn hash_gnd(&self, state: &mut dyn Hasher) {
+        state.write_i64(self.0.num_seconds());
+        state.write_i32(self.0.subsec_nanos());
+    }
+}
+
+/// Time elapsed since an arbitrary point fixed at the first call
+pub fn monotonic_elapsed() -> TimeDelta {
+    static START: OnceLock<Instant> = OnceLock::new();
+    TimeDelta::from_std(START.get_or_init(Instant::now).elapsed()).unwrap_or(TimeDelta::MAX)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NowOp {}
+
+impl Display for NowOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "now")
+    }
+}
+
+impl Grounded for NowOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_TIME])
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        Ok(vec![Atom::gnd(TimeAtom::now())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MonotonicMsOp {}
+
+impl Display for MonotonicMsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "monotonic-ms")
+    }
+}
+
+impl Grounded for MonotonicMsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        Ok(vec![Atom::gnd(Number::Integer(monotonic_elapsed().num_milliseconds()))])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeOp {}
+
+impl Display for TimeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time")
+    }
+}
+
+impl Grounded for TimeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SYMBOL, ATOM_TYPE_TIME])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let text = args.first().and_then(atom_as_str).ok_or("time expects ISO-8601 string as an argument")?;
+        Ok(vec![Atom::gnd(TimeAtom::parse(text)?)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeFormatOp {}
+
+impl Display for TimeFormatOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time-format")
+    }
+}
+
+impl Grounded for TimeFormatOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_TIME, ATOM_TYPE_SYMBOL])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let time = args.first().and_then(Atom::as_gnd::<TimeAtom>).ok_or("time-format expects time as an argument")?;
+        Ok(vec![str_atom(&time.format())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DurationMsOp {}
+
+impl Display for DurationMsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "duration-ms")
+    }
+}
+
+impl Grounded for DurationMsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_DURATION])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "duration-ms expects number of milliseconds as an argument";
+        let ms = args.first().and_then(Atom::as_gnd::<Number>).ok_or(usage)?;
+        let duration = DurationAtom::from_ms(ms).ok_or_else(|| format!("Duration is out of range: {}", ms))?;
+        Ok(vec![Atom::gnd(duration)])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct DurationToMsOp {}
+
+impl Display for DurationToMsOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "duration-to-ms")
+    }
+}
+
+impl Grounded for DurationToMsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_DURATION, ATOM_TYPE_NUMBER])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let duration = args.first().and_then(Atom::as_gnd::<DurationAtom>).ok_or("duration-to-ms expects duration as an argument")?;
+        Ok(vec![Atom::gnd(duration.to_ms())])
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+/// Time or duration argument of the arithmetic and comparison operations
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum TimeArg {
+    Time(TimeAtom),
+    Duration(DurationAtom),
+}
+
+fn time_arg(args: &[Atom], n: usize, usage: &'static str) -> Result<TimeArg, ExecError> {
+    let arg = args.get(n).ok_or(usage)?;
+    match (arg.as_gnd::<TimeAtom>(), arg.as_gnd::<DurationAtom>()) {
+        (Some(time), _) => Ok(TimeArg::Time(*time)),
+        (None, Some(duration)) => Ok(TimeArg::Duration(*duration)),
+        (None, None) => Err(usage.into()),
+    }
+}
+
+fn time_result(result: Option<TimeArg>) -> Result<Vec<Atom>, ExecError> {
+    match result {
+        Some(TimeArg::Time(time)) => Ok(vec![Atom::gnd(time)]),
+        Some(TimeArg::Duration(duration)) => Ok(vec![Atom::gnd(duration)]),
+        None => Err("Time is out of range".into()),
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeAddOp {}
+
+impl Display for TimeAddOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time-add")
+    }
+}
+
+impl Grounded for TimeAddOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_DURATION, ATOM_TYPE_UNDEFINED])
+    }
+
+    /// Adds duration to time or to another duration
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "time-add expects time or duration and duration as arguments";
+        let result = match (time_arg(args, 0, usage)?, time_arg(args, 1, usage)?) {
+            (TimeArg::Time(a), TimeArg::Duration(b)) => a.0.checked_add_signed(b.0).map(|t| TimeArg::Time(TimeAtom(t))),
+            (TimeArg::Duration(a), TimeArg::Duration(b)) => a.0.checked_add(&b.0).map(|d| TimeArg::Duration(DurationAtom(d))),
+            _ => return Err(usage.into()),
+        };
+        time_result(result)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeSubOp {}
+
+impl Display for TimeSubOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "time-sub")
+    }
+}
+
+impl Grounded for TimeSubOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let usage = "time-sub expects two times, time and duration or two durations as arguments";
+        let result = match (time_arg(args, 0, usage)?, time_arg(args, 1, usage)?) {
+            (TimeArg::Time(a), TimeArg::Time(b)) => Some(TimeArg::Duration(DurationAtom(a.0.signed_duration_since(b.0)))),
+            (TimeArg::Time(a), TimeArg::Duration(b)) => a.0.checked_sub_signed(b.0).map(|t| TimeArg::Time(TimeAtom(t))),
+            (TimeArg::Duration(a), TimeArg::Duration(b)) => a.0.checked_sub(&b.0).map(|d| TimeArg::Duration(DurationAtom(d))),
+            (TimeArg::Duration(_), TimeArg::Time(_)) => return Err(usage.into()),
+        };
+        time_result(result)
+    }
+
+    fn match_(&self, other: &Atom) -> MatchResultIter {
+        match_by_equality(self, other)
+    }
+}
+
+macro_rules! def_time_compare_op {
+    ($name:ident, $token:literal, $cmp:expr) => {
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct $name{}
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, $token)
+            }
+        }
+
+        impl Grounded for $name {
+            fn type_(&self) -> Atom {
+                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED, ATOM_TYPE_BOOL])
+            }
+
+            fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+                let usage = concat!($token, " expects two times or two durations as arguments");
+                let a = time_arg(args, 0, usage)?;
+                let b = time_arg(args, 1, usage)?;
+                let ordering = a.partial_cmp(&b).filter(|_| std::mem::discriminant(&a) == std::mem::discriminant(&b))
+                    .ok_or(usage)?;
+                Ok(vec![Atom::gnd(Bool($cmp(ordering)))])
+            }
+
+            fn match_(&self, other: &Atom) -> MatchResultIter {
+                match_by_equality(self, other)
+            }
+        }
+    }
+}
+
+def_time_compare_op!(TimeLessOp, "time-lt", Ordering::is_lt);
+def_time_compare_op!(TimeGreaterOp, "time-gt", Ordering::is_gt);
+def_time_compare_op!(TimeEqOp, "time-eq", Ordering::is_eq);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::text::SExprParser;
+    use crate::metta::runner::{Metta, EnvBuilder};
+
+    fn time(text: &str) -> Atom {
+        Atom::gnd(TimeAtom::parse(text).unwrap())
+    }
+
+    fn ms(ms: i64) -> Atom {
+        Atom::gnd(DurationAtom(TimeDelta::milliseconds(ms)))
+    }
+
+    #[test]
+    fn time_parse_and_format() {
+        assert_eq!(TimeAtom::parse("2024-03-01T10:20:30+02:00").map(|t| t.format()), Ok("2024-03-01T08:20:30Z".into()));
+        assert_eq!(TimeAtom::parse("2024-03-01T10:20:30.5").map(|t| t.format()), Ok("2024-03-01T10:20:30.500Z".into()));
+        assert_eq!(TimeAtom::parse("2024-03-01").map(|t| t.format()), Ok("2024-03-01T00:00:00Z".into()));
+        assert!(TimeAtom::parse("01.03.2024").is_err());
+        assert_eq!(time("2024-03-01T10:20:30Z").to_string(), "(time \"2024-03-01T10:20:30Z\")");
+    }
+
+    #[test]
+    fn duration_ms() {
+        assert_eq!(DurationMsOp{}.execute(&[Atom::gnd(Number::Integer(1500))]), Ok(vec![ms(1500)]));
+        assert_eq!(DurationMsOp{}.execute(&[Atom::gnd(Number::Float(0.25))]),
+            Ok(vec![Atom::gnd(DurationAtom(TimeDelta::microseconds(250)))]));
+        assert!(DurationMsOp{}.execute(&[Atom::gnd(Number::Float(f64::INFINITY))]).is_err());
+        assert_eq!(DurationToMsOp{}.execute(&[ms(-20)]), Ok(vec![Atom::gnd(Number::Integer(-20))]));
+        assert_eq!(DurationToMsOp{}.execute(&[Atom::gnd(DurationAtom(TimeDelta::microseconds(1500)))]),
+            Ok(vec![Atom::gnd(Number::Float(1.5))]));
+        assert_eq!(ms(1500).to_string(), "(duration-ms 1500)");
+    }
+
+    #[test]
+    fn time_arithmetics() {
+        let t = time("2024-03-01T00:00:00Z");
+        assert_eq!(TimeAddOp{}.execute(&[t.clone(), ms(90_000)]), Ok(vec![time("2024-03-01T00:01:30Z")]));
+        assert_eq!(TimeAddOp{}.execute(&[ms(1), ms(2)]), Ok(vec![ms(3)]));
+        assert!(TimeAddOp{}.execute(&[t.clone(), t.clone()]).is_err());
+        assert_eq!(TimeSubOp{}.execute(&[time("2024-03-02T00:00:00Z"), t.clone()]), Ok(vec![ms(86_400_000)]));
+        assert_eq!(TimeSubOp{}.execute(&[t.clone(), ms(1000)]), Ok(vec![time("2024-02-29T23:59:59Z")]));
+        assert!(TimeSubOp{}.execute(&[ms(1000), t]).is_err());
+    }
+
+    #[test]
+    fn time_comparison() {
+        let a = time("2024-03-01T00:00:00Z");
+        let b = time("2024-03-01T00:00:01Z");
+        assert_eq!(TimeLessOp{}.execute(&[a.clone(), b.clone()]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(TimeGreaterOp{}.execute(&[a.clone(), b.clone()]), Ok(vec![Atom::gnd(Bool(false))]));
+        assert_eq!(TimeEqOp{}.execute(&[ms(5), ms(5)]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert!(TimeLessOp{}.execute(&[a, ms(5)]).is_err());
+    }
+
+    #[test]
+    fn monotonic_ms_does_not_decrease() {
+        let first = MonotonicMsOp{}.execute(&[]).unwrap();
+        let second = MonotonicMsOp{}.execute(&[]).unwrap();
+        match (first[0].as_gnd::<Number>(), second[0].as_gnd::<Number>()) {
+            (Some(Number::Integer(a)), Some(Number::Integer(b))) => assert!(a <= b),
+            _ => panic!("Integer numbers are expected: {:?} {:?}", first, second),
+        }
+    }
+
+    #[test]
+    fn time_ops_in_runner() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let result = metta.run(SExprParser::new("
+            !(time-format (time-add (time \"2024-03-01T00:00:00Z\") (duration-ms 61000)))
+            !(time-lt (time \"2024-03-01\") (now))
+            !(duration-to-ms (time-sub (time \"2024-03-01T00:00:02Z\") (time \"2024-03-01\")))
+        "));
+        assert_eq!(result, Ok(vec![
+            vec![str_atom("2024-03-01T00:01:01Z")],
+            vec![Atom::gnd(Bool(true))],
+            vec![Atom::gnd(Number::Integer(2000))],
+        ]));
+    }
+}
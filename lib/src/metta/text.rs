@@ -5,6 +5,8 @@ use crate::*;
 use core::ops::Range;
 use std::str::CharIndices;
 use std::iter::Peekable;
+use std::collections::VecDeque;
+use std::io::BufRead;
 use regex::Regex;
 use std::rc::Rc;
 
@@ -462,6 +464,148 @@ impl<'a> SExprParser<'a> {
 
 }
 
+/// Provides a parser for MeTTa code read incrementally from a [BufRead] source
+///
+/// The source is read until a complete top-level item is accumulated, and
+/// only then the accumulated text is parsed by [SExprParser]. Reading stops
+/// right after the end of the item even if it is in the middle of a line.
+/// Thus the memory used by the parser is bounded by the size of the largest
+/// top-level expression rather than by the size of the whole source or its
+/// longest line.
+pub struct StreamParser<R: BufRead> {
+    reader: R,
+    buf: Vec<u8>,
+    state: ChunkState,
+    atoms: VecDeque<Result<Atom, String>>,
+    eof: bool,
+}
+
+/// Lexical state which is kept between reads to find the end of a top-level item
+#[derive(Default)]
+struct ChunkState {
+    depth: isize,
+    started: bool,
+    in_string: bool,
+    escaped: bool,
+    in_comment: bool,
+}
+
+impl ChunkState {
+    /// Feeds next byte of the source and returns true when the byte
+    /// finishes a top-level item. All bytes which are significant for the
+    /// syntax are ASCII, thus bytes of multibyte UTF-8 characters cannot be
+    /// confused with them and an item never ends inside of a character.
+    fn feed(&mut self, b: u8) -> bool {
+        if self.in_comment {
+            if b == b'\n' {
+                self.in_comment = false;
+                return self.is_complete();
+            }
+        } else if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+            }
+        } else {
+            match b {
+                b';' => self.in_comment = true,
+                b'"' => {
+                    self.in_string = true;
+                    self.started = true;
+                },
+                b'(' => {
+                    self.depth += 1;
+                    self.started = true;
+                },
+                // Negative depth means unbalanced closing bracket, the chunk
+                // is passed to the parser immediately to report the error
+                b')' => {
+                    self.depth -= 1;
+                    return self.depth <= 0;
+                },
+                _ if b.is_ascii_whitespace() => return self.is_complete(),
+                _ => self.started = true,
+            }
+        }
+        false
+    }
+
+    fn is_complete(&self) -> bool {
+        self.started && self.depth <= 0
+    }
+}
+
+impl<R: BufRead> StreamParser<R> {
+    pub fn new(reader: R) -> Self {
+        Self{ reader, buf: Vec::new(), state: ChunkState::default(),
+            atoms: VecDeque::new(), eof: false }
+    }
+
+    fn read_chunk(&mut self) -> Result<(), String> {
+        loop {
+            let available = self.reader.fill_buf()
+                .map_err(|err| format!("Could not read source: {}", err))?;
+            if available.is_empty() {
+                self.eof = true;
+                return Ok(());
+            }
+            let mut len = 0;
+            let mut complete = false;
+            for b in available {
+                len += 1;
+                if self.state.feed(*b) {
+                    complete = true;
+                    break;
+                }
+            }
+            self.buf.extend_from_slice(&available[..len]);
+            self.reader.consume(len);
+            if complete {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_chunk(&mut self, tokenizer: &Tokenizer) {
+        match std::str::from_utf8(&self.buf) {
+            Ok(text) => {
+                let mut parser = SExprParser::new(text);
+                loop {
+                    match parser.parse(tokenizer) {
+                        Ok(Some(atom)) => self.atoms.push_back(Ok(atom)),
+                        Ok(None) => break,
+                        Err(err) => {
+                            self.atoms.push_back(Err(err));
+                            break;
+                        },
+                    }
+                }
+            },
+            Err(err) => self.atoms.push_back(Err(format!("Could not read source: {}", err))),
+        }
+        self.buf.clear();
+        self.state = ChunkState::default();
+    }
+}
+
+impl<R: BufRead> Parser for StreamParser<R> {
+    fn next_atom(&mut self, tokenizer: &Tokenizer) -> Result<Option<Atom>, String> {
+        loop {
+            if let Some(result) = self.atoms.pop_front() {
+                return result.map(Some);
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            self.read_chunk()?;
+            self.parse_chunk(tokenizer);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -593,6 +737,60 @@ mod tests {
         result
     }
 
+    fn parse_atoms_stream(program: &str) -> Vec<Result<Atom, String>> {
+        let tokenizer = Tokenizer::new();
+        let mut parser = StreamParser::new(program.as_bytes());
+        let mut result = Vec::new();
+        loop {
+            match parser.next_atom(&tokenizer) {
+                Ok(Some(atom)) => result.push(Ok(atom)),
+                Ok(None) => break,
+                Err(err) => { result.push(Err(err)); break; },
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn stream_parser_multiline_expressions() {
+        let program = "(a 4) (b\n 5)\n; (c 6)\n(d \"str ; ( \\\" \"\n  (e))\n f";
+        let expected: Vec<Result<Atom, String>> = parse_atoms(program).into_iter().map(Ok).collect();
+        assert_eq!(expected.len(), 4);
+        assert_eq!(parse_atoms_stream(program), expected);
+    }
+
+    #[test]
+    fn stream_parser_reads_single_line_by_items() {
+        let program = "(a 1) (b (c 2)) d \"e f\" (g)";
+        let tokenizer = Tokenizer::new();
+        let mut parser = StreamParser::new(std::io::BufReader::with_capacity(4, program.as_bytes()));
+        assert_eq!(parser.next_atom(&tokenizer), Ok(Some(expr!("a" "1"))));
+        // the rest of the line is not read yet
+        assert!(parser.reader.get_ref().len() > program.len() / 2);
+        let expected: Vec<Result<Atom, String>> = parse_atoms(program).into_iter().map(Ok).collect();
+        assert_eq!(parse_atoms_stream(program), expected);
+        assert_eq!(expected.len(), 5);
+    }
+
+    #[test]
+    fn stream_parser_keeps_multibyte_characters() {
+        let program = "(\u{44b} \"\u{2764}\") \u{44b}\u{44b}";
+        let expected: Vec<Result<Atom, String>> = parse_atoms(program).into_iter().map(Ok).collect();
+        let tokenizer = Tokenizer::new();
+        let mut parser = StreamParser::new(std::io::BufReader::with_capacity(1, program.as_bytes()));
+        assert_eq!(parser.next_atom(&tokenizer), expected[0].clone().map(Some));
+        assert_eq!(parser.next_atom(&tokenizer), expected[1].clone().map(Some));
+        assert_eq!(parser.next_atom(&tokenizer), Ok(None));
+    }
+
+    #[test]
+    fn stream_parser_reports_errors() {
+        assert_eq!(parse_atoms_stream("(a 1)\n(b 2))\n(c 3)"),
+            vec![Ok(expr!("a" "1")), Ok(expr!("b" "2")), Err(String::from("Unexpected right bracket"))]);
+        assert_eq!(parse_atoms_stream("(a 1)\n(b"),
+            vec![Ok(expr!("a" "1")), Err(String::from("Unexpected end of expression"))]);
+    }
+
     #[test]
     fn test_lattice_in_var_name() {
         let mut parser = SExprParser::new("$a#");
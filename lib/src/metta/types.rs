@@ -17,12 +17,20 @@
 //!
 //! When atom has no type assigned by user it has type `%Undefined%`. The value
 //! of `%Undefined%` type can be matched with any type required.
+//!
+//! Types can be computed at type level. Before matching types the type checker
+//! reduces their subexpressions which have no variables using `=` rules and
+//! grounded functions from the space (see [reduce_type]). For example after
+//! `(: vcons (-> $t (Vec $n $t) (Vec (+ $n 1) $t)))` is applied to the vector
+//! of type `(Vec 2 A)` the result has type `(Vec 3 A)`.
 
 use std::convert::TryInto;
+use std::collections::HashMap;
 
 use super::*;
-use crate::atom::matcher::{Bindings, BindingsSet, apply_bindings_to_atom};
-use crate::space::Space;
+use crate::atom::matcher::{Bindings, BindingsSet, apply_bindings_to_atom, atoms_are_equivalent};
+use crate::space::{Space, SpaceEvent, SpaceObserver, SpaceObserverRef};
+use crate::metta::runner::arithmetics::Number;
 
 fn typeof_query(atom: &Atom, typ: &Atom) -> Atom {
     Atom::expr(vec![HAS_TYPE_SYMBOL, atom.clone(), typ.clone()])
@@ -36,43 +44,289 @@ fn query_has_type(space: &dyn Space, sub_type: &Atom, super_type: &Atom) -> Bind
     space.query(&typeof_query(sub_type, super_type))
 }
 
+/// Graph of the `:<` relations of the space. It is built on the first use
+/// and then kept in sync with the space by handling [SpaceEvent]s, thus
+/// the type checker doesn't query the space each time it needs super types.
+/// The graph is rebuilt after the space is modified without notification
+/// (see [crate::space::SpaceCommon::mark_unobserved_change]).
+#[derive(Default)]
+struct SubtypeGraph {
+    /// Number of the unobserved space modifications when the graph was built
+    built: Option<usize>,
+    /// Direct super types of the types, contains relations without variables
+    supers: HashMap<Atom, Vec<Atom>>,
+    /// Direct sub types of the types, contains relations without variables
+    subs: HashMap<Atom, Vec<Atom>>,
+    /// `:<` relations with variables, they are matched on each lookup
+    patterns: Vec<Atom>,
+    /// Cached transitive closure of the relation for the types without variables
+    super_closure: HashMap<Atom, Vec<Atom>>,
+    sub_closure: HashMap<Atom, Vec<Atom>>,
+}
+
+impl SubtypeGraph {
+    fn build(&mut self, space: &dyn Space) {
+        let changes = space.common().unobserved_changes();
+        if self.built == Some(changes) {
+            return;
+        }
+        *self = Self::default();
+        let sub = Atom::Variable(VariableAtom::new("sub").make_unique());
+        let sup = Atom::Variable(VariableAtom::new("super").make_unique());
+        let query = isa_query(&sub, &sup);
+        for bindings in space.query(&query) {
+            self.add(&apply_bindings_to_atom(&query, &bindings));
+        }
+        self.built = Some(changes);
+    }
+
+    fn add(&mut self, relation: &Atom) {
+        match split_isa(relation) {
+            Some(_) if has_variables(relation) => self.patterns.push(relation.clone()),
+            Some((sub, sup)) => {
+                self.supers.entry(sub.clone()).or_default().push(sup.clone());
+                self.subs.entry(sup.clone()).or_default().push(sub.clone());
+            },
+            None => return,
+        }
+        self.super_closure.clear();
+        self.sub_closure.clear();
+    }
+
+    fn remove(&mut self, relation: &Atom) {
+        fn remove_first(types: Option<&mut Vec<Atom>>, typ: &Atom) {
+            if let Some(types) = types {
+                if let Some(i) = types.iter().position(|t| t == typ) {
+                    types.remove(i);
+                }
+            }
+        }
+        match split_isa(relation) {
+            Some(_) if has_variables(relation) => {
+                if let Some(i) = self.patterns.iter().position(|p| atoms_are_equivalent(p, relation)) {
+                    self.patterns.remove(i);
+                }
+            },
+            Some((sub, sup)) => {
+                remove_first(self.supers.get_mut(sub), sup);
+                remove_first(self.subs.get_mut(sup), sub);
+            },
+            None => return,
+        }
+        self.super_closure.clear();
+        self.sub_closure.clear();
+    }
+
+    /// Returns direct super types of the `typ` when `up` is true and direct
+    /// sub types otherwise.
+    fn direct(&self, typ: &Atom, up: bool) -> Vec<Atom> {
+        let var_x = Atom::Variable(VariableAtom::new("X").make_unique());
+        let query = if up { isa_query(typ, &var_x) } else { isa_query(&var_x, typ) };
+        let match_relation = |relation: &Atom| -> Vec<Atom> {
+            matcher::match_atoms(&make_variables_unique(relation.clone()), &query)
+                .map(|bindings| apply_bindings_to_atom(&var_x, &bindings))
+                .collect()
+        };
+        let mut types = Vec::new();
+        if has_variables(typ) {
+            for (sub, sups) in &self.supers {
+                sups.iter().for_each(|sup| types.extend(match_relation(&isa_query(sub, sup))));
+            }
+        } else {
+            let index = if up { &self.supers } else { &self.subs };
+            types.extend(index.get(typ).into_iter().flatten().cloned());
+        }
+        self.patterns.iter().for_each(|relation| types.extend(match_relation(relation)));
+        types
+    }
+
+    /// Returns all super types of the `typ` when `up` is true and all sub
+    /// types otherwise. Types are ordered by the distance from the `typ`.
+    fn closure(&mut self, typ: &Atom, up: bool) -> Vec<Atom> {
+        let cache = if up { &self.super_closure } else { &self.sub_closure };
+        if let Some(types) = cache.get(typ) {
+            return types.clone();
+        }
+        let mut types: Vec<Atom> = Vec::new();
+        let mut front = vec![typ.clone()];
+        while !front.is_empty() {
+            let mut next = Vec::new();
+            for t in &front {
+                for t in self.direct(t, up) {
+                    if t != *typ && !types.contains(&t) {
+                        types.push(t.clone());
+                        next.push(t);
+                    }
+                }
+            }
+            front = next;
+        }
+        if !has_variables(typ) {
+            let cache = if up { &mut self.super_closure } else { &mut self.sub_closure };
+            cache.insert(typ.clone(), types.clone());
+        }
+        types
+    }
+}
+
+impl SpaceObserver for SubtypeGraph {
+    fn notify(&mut self, event: &SpaceEvent) {
+        if self.built.is_none() {
+            return;
+        }
+        match event {
+            SpaceEvent::Add(atom) => self.add(atom),
+            SpaceEvent::Remove(atom) => self.remove(atom),
+            SpaceEvent::Replace(from, to) => {
+                self.remove(from);
+                self.add(to);
+            },
+        }
+    }
+}
+
+fn split_isa(atom: &Atom) -> Option<(&Atom, &Atom)> {
+    match atom {
+        Atom::Expression(expr) => match expr.children().as_slice() {
+            [op, sub, sup] if *op == SUB_TYPE_SYMBOL => Some((sub, sup)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn subtype_graph(space: &dyn Space) -> SpaceObserverRef<SubtypeGraph> {
+    let graph = if space.notifies_observers() {
+        space.common().owned_observer(SubtypeGraph::default)
+    } else {
+        // space can be modified without notification, thus the graph is
+        // built from scratch on each call
+        SpaceObserverRef::from(std::rc::Rc::new(std::cell::RefCell::new(SubtypeGraph::default())))
+    };
+    graph.borrow_mut().build(space);
+    graph
+}
+
 fn query_super_types(space: &dyn Space, sub_type: &Atom) -> Vec<Atom> {
     // TODO: query should check that sub type is a type and not another typed symbol
-    let var_x = VariableAtom::new("X").make_unique();
-    let mut super_types = space.query(&isa_query(&sub_type, &Atom::Variable(var_x.clone())));
-    let atom_x = Atom::Variable(var_x);
-    super_types.drain(0..).map(|bindings| { apply_bindings_to_atom(&atom_x, &bindings) }).collect()
+    subtype_graph(space).borrow().direct(sub_type, true)
 }
 
 fn add_super_types(space: &dyn Space, sub_types: &mut Vec<Atom>, from: usize) {
+    let graph = subtype_graph(space);
+    let mut graph = graph.borrow_mut();
     let mut types = Vec::new();
-    sub_types.iter().skip(from).for_each(|typ| {
-        for typ in query_super_types(space, typ) {
-            if !sub_types.contains(&typ) {
+    for typ in sub_types.iter().skip(from) {
+        for typ in graph.closure(typ, true) {
+            if !sub_types.contains(&typ) && !types.contains(&typ) {
                 types.push(typ);
             }
         }
-    });
-    if !types.is_empty() {
-        let from = sub_types.len();
-        sub_types.append(&mut types.clone());
-        add_super_types(space, sub_types, from);
     }
+    sub_types.append(&mut types);
+}
+
+/// Returns all super types of the `typ` declared using `:<` relation
+/// including the indirect ones. The types are ordered by the distance from
+/// the `typ`.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::get_super_types;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(:< Cat Mammal) (:< Mammal Animal)")).unwrap();
+///
+/// assert_eq!(get_super_types(&metta.space(), &expr!("Cat")), vec![expr!("Mammal"), expr!("Animal")]);
+/// ```
+pub fn get_super_types(space: &dyn Space, typ: &Atom) -> Vec<Atom> {
+    subtype_graph(space).borrow_mut().closure(typ, true)
 }
 
-fn check_arg_types(actual: &[Vec<Atom>], meta: &[Vec<Atom>], expected: &[Atom], bindings: Bindings) -> BindingsSet {
+/// Returns all sub types of the `typ` declared using `:<` relation
+/// including the indirect ones. The types are ordered by the distance from
+/// the `typ`.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::get_sub_types;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(:< Cat Mammal) (:< Mammal Animal)")).unwrap();
+///
+/// assert_eq!(get_sub_types(&metta.space(), &expr!("Animal")), vec![expr!("Mammal"), expr!("Cat")]);
+/// ```
+pub fn get_sub_types(space: &dyn Space, typ: &Atom) -> Vec<Atom> {
+    subtype_graph(space).borrow_mut().closure(typ, false)
+}
+
+/// Returns the least upper bounds of the types `a` and `b`: the common
+/// super types which have no common sub types among other common super
+/// types. A type is considered to be its own super type, thus if `a` is
+/// a sub type of `b` then `b` is returned. Returns empty vector when types
+/// have no common super types.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::get_least_upper_bounds;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("
+///     (:< Cat Mammal) (:< Dog Mammal) (:< Mammal Animal) (:< Frog Animal)
+/// ")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(get_least_upper_bounds(&space, &expr!("Cat"), &expr!("Dog")), vec![expr!("Mammal")]);
+/// assert_eq!(get_least_upper_bounds(&space, &expr!("Cat"), &expr!("Frog")), vec![expr!("Animal")]);
+/// assert_eq!(get_least_upper_bounds(&space, &expr!("Cat"), &expr!("Mammal")), vec![expr!("Mammal")]);
+/// ```
+pub fn get_least_upper_bounds(space: &dyn Space, a: &Atom, b: &Atom) -> Vec<Atom> {
+    let graph = subtype_graph(space);
+    let mut graph = graph.borrow_mut();
+    let mut upper = |typ: &Atom| {
+        let mut types = vec![typ.clone()];
+        types.extend(graph.closure(typ, true));
+        types
+    };
+    let upper_b = upper(b);
+    let common: Vec<(Atom, Vec<Atom>)> = upper(a).into_iter()
+        .filter(|typ| upper_b.contains(typ))
+        .map(|typ| { let sup = upper(&typ); (typ, sup) })
+        .collect();
+    common.iter()
+        .filter(|(typ, sup)| !common.iter().any(|(other, other_sup)| {
+            other != typ && other_sup.contains(typ) && !sup.contains(other)
+        }))
+        .map(|(typ, _)| typ.clone())
+        .collect()
+}
+
+fn check_arg_types(space: &dyn Space, actual: &[Vec<Atom>], meta: &[Vec<Atom>], expected: &[Atom], bindings: Bindings) -> BindingsSet {
     log::trace!("check_arg_types: actual: {:?}, expected: {:?}", actual, expected);
     let matched = match (actual, meta, expected) {
         ([actual, actual_tail @ ..], [meta, meta_tail @ ..], [expected, expected_tail @ ..]) => {
             if meta.contains(expected) {
                 BindingsSet::single()
             } else {
+                let expected = reduce_expected_type(space, expected, &bindings);
                 let mut result_bindings = BindingsSet::empty();
                 for typ in actual {
                     result_bindings.extend(
-                        match_reducted_types_v2(typ, expected)
+                        match_reducted_types_v2(typ, &expected)
                             .flat_map(|b| b.merge_v2(&bindings))
-                            .flat_map(|b| check_arg_types(actual_tail, meta_tail, expected_tail, b))
+                            .flat_map(|b| check_arg_types(space, actual_tail, meta_tail, expected_tail, b))
                     );
                 }
                 result_bindings
@@ -109,7 +363,7 @@ fn query_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
     let var_x = VariableAtom::new("X").make_unique();
     let mut types = query_has_type(space, atom, &Atom::Variable(var_x.clone()));
     let atom_x = Atom::Variable(var_x);
-    let mut types = types.drain(0..).filter_map(|bindings| {
+    let types: Vec<Atom> = types.drain(0..).filter_map(|bindings| {
         let atom = apply_bindings_to_atom(&atom_x, &bindings);
         if atom_x == atom {
             None
@@ -117,6 +371,7 @@ fn query_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
             Some(atom)
         }
     }).collect();
+    let mut types = types.into_iter().map(|typ| reduce_type(space, &typ)).collect();
     add_super_types(space, &mut types, 0);
     types
 }
@@ -190,13 +445,8 @@ fn get_args(expr: &ExpressionAtom) -> &[Atom] {
 pub fn get_atom_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
     log::trace!("get_atom_types: atom: {}", atom);
     let types = match atom {
-        // TODO: type of the variable could be actually a type variable,
-        // in this case inside each variant of type for the atom we should
-        // also keep bindings for the type variables. For example,
-        // we have an expression `(let $n (foo) (+ $n $n))`, where
-        // `(: let (-> $t $t $r $r))`, `(: foo (-> $tt))`,
-        // and `(: + (-> Num Num Num))`then type checker can find that
-        // `{ $r = $t = $tt = Num }`.
+        // Types of the variables depend on the expression they are used in,
+        // see [infer_variable_types]
         Atom::Variable(_) => vec![ATOM_TYPE_UNDEFINED],
         Atom::Grounded(gnd) => vec![make_variables_unique(gnd.type_())],
         Atom::Symbol(_) => {
@@ -280,17 +530,18 @@ fn get_application_types(space: &dyn Space, atom: &Atom, expr: &ExpressionAtom)
         let mut meta_arg_types = Vec::new();
         for arg in args {
             actual_arg_types.push(get_atom_types(space, arg));
-            meta_arg_types.push(vec![get_meta_type(arg), ATOM_TYPE_ATOM]);
+            meta_arg_types.push(get_arg_meta_types(arg));
         }
         let mut fn_types = get_atom_types(space, op);
         let fn_types = fn_types.drain(0..).filter(is_func);
         for fn_type in fn_types {
             has_function_types = true;
             let (expected_arg_types, ret_typ) = get_arg_types(&fn_type);
-            for bindings in check_arg_types(actual_arg_types.as_slice(), meta_arg_types.as_slice(), expected_arg_types, Bindings::new()) {
-                types.push(apply_bindings_to_atom(&ret_typ, &bindings));
+            for bindings in check_arg_types(space, actual_arg_types.as_slice(), meta_arg_types.as_slice(), expected_arg_types, Bindings::new()) {
+                types.push(reduce_expected_type(space, ret_typ, &bindings));
             }
         }
+        add_super_types(space, &mut types, 0);
         log::trace!("get_application_types: function application {} types {:?}", atom, types);
     }
     if has_function_types {
@@ -300,6 +551,104 @@ fn get_application_types(space: &dyn Space, atom: &Atom, expr: &ExpressionAtom)
     }
 }
 
+/// Maximal number of the reduction steps made while reducing a type. Type
+/// level functions are not guaranteed to terminate, thus the type checker
+/// gives up and keeps the type partially reduced after the limit is reached.
+const TYPE_REDUCTION_LIMIT: usize = 64;
+
+/// Reduces the `typ` using `=` rules from the `space` and grounded operations
+/// which are marked by [crate::Grounded::is_type_level]. Other grounded
+/// operations are not executed because they can have side effects. Only types which have subexpressions are
+/// reduced and only subexpressions which have no variables are reduced, other
+/// parts of the type are kept as is and matched structurally. Subexpression
+/// is reduced only when it has a single result, non-deterministic type
+/// functions are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::reduce_type;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("
+///     (= (plus Z $n) $n)
+///     (= (plus (S $m) $n) (S (plus $m $n)))
+/// ")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(reduce_type(&space, &expr!("Vec" ("plus" ("S" "Z") ("S" "Z")) "A")),
+///     expr!("Vec" ("S" ("S" "Z")) "A"));
+/// assert_eq!(reduce_type(&space, &expr!("Vec" ("plus" n ("S" "Z")) "A")),
+///     expr!("Vec" ("plus" n ("S" "Z")) "A"));
+/// ```
+pub fn reduce_type(space: &dyn Space, typ: &Atom) -> Atom {
+    let has_subexpressions = match typ {
+        Atom::Expression(expr) => expr.children().iter().any(|child| matches!(child, Atom::Expression(_))),
+        _ => false,
+    };
+    if !has_subexpressions {
+        return typ.clone();
+    }
+    let mut steps = TYPE_REDUCTION_LIMIT;
+    reduce_type_steps(space, typ, &mut steps)
+}
+
+fn reduce_type_steps(space: &dyn Space, typ: &Atom, steps: &mut usize) -> Atom {
+    match typ {
+        Atom::Expression(expr) if !expr.children().is_empty() => {
+            let typ = Atom::expr(expr.children().iter()
+                .map(|child| reduce_type_steps(space, child, steps))
+                .collect::<Vec<Atom>>());
+            if *steps == 0 || has_variables(&typ) {
+                return typ;
+            }
+            match reduce_type_step(space, &typ) {
+                Some(reduced) if reduced != typ => {
+                    *steps -= 1;
+                    reduce_type_steps(space, &reduced, steps)
+                },
+                _ => typ,
+            }
+        },
+        _ => typ.clone(),
+    }
+}
+
+fn reduce_type_step(space: &dyn Space, typ: &Atom) -> Option<Atom> {
+    let expr = match typ {
+        Atom::Expression(expr) => expr,
+        _ => return None,
+    };
+    let mut results = match get_op(expr) {
+        Atom::Grounded(gnd) => if gnd.is_type_level() {
+            gnd.execute(get_args(expr)).ok()?
+        } else {
+            return None;
+        },
+        _ => {
+            let var_x = Atom::Variable(VariableAtom::new("X").make_unique());
+            space.query(&Atom::expr([EQUAL_SYMBOL, typ.clone(), var_x.clone()])).into_iter()
+                .map(|bindings| apply_bindings_to_atom(&var_x, &bindings))
+                .collect()
+        },
+    };
+    if results.len() == 1 {
+        results.pop()
+    } else {
+        None
+    }
+}
+
+/// Applies `bindings` collected from the previous arguments to the
+/// `expected` type and reduces the type using the values of the
+/// dependencies.
+fn reduce_expected_type(space: &dyn Space, expected: &Atom, bindings: &Bindings) -> Atom {
+    reduce_type(space, &apply_bindings_to_atom(expected, bindings))
+}
+
 #[derive(Clone, PartialEq, Debug)]
 struct UndefinedTypeMatch { }
 
@@ -383,6 +732,7 @@ fn replace_undefined_types(atom: &Atom) -> Atom {
 }
 
 fn get_matched_types(space: &dyn Space, atom: &Atom, typ: &Atom) -> Vec<(Atom, Bindings)> {
+    let typ = &reduce_type(space, typ);
     let mut types = get_atom_types(space, atom);
     types.drain(0..).flat_map(|t| {
         // TODO: write a unit test
@@ -477,6 +827,617 @@ pub fn validate_atom(space: &dyn Space, atom: &Atom) -> bool {
     !get_atom_types(space, atom).is_empty()
 }
 
+fn has_variables(atom: &Atom) -> bool {
+    atom.iter().any(|atom| matches!(atom, Atom::Variable(_)))
+}
+
+/// Reason of the failed function call check and bindings of the type
+/// variables collected before the failed argument.
+type InferenceError = Box<(TypeErrorKind, Bindings)>;
+
+/// Type checker which assigns a type variable to each variable of the
+/// expression and carries bindings of the type variables from one argument
+/// to another. Thus all occurrences of the variable should have compatible
+/// types.
+struct TypeInference<'a> {
+    space: &'a dyn Space,
+    var_types: HashMap<VariableAtom, VariableAtom>,
+}
+
+impl<'a> TypeInference<'a> {
+    fn new(space: &'a dyn Space) -> Self {
+        Self{ space, var_types: HashMap::new() }
+    }
+
+    fn var_type(&mut self, var: &VariableAtom) -> Atom {
+        Atom::Variable(self.var_types.entry(var.clone())
+            .or_insert_with(|| VariableAtom::new("t").make_unique()).clone())
+    }
+
+    /// Narrows the type of the variable `arg` down to the `expected` type
+    /// when `expected` is a sub type of the type inferred so far. The most
+    /// specific type is compatible with all previous occurrences of the
+    /// variable, thus the result doesn't depend on the order of occurrences.
+    fn narrow_var_type(&mut self, arg: &Atom, expected: &Atom, bindings: &Bindings) -> Vec<Bindings> {
+        let typ_var = match arg {
+            Atom::Variable(var) => match self.var_type(var) {
+                Atom::Variable(typ_var) => typ_var,
+                _ => return Vec::new(),
+            },
+            _ => return Vec::new(),
+        };
+        let typ = apply_bindings_to_atom(&Atom::Variable(typ_var.clone()), bindings);
+        if matches!(typ, Atom::Variable(_)) || !get_sub_types(self.space, &typ).contains(expected) {
+            return Vec::new();
+        }
+        let mut narrowed = bindings.clone();
+        if narrowed.replace_var_value(&typ_var, expected.clone()) {
+            vec![narrowed]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Returns types of the `atom` paired with type variable bindings which
+    /// extend passed `bindings`.
+    fn infer(&mut self, atom: &Atom, bindings: &Bindings) -> Vec<(Atom, Bindings)> {
+        match atom {
+            Atom::Variable(var) => {
+                let typ = apply_bindings_to_atom(&self.var_type(var), bindings);
+                let mut types = vec![typ];
+                if !matches!(types[0], Atom::Variable(_)) {
+                    add_super_types(self.space, &mut types, 0);
+                }
+                types.into_iter().map(|typ| (typ, bindings.clone())).collect()
+            },
+            Atom::Expression(expr) if has_variables(atom) => self.infer_expr(expr, bindings),
+            _ => get_atom_types(self.space, atom).into_iter()
+                .map(|typ| (make_variables_unique(typ), bindings.clone())).collect(),
+        }
+    }
+
+    fn fn_types(&self, op: &Atom) -> Vec<Atom> {
+        get_atom_types(self.space, op).into_iter()
+            .filter(is_func).map(make_variables_unique).collect()
+    }
+
+    fn infer_expr(&mut self, expr: &ExpressionAtom, bindings: &Bindings) -> Vec<(Atom, Bindings)> {
+        let fn_types = self.fn_types(get_op(expr));
+        if fn_types.is_empty() {
+            self.infer_tuple(expr, bindings).map_or(Vec::new(),
+                |variants| variants.into_iter().map(|b| (ATOM_TYPE_UNDEFINED, b)).collect())
+        } else {
+            let args = get_args(expr);
+            fn_types.iter()
+                .flat_map(|fn_type| self.infer_application(fn_type, args, bindings).unwrap_or_default())
+                .collect()
+        }
+    }
+
+    /// Carries bindings through the children of the expression which is not
+    /// a function call. Returns the index of the child which cannot be typed
+    /// with the bindings collected from the previous children on error.
+    fn infer_tuple(&mut self, expr: &ExpressionAtom, bindings: &Bindings) -> Result<Vec<Bindings>, (usize, Bindings)> {
+        let mut variants = vec![bindings.clone()];
+        for (i, child) in expr.children().iter().enumerate() {
+            let next: Vec<Bindings> = variants.iter()
+                .flat_map(|b| self.infer(child, b))
+                .map(|(_typ, b)| b)
+                .collect();
+            if next.is_empty() {
+                return Err((i, variants.swap_remove(0)));
+            }
+            variants = next;
+        }
+        Ok(variants)
+    }
+
+    /// Checks arguments of the function call against `fn_type` and returns
+    /// return types of the call. Returns the reason of the mismatch and
+    /// bindings collected before the failed argument on error.
+    fn infer_application(&mut self, fn_type: &Atom, args: &[Atom], bindings: &Bindings) -> Result<Vec<(Atom, Bindings)>, InferenceError> {
+        let (expected, ret_typ) = get_arg_types(fn_type);
+        if expected.len() != args.len() {
+            let kind = TypeErrorKind::IncorrectNumberOfArguments{ expected: expected.len(), actual: args.len() };
+            return Err(Box::new((kind, bindings.clone())));
+        }
+        let mut variants = vec![bindings.clone()];
+        for (i, (arg, expected)) in args.iter().zip(expected).enumerate() {
+            let next: Vec<Bindings> = if check_meta_type(arg, expected) {
+                variants.clone()
+            } else {
+                variants.iter()
+                    .flat_map(|b| {
+                        let expected = reduce_expected_type(self.space, expected, b);
+                        let matched: Vec<Bindings> = self.infer(arg, b).into_iter()
+                            .flat_map(|(typ, b)| match_reducted_types_v2(&typ, &expected)
+                                .flat_map(move |m| m.merge_v2(&b)))
+                            .collect();
+                        if matched.is_empty() {
+                            self.narrow_var_type(arg, &expected, b)
+                        } else {
+                            matched
+                        }
+                    })
+                    .collect()
+            };
+            if next.is_empty() {
+                let bindings = variants.swap_remove(0);
+                let expected = reduce_expected_type(self.space, expected, &bindings);
+                let actual = self.infer(arg, &bindings).into_iter().map(|(typ, _)| typ).collect();
+                return Err(Box::new((TypeErrorKind::BadArgType{ position: i + 1, expected, actual }, bindings)));
+            }
+            variants = next;
+        }
+        Ok(variants.into_iter().flat_map(|b| {
+            let mut types = vec![reduce_expected_type(self.space, ret_typ, &b)];
+            if !matches!(types[0], Atom::Variable(_)) {
+                add_super_types(self.space, &mut types, 0);
+            }
+            types.into_iter().map(move |typ| (typ, b.clone()))
+        }).collect())
+    }
+
+    /// Finds the innermost expression which cannot be typed because of the
+    /// conflicting types of the variables.
+    fn diagnose(&mut self, atom: &Atom, bindings: &Bindings) -> Option<(Atom, TypeErrorKind)> {
+        let expr = match atom {
+            Atom::Expression(expr) if has_variables(atom) => expr,
+            _ => return None,
+        };
+        let fn_types = self.fn_types(get_op(expr));
+        if fn_types.is_empty() {
+            let (i, bindings) = self.infer_tuple(expr, bindings).err()?;
+            return self.diagnose(&expr.children()[i], &bindings);
+        }
+        let args = get_args(expr);
+        let mut errors = Vec::new();
+        for fn_type in &fn_types {
+            match self.infer_application(fn_type, args, bindings) {
+                Ok(types) if !types.is_empty() => return None,
+                Ok(_) => {},
+                Err(err) => errors.push(err),
+            }
+        }
+        let (kind, bindings) = *errors.into_iter().max_by_key(|err| error_position(&err.0))?;
+        if let TypeErrorKind::BadArgType{ position, actual, .. } = &kind {
+            if actual.is_empty() {
+                if let Some(inner) = self.diagnose(&args[position - 1], &bindings) {
+                    return Some(inner);
+                }
+            }
+        }
+        Some((atom.clone(), kind))
+    }
+}
+
+/// Infers types of the variables of the `atom` from the types of the
+/// functions they are passed to. Returns a variant of the variable types for
+/// each way the `atom` can be typed, variables which type cannot be inferred
+/// are not included. Returns empty vector if the `atom` cannot be typed
+/// because of conflicting types of the variables.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, bind};
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::infer_variable_types;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(: foo (-> A A B)) (: bar (-> B A))")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(infer_variable_types(&space, &expr!("bar" x)), vec![bind!{x: expr!("B")}]);
+/// assert!(infer_variable_types(&space, &expr!("foo" ("bar" x) x)).is_empty());
+/// ```
+pub fn infer_variable_types(space: &dyn Space, atom: &Atom) -> Vec<Bindings> {
+    let mut inference = TypeInference::new(space);
+    let variants = inference.infer(atom, &Bindings::new());
+    let mut result: Vec<Bindings> = Vec::new();
+    for (_typ, bindings) in variants {
+        let mut types = Bindings::new();
+        for (var, typ_var) in &inference.var_types {
+            let typ = apply_bindings_to_atom(&Atom::Variable(typ_var.clone()), &bindings);
+            if !matches!(typ, Atom::Variable(_)) {
+                types.add_var_binding(var, typ);
+            }
+        }
+        if !result.contains(&types) {
+            result.push(types);
+        }
+    }
+    result
+}
+
+/// Describes why an expression is not typed correctly.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeErrorKind {
+    /// Argument at `position` (starting from 1) has none of the types which
+    /// are compatible with the `expected` type, `actual` contains all types
+    /// returned by [get_atom_types] for the argument.
+    BadArgType{ position: usize, expected: Atom, actual: Vec<Atom> },
+    /// Number of arguments doesn't match the function type.
+    IncorrectNumberOfArguments{ expected: usize, actual: usize },
+    /// Body of the `=` definition has none of the types compatible with the
+    /// return type of the function, `actual` contains types of the body.
+    BadReturnType{ expected: Atom, actual: Vec<Atom> },
+}
+
+/// Diagnostic information about the innermost ill-typed expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError {
+    /// Ill-typed expression, all its children are typed correctly
+    pub atom: Atom,
+    /// Reason why the expression is ill-typed
+    pub kind: TypeErrorKind,
+    /// `:` and `:<` declarations from the space which are related to the
+    /// function and to the argument which failed the check
+    pub declarations: Vec<Atom>,
+}
+
+impl TypeError {
+    /// Converts diagnostic into the atom which can be put as a reason into
+    /// the `Error` expression. The format is either
+    /// `(BadArgType <atom> <position> <expected> (<actual>...) (<declarations>...))`
+    /// or `(IncorrectNumberOfArguments <atom> <expected> <actual> (<declarations>...))`
+    /// or `(BadReturnType <atom> <expected> (<actual>...) (<declarations>...))`.
+    pub fn to_atom(&self) -> Atom {
+        let declarations = Atom::expr(self.declarations.clone());
+        match &self.kind {
+            TypeErrorKind::BadArgType{ position, expected, actual } =>
+                Atom::expr([BAD_ARG_TYPE_SYMBOL, self.atom.clone(),
+                    Atom::gnd(Number::Integer(*position as i64)), expected.clone(),
+                    Atom::expr(actual.clone()), declarations]),
+            TypeErrorKind::IncorrectNumberOfArguments{ expected, actual } =>
+                Atom::expr([INCORRECT_NUMBER_OF_ARGUMENTS_SYMBOL, self.atom.clone(),
+                    Atom::gnd(Number::Integer(*expected as i64)),
+                    Atom::gnd(Number::Integer(*actual as i64)), declarations]),
+            TypeErrorKind::BadReturnType{ expected, actual } =>
+                Atom::expr([BAD_RETURN_TYPE_SYMBOL, self.atom.clone(), expected.clone(),
+                    Atom::expr(actual.clone()), declarations]),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.kind {
+            TypeErrorKind::BadArgType{ position, expected, actual } => {
+                write!(f, "argument {} of {} is expected to have type {}, actual types: ", position, self.atom, expected)?;
+                write_types(f, actual)?;
+            },
+            TypeErrorKind::IncorrectNumberOfArguments{ expected, actual } =>
+                write!(f, "{} expects {} arguments, {} passed", self.atom, expected, actual)?,
+            TypeErrorKind::BadReturnType{ expected, actual } => {
+                write!(f, "{} is expected to have return type {}, actual types: ", self.atom, expected)?;
+                write_types(f, actual)?;
+            },
+        }
+        self.declarations.iter().try_for_each(|decl| write!(f, "\n  declared: {}", decl))
+    }
+}
+
+fn write_types(f: &mut std::fmt::Formatter, types: &[Atom]) -> std::fmt::Result {
+    write!(f, "[")?;
+    types.iter().enumerate().try_for_each(|(i, typ)| {
+        if i > 0 { write!(f, ", ")?; }
+        write!(f, "{}", typ)
+    })?;
+    write!(f, "]")
+}
+
+/// Returns diagnostic for the innermost ill-typed expression of the `atom`
+/// or `None` when `atom` is typed correctly, see [validate_atom]. Unlike
+/// [validate_atom] it also reports variables which are used with
+/// incompatible types, see [infer_variable_types].
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::{get_type_error, TypeErrorKind};
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(: foo (-> A B)) (: a A) (: b B)")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(get_type_error(&space, &expr!("foo" ("foo" "a"))).unwrap().kind,
+///     TypeErrorKind::BadArgType{ position: 1, expected: expr!("A"), actual: vec![expr!("B")] });
+/// assert_eq!(get_type_error(&space, &expr!("foo" "a")), None);
+/// ```
+pub fn get_type_error(space: &dyn Space, atom: &Atom) -> Option<TypeError> {
+    if validate_atom(space, atom) && !has_variable_type_conflict(space, atom) {
+        return None;
+    }
+    match atom {
+        Atom::Expression(expr) => expr.children().iter()
+            .find_map(|child| get_type_error(space, child))
+            .or_else(|| get_application_error(space, atom, expr)),
+        _ => None,
+    }
+}
+
+fn has_variable_type_conflict(space: &dyn Space, atom: &Atom) -> bool {
+    has_variables(atom) && TypeInference::new(space).infer(atom, &Bindings::new()).is_empty()
+}
+
+fn get_application_error(space: &dyn Space, atom: &Atom, expr: &ExpressionAtom) -> Option<TypeError> {
+    if expr.children().is_empty() {
+        return None;
+    }
+    let op = get_op(expr);
+    let args = get_args(expr);
+    let actual_arg_types: Vec<Vec<Atom>> = args.iter().map(|arg| get_atom_types(space, arg)).collect();
+    let meta_arg_types: Vec<Vec<Atom>> = args.iter().map(get_arg_meta_types).collect();
+    let kind = get_atom_types(space, op).iter()
+        .filter(|typ| is_func(typ))
+        .filter_map(|fn_type| find_arg_mismatch(space, fn_type, &actual_arg_types, &meta_arg_types))
+        // report the function type which passed the most of the arguments
+        .max_by_key(error_position);
+    match kind {
+        Some(kind) => Some(new_type_error(space, atom, kind)),
+        // each argument is typed correctly when checked separately, thus
+        // the error is caused by the conflicting types of the variables
+        None => TypeInference::new(space).diagnose(atom, &Bindings::new())
+            .map(|(atom, kind)| new_type_error(space, &atom, kind)),
+    }
+}
+
+fn error_position(kind: &TypeErrorKind) -> usize {
+    match kind {
+        TypeErrorKind::BadArgType{ position, .. } => *position,
+        TypeErrorKind::IncorrectNumberOfArguments{ .. } => 0,
+        TypeErrorKind::BadReturnType{ .. } => 0,
+    }
+}
+
+fn new_type_error(space: &dyn Space, atom: &Atom, kind: TypeErrorKind) -> TypeError {
+    let (op, args) = match atom {
+        Atom::Expression(expr) => (get_op(expr), get_args(expr)),
+        _ => panic!("Expression is expected: {}", atom),
+    };
+    let mut declarations = query_declarations(space, op);
+    if let TypeErrorKind::BadArgType{ position, actual, .. } = &kind {
+        declarations.extend(query_declarations(space, &args[position - 1]));
+        for typ in actual {
+            declarations.extend(query_super_types(space, typ).into_iter()
+                .map(|sup| isa_query(typ, &sup)));
+        }
+    }
+    TypeError{ atom: atom.clone(), kind, declarations }
+}
+
+fn find_arg_mismatch(space: &dyn Space, fn_type: &Atom, actual: &[Vec<Atom>], meta: &[Vec<Atom>]) -> Option<TypeErrorKind> {
+    let (expected, _ret) = get_arg_types(fn_type);
+    if expected.len() != actual.len() {
+        return Some(TypeErrorKind::IncorrectNumberOfArguments{ expected: expected.len(), actual: actual.len() });
+    }
+    let mut variants = vec![Bindings::new()];
+    for (i, expected) in expected.iter().enumerate() {
+        let next: Vec<Bindings> = if meta[i].contains(expected) {
+            variants.clone()
+        } else {
+            variants.iter().flat_map(|bindings| {
+                let expected = reduce_expected_type(space, expected, bindings);
+                actual[i].iter().flat_map(move |typ| {
+                    match_reducted_types_v2(typ, &expected).flat_map(move |b| b.merge_v2(bindings))
+                }).collect::<Vec<Bindings>>()
+            }).collect()
+        };
+        if next.is_empty() {
+            let expected = reduce_expected_type(space, expected, &variants[0]);
+            return Some(TypeErrorKind::BadArgType{ position: i + 1, expected, actual: actual[i].clone() });
+        }
+        variants = next;
+    }
+    None
+}
+
+fn query_declarations(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
+    match atom {
+        Atom::Symbol(_) | Atom::Expression(_) => {
+            let query = typeof_query(atom, &Atom::Variable(VariableAtom::new("X").make_unique()));
+            space.query(&query).into_iter()
+                .map(|bindings| apply_bindings_to_atom(&query, &bindings))
+                .collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Placeholder which is substituted instead of the pattern variable of the
+/// `=` definition while checking the body. It keeps the type of the variable
+/// inferred from the function signature.
+#[derive(Clone, PartialEq, Debug)]
+struct TypedVariable {
+    var: VariableAtom,
+    typ: Atom,
+}
+
+impl std::fmt::Display for TypedVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.var)
+    }
+}
+
+impl Grounded for TypedVariable {
+    fn type_(&self) -> Atom {
+        self.typ.clone()
+    }
+
+    fn match_(&self, other: &Atom) -> crate::matcher::MatchResultIter {
+        match_by_equality(self, other)
+    }
+
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        execute_not_executable(self)
+    }
+}
+
+/// Returns meta-types which argument satisfies. Value of the [TypedVariable]
+/// is not known while the definition is checked, thus it is accepted as an
+/// argument of any meta-type like an ordinary variable.
+fn get_arg_meta_types(arg: &Atom) -> Vec<Atom> {
+    match arg.as_gnd::<TypedVariable>() {
+        Some(_) => vec![ATOM_TYPE_VARIABLE, ATOM_TYPE_SYMBOL, ATOM_TYPE_EXPRESSION,
+            ATOM_TYPE_GROUNDED, ATOM_TYPE_ATOM],
+        None => vec![get_meta_type(arg), ATOM_TYPE_ATOM],
+    }
+}
+
+fn is_meta_type(typ: &Atom) -> bool {
+    [ATOM_TYPE_ATOM, ATOM_TYPE_SYMBOL, ATOM_TYPE_VARIABLE, ATOM_TYPE_EXPRESSION,
+        ATOM_TYPE_GROUNDED, ATOM_TYPE_UNDEFINED].contains(typ)
+}
+
+fn replace_typed_variables(atom: &Atom, var_types: &HashMap<VariableAtom, Atom>) -> Atom {
+    let mut atom = atom.clone();
+    atom.iter_mut().for_each(|atom| {
+        let typed = match atom {
+            Atom::Variable(var) => var_types.get(var)
+                .map(|typ| Atom::gnd(TypedVariable{ var: var.clone(), typ: typ.clone() })),
+            _ => None,
+        };
+        if let Some(typed) = typed {
+            *atom = typed;
+        }
+    });
+    atom
+}
+
+fn restore_variables(atom: &Atom) -> Atom {
+    let mut atom = atom.clone();
+    atom.iter_mut().for_each(|atom| {
+        let var = atom.as_gnd::<TypedVariable>().map(|typed| Atom::Variable(typed.var.clone()));
+        if let Some(var) = var {
+            *atom = var;
+        }
+    });
+    atom
+}
+
+/// Matches `pattern` from the left part of the `=` definition with the
+/// `expected` type and collects types of the pattern variables. Returns
+/// false if pattern cannot have the `expected` type.
+fn infer_pattern_types(space: &dyn Space, pattern: &Atom, expected: &Atom, var_types: &mut HashMap<VariableAtom, Atom>) -> bool {
+    match pattern {
+        Atom::Variable(var) => {
+            if !is_meta_type(expected) && !matches!(expected, Atom::Variable(_)) {
+                var_types.entry(var.clone()).or_insert_with(|| expected.clone());
+            }
+            true
+        },
+        Atom::Expression(expr) if !expr.children().is_empty() => {
+            let args = get_args(expr);
+            let fn_types: Vec<Atom> = get_atom_types(space, get_op(expr)).into_iter()
+                .filter(is_func).collect();
+            if fn_types.is_empty() {
+                return check_type(space, pattern, expected);
+            }
+            fn_types.iter().any(|fn_type| {
+                let (arg_types, ret_typ) = get_arg_types(fn_type);
+                if arg_types.len() != args.len() {
+                    return false;
+                }
+                match_reducted_types_v2(ret_typ, expected).any(|bindings| {
+                    let mut types = var_types.clone();
+                    let matched = args.iter().zip(arg_types).all(|(arg, typ)| {
+                        infer_pattern_types(space, arg, &apply_bindings_to_atom(typ, &bindings), &mut types)
+                    });
+                    if matched {
+                        *var_types = types;
+                    }
+                    matched
+                })
+            })
+        },
+        _ => check_type(space, pattern, expected),
+    }
+}
+
+fn get_definition_type_error(space: &dyn Space, head: &Atom, body: &Atom, fn_type: &Atom) -> Option<TypeError> {
+    let (op, args) = match head {
+        Atom::Expression(expr) => (get_op(expr), get_args(expr)),
+        _ => unreachable!("Expression is expected"),
+    };
+    let (arg_types, ret_typ) = get_arg_types(fn_type);
+    let error = |atom: &Atom, kind| Some(TypeError{ atom: atom.clone(), kind, declarations: query_declarations(space, op) });
+    if arg_types.len() != args.len() {
+        return error(head, TypeErrorKind::IncorrectNumberOfArguments{ expected: arg_types.len(), actual: args.len() });
+    }
+    let mut var_types = HashMap::new();
+    for (i, (arg, typ)) in args.iter().zip(arg_types).enumerate() {
+        if !infer_pattern_types(space, arg, typ, &mut var_types) {
+            let actual = get_atom_types(space, arg);
+            return error(head, TypeErrorKind::BadArgType{ position: i + 1, expected: typ.clone(), actual });
+        }
+    }
+    let typed_body = replace_typed_variables(body, &var_types);
+    if let Some(mut err) = get_type_error(space, &typed_body) {
+        err.atom = restore_variables(&err.atom);
+        if let TypeErrorKind::BadArgType{ actual, .. } = &mut err.kind {
+            *actual = actual.iter().map(restore_variables).collect();
+        }
+        return Some(err);
+    }
+    if check_meta_type(body, ret_typ) || check_type(space, &typed_body, ret_typ) {
+        None
+    } else {
+        let actual = get_atom_types(space, &typed_body);
+        error(body, TypeErrorKind::BadReturnType{ expected: ret_typ.clone(), actual })
+    }
+}
+
+/// Checks `(= <head> <body>)` definition of the function against the
+/// function's type. Types of the variables in `<head>` are inferred from the
+/// types of the arguments and then `<body>` is checked to have the return
+/// type of the function. Returns `None` if `atom` is not a definition,
+/// function has no type declared, or definition is typed correctly.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::{get_definition_error, TypeErrorKind};
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(: foo (-> A B)) (: bar (-> A A))")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(get_definition_error(&space, &expr!("=" ("foo" x) ("bar" x))).unwrap().kind,
+///     TypeErrorKind::BadReturnType{ expected: expr!("B"), actual: vec![expr!("A")] });
+/// assert_eq!(get_definition_error(&space, &expr!("=" ("bar" x) ("bar" x))), None);
+/// ```
+pub fn get_definition_error(space: &dyn Space, atom: &Atom) -> Option<TypeError> {
+    let (head, body) = match atom {
+        Atom::Expression(expr) => match expr.children().as_slice() {
+            [eq, head @ Atom::Expression(head_expr), body]
+                if *eq == EQUAL_SYMBOL && !head_expr.children().is_empty() => (head, body),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let op = match head {
+        Atom::Expression(expr) => get_op(expr),
+        _ => return None,
+    };
+    let mut errors = get_atom_types(space, op).into_iter()
+        .filter(is_func)
+        .map(|fn_type| get_definition_type_error(space, head, body, &fn_type));
+    // definition is correct when it is correct for any of the function types
+    let first = errors.next()?;
+    match first {
+        None => None,
+        Some(err) => if errors.any(|err| err.is_none()) { None } else { Some(err) },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -558,6 +1519,115 @@ mod tests {
         assert!(check_type(&space, &expr!("do" "you" "like" "music"), &sym!("Quest")));
     }
 
+    #[test]
+    fn super_types_are_updated_on_space_modification() {
+        let mut space = metta_space("
+            (:< Cat Mammal)
+            (:< Mammal Animal)
+            (: tom Cat)
+        ");
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal"), atom("Animal")]);
+
+        space.add(atom("(:< Animal Entity)"));
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal"), atom("Animal"), atom("Entity")]);
+        assert_eq!(get_sub_types(&space, &atom("Entity")), vec![atom("Animal"), atom("Mammal"), atom("Cat")]);
+
+        space.remove(&atom("(:< Mammal Animal)"));
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal")]);
+        assert_eq!(get_sub_types(&space, &atom("Entity")), vec![atom("Animal")]);
+
+        space.replace(&atom("(:< Cat Mammal)"), atom("(:< Cat Animal)"));
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Animal"), atom("Entity")]);
+        assert!(check_type(&space, &atom("tom"), &atom("Entity")));
+        assert!(!check_type(&space, &atom("tom"), &atom("Mammal")));
+    }
+
+    #[test]
+    fn super_types_are_updated_on_bulk_addition() {
+        let mut space = metta_space("(:< Cat Mammal)");
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal")]);
+
+        space.add_bulk(vec![atom("(:< Mammal Animal)")]);
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal"), atom("Animal")]);
+    }
+
+    /// Space which doesn't notify observers about modifications
+    struct SilentSpace {
+        common: crate::space::SpaceCommon,
+        space: std::cell::RefCell<GroundingSpace>,
+    }
+
+    impl std::fmt::Display for SilentSpace {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "SilentSpace")
+        }
+    }
+
+    impl std::fmt::Debug for SilentSpace {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "SilentSpace")
+        }
+    }
+
+    impl Space for SilentSpace {
+        fn common(&self) -> crate::common::FlexRef<crate::space::SpaceCommon> {
+            crate::common::FlexRef::from_simple(&self.common)
+        }
+        fn query(&self, query: &Atom) -> BindingsSet {
+            self.space.borrow().query(query)
+        }
+        fn as_any(&self) -> Option<&dyn std::any::Any> {
+            None
+        }
+        fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+            None
+        }
+    }
+
+    #[test]
+    fn super_types_of_space_without_notifications() {
+        let space = SilentSpace{ common: Default::default(),
+            space: std::cell::RefCell::new(metta_space("(:< Cat Mammal)")) };
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal")]);
+
+        space.space.borrow_mut().add(atom("(:< Mammal Animal)"));
+        assert_eq!(get_super_types(&space, &atom("Cat")), vec![atom("Mammal"), atom("Animal")]);
+    }
+
+    #[test]
+    fn super_types_of_parameterized_types() {
+        let mut space = metta_space("
+            (:< (List $t) Collection)
+            (:< (Set Number) NumberCollection)
+            (:< Collection Entity)
+        ");
+
+        assert_eq!(get_super_types(&space, &atom("(List Number)")), vec![atom("Collection"), atom("Entity")]);
+        assert_eq!(get_super_types(&space, &atom("(Set Number)")), vec![atom("NumberCollection")]);
+        assert_eq!(get_super_types(&space, &atom("(Set $t)")), vec![atom("NumberCollection")]);
+        assert_eq!(get_sub_types(&space, &atom("Entity")).len(), 2);
+
+        assert!(space.remove(&atom("(:< (List $t) Collection)")));
+        assert_eq!(get_super_types(&space, &atom("(List Number)")), Vec::<Atom>::new());
+    }
+
+    #[test]
+    fn least_upper_bounds() {
+        let space = metta_space("
+            (:< Cat Mammal)
+            (:< Cat Pet)
+            (:< Dog Mammal)
+            (:< Dog Pet)
+            (:< Mammal Animal)
+            (:< Pet Animal)
+        ");
+
+        assert_eq_no_order!(get_least_upper_bounds(&space, &atom("Cat"), &atom("Dog")),
+            vec![atom("Mammal"), atom("Pet")]);
+        assert_eq!(get_least_upper_bounds(&space, &atom("Cat"), &atom("Cat")), vec![atom("Cat")]);
+        assert_eq!(get_least_upper_bounds(&space, &atom("Mammal"), &atom("Pet")), vec![atom("Animal")]);
+    }
+
     #[test]
     fn nested_type() {
         let space = metta_space("
@@ -980,6 +2050,108 @@ mod tests {
         assert!(check_type(&space, &atom("(Refl TermGnd)"), &atom("(=== $a $b)")));
     }
 
+    #[test]
+    fn check_type_dependent_type_grounded_function() {
+        let space = metta_space("
+            (: vnil (Vec 0 $t))
+            (: vcons (-> $t (Vec $n $t) (Vec (+ $n 1) $t)))
+            (: vsnoc-of (-> (Vec $n $t) (Vec (+ $n 1) $t) Bool))
+            (: a A)
+            (: v1 (Vec 1 A))
+            (: v2 (Vec (+ 1 1) A))
+        ");
+
+        assert_eq!(get_atom_types(&space, &atom("(vcons a (vcons a vnil))")), vec![atom("(Vec 2 A)")]);
+        assert!(check_type(&space, &atom("v2"), &atom("(Vec 2 A)")));
+        assert!(check_type(&space, &atom("(vcons a v1)"), &atom("(Vec (+ 1 1) A)")));
+        assert!(!check_type(&space, &atom("(vcons a v1)"), &atom("(Vec 1 A)")));
+        assert!(validate_atom(&space, &atom("(vsnoc-of v1 v2)")));
+        assert!(!validate_atom(&space, &atom("(vsnoc-of v2 v1)")));
+        assert!(!validate_atom(&space, &atom("(vsnoc-of v1 v1)")));
+        assert!(validate_atom(&space, &atom("(vsnoc-of (vcons a v1) $v)")));
+        assert_eq!(infer_variable_types(&space, &atom("(vsnoc-of (vcons a v1) $v)")),
+            vec![bind!{ v: atom("(Vec 3 A)") }]);
+    }
+
+    #[test]
+    fn check_type_dependent_type_type_function() {
+        let space = metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (plus Z $n) $n)
+            (= (plus (S $m) $n) (S (plus $m $n)))
+            (: vappend (-> (Vec $n $t) (Vec $m $t) (Vec (plus $n $m) $t)))
+            (: v1 (Vec (S Z) A))
+            (: v2 (Vec (S (S Z)) A))
+        ");
+
+        assert!(check_type(&space, &atom("(vappend v1 v2)"), &atom("(Vec (S (S (S Z))) A)")));
+        assert!(check_type(&space, &atom("(vappend v2 v1)"), &atom("(Vec (plus (S Z) (S (S Z))) A)")));
+        assert!(!check_type(&space, &atom("(vappend v1 v1)"), &atom("(Vec (S (S (S Z))) A)")));
+        let types = get_atom_types(&space, &atom("(vappend v1 $v)"));
+        assert_eq!(types.len(), 1);
+        assert!(atoms_are_equivalent(&types[0], &atom("(Vec (plus (S Z) $m) A)")));
+    }
+
+    #[test]
+    fn reduce_type_stops_on_non_terminating_function() {
+        let space = metta_space("
+            (= (loop $x) (loop (S $x)))
+            (= (either) A)
+            (= (either) B)
+        ");
+
+        let mut unrolled = atom("Z");
+        for _ in 0..TYPE_REDUCTION_LIMIT {
+            unrolled = Atom::expr([atom("S"), unrolled]);
+        }
+        assert_eq!(reduce_type(&space, &atom("(Vec (loop Z) A)")),
+            Atom::expr([atom("Vec"), Atom::expr([atom("loop"), unrolled]), atom("A")]));
+        assert_eq!(reduce_type(&space, &atom("(Vec (either) A)")), atom("(Vec (either) A)"));
+    }
+
+    #[derive(Clone, Debug)]
+    struct SideEffectOp(std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl PartialEq for SideEffectOp {
+        fn eq(&self, other: &Self) -> bool {
+            std::rc::Rc::ptr_eq(&self.0, &other.0)
+        }
+    }
+
+    impl std::fmt::Display for SideEffectOp {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "side-effect")
+        }
+    }
+
+    impl Grounded for SideEffectOp {
+        fn type_(&self) -> Atom {
+            Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED])
+        }
+
+        fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+            self.0.set(self.0.get() + 1);
+            Ok(args.to_vec())
+        }
+
+        fn match_(&self, other: &Atom) -> crate::matcher::MatchResultIter {
+            match_by_equality(self, other)
+        }
+    }
+
+    #[test]
+    fn reduce_type_executes_only_arithmetic_operations() {
+        let space = metta_space("");
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let typ = Atom::expr([atom("Vec"), Atom::expr([Atom::gnd(SideEffectOp(calls.clone())),
+            Atom::gnd(Number::Integer(1))]), atom("A")]);
+
+        assert_eq!(reduce_type(&space, &typ), typ);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(reduce_type(&space, &atom("(Vec (+ 1 1) A)")), atom("(Vec 2 A)"));
+    }
+
     #[test]
     fn check_type_accept_meta_type() {
         let type_r = &atom("R");
@@ -1132,4 +2304,163 @@ mod tests {
         assert!(validate_atom(&space, &atom("(varF (varR a))")));
         assert!(!validate_atom(&space, &atom("(varF (atomR a))")));
     }
+
+    #[test]
+    fn get_type_error_reports_innermost_expression() {
+        let space = metta_space("
+            (: A Type)
+            (: B Type)
+            (: C Type)
+            (:< C B)
+            (: a A)
+            (: c C)
+            (: f (-> A B))
+            (: g (-> B A))
+        ");
+
+        assert_eq!(get_type_error(&space, &atom("(g (f (f c)))")), Some(TypeError{
+            atom: atom("(f c)"),
+            kind: TypeErrorKind::BadArgType{ position: 1, expected: atom("A"), actual: vec![atom("C"), atom("B")] },
+            declarations: vec![atom("(: f (-> A B))"), atom("(: c C)"), atom("(:< C B)")],
+        }));
+        assert_eq!(get_type_error(&space, &atom("(g (f a))")), None);
+    }
+
+    #[test]
+    fn get_type_error_reports_argument_position() {
+        let space = metta_space("
+            (: a A)
+            (: b B)
+            (: foo (-> A $t $t B))
+        ");
+
+        assert_eq!(get_type_error(&space, &atom("(foo a b a)")).map(|err| err.kind),
+            Some(TypeErrorKind::BadArgType{ position: 3, expected: atom("B"), actual: vec![atom("A")] }));
+        assert_eq!(get_type_error(&space, &atom("(foo a b)")).map(|err| err.kind),
+            Some(TypeErrorKind::IncorrectNumberOfArguments{ expected: 3, actual: 2 }));
+    }
+
+    #[test]
+    fn type_error_to_atom() {
+        let space = metta_space("(: foo (-> A B)) (: b B)");
+        let err = get_type_error(&space, &atom("(foo b)")).unwrap();
+
+        assert_eq!(err.to_atom(), expr!("BadArgType" ("foo" "b") {Number::Integer(1)} "A" ("B")
+            ((":" "foo" ("->" "A" "B")) (":" "b" "B"))));
+    }
+
+    #[test]
+    fn get_definition_error_infers_variable_types() {
+        let space = metta_space("
+            (: a A)
+            (: b B)
+            (: inc (-> A A))
+            (: show (-> A B))
+            (: hide (-> B B))
+            (: f (-> A A))
+        ");
+
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (inc $x))")), None);
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (show $x))")).map(|err| err.kind),
+            Some(TypeErrorKind::BadReturnType{ expected: atom("A"), actual: vec![atom("B")] }));
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (inc (hide $x)))")).map(|err| (err.atom, err.kind)),
+            Some((atom("(hide $x)"), TypeErrorKind::BadArgType{ position: 1, expected: atom("B"), actual: vec![atom("A")] })));
+        assert_eq!(get_definition_error(&space, &atom("(= (f b) a)")).map(|err| err.kind),
+            Some(TypeErrorKind::BadArgType{ position: 1, expected: atom("A"), actual: vec![atom("B")] }));
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x $y) $x)")).map(|err| err.kind),
+            Some(TypeErrorKind::IncorrectNumberOfArguments{ expected: 1, actual: 2 }));
+    }
+
+    #[test]
+    fn get_definition_error_accepts_typed_variable_as_meta_typed_arg() {
+        let space = metta_space("
+            (: f (-> A Atom))
+            (: var-arg (-> Variable Atom))
+            (: expr-arg (-> Expression Atom))
+            (: sym-arg (-> Symbol Atom))
+        ");
+
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (var-arg $x))")), None);
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (expr-arg $x))")), None);
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (sym-arg $x))")), None);
+    }
+
+    #[test]
+    fn get_definition_error_infers_nested_pattern_types() {
+        let space = metta_space("
+            (: Cons (-> $t (List $t) (List $t)))
+            (: Nil (List $t))
+            (: head (-> (List A) A))
+        ");
+
+        assert_eq!(get_definition_error(&space, &atom("(= (head (Cons $h $t)) $h)")), None);
+        assert_eq!(get_definition_error(&space, &atom("(= (head (Cons $h $t)) $t)")).map(|err| err.kind),
+            Some(TypeErrorKind::BadReturnType{ expected: atom("A"), actual: vec![atom("(List A)")] }));
+    }
+
+    #[test]
+    fn get_definition_error_ignores_untyped_functions() {
+        let space = metta_space("(: a A)");
+
+        assert_eq!(get_definition_error(&space, &atom("(= (f $x) (g $x))")), None);
+        assert_eq!(get_definition_error(&space, &atom("(f $x)")), None);
+    }
+
+    #[test]
+    fn infer_variable_types_carries_bindings_across_expression() {
+        let space = metta_space("
+            (: let-var (-> $t $t $r $r))
+            (: num (-> Number))
+            (: str (-> String))
+            (: plus (-> Number Number Number))
+        ");
+
+        assert_eq!(infer_variable_types(&space, &atom("(let-var $n (num) (plus $n $n))")),
+            vec![bind!{n: atom("Number")}]);
+        assert!(validate_atom(&space, &atom("(let-var $n (num) (plus $n $n))")));
+        assert_eq!(infer_variable_types(&space, &atom("(let-var $n (str) (plus $n $n))")), Vec::<Bindings>::new());
+        assert!(validate_atom(&space, &atom("(let-var $n (str) (plus $n $n))")));
+    }
+
+    #[test]
+    fn infer_variable_types_uses_super_types() {
+        let space = metta_space("
+            (:< Nat Number)
+            (: nat (-> Nat Nat))
+            (: pair (-> Nat Number Nat))
+            (: plus (-> Number Number Number))
+        ");
+
+        assert!(validate_atom(&space, &atom("(pair $x $x)")));
+        assert!(validate_atom(&space, &atom("(plus $x (nat $x))")));
+        assert!(validate_atom(&space, &atom("(plus (nat $x) $x)")));
+        assert_eq!(infer_variable_types(&space, &atom("(plus $x (nat $x))")), vec![bind!{x: atom("Nat")}]);
+        assert_eq!(infer_variable_types(&space, &atom("(plus (nat $x) $x)")), vec![bind!{x: atom("Nat")}]);
+    }
+
+    #[test]
+    fn infer_variable_types_rejects_unrelated_types() {
+        let space = metta_space("
+            (:< Nat Number)
+            (: nat (-> Nat Nat))
+            (: str (-> String String))
+            (: plus (-> Number Number Number))
+        ");
+
+        assert_eq!(infer_variable_types(&space, &atom("(plus $x (str $x))")), Vec::<Bindings>::new());
+        assert_eq!(infer_variable_types(&space, &atom("(plus (str $x) (nat $x))")), Vec::<Bindings>::new());
+        assert_eq!(infer_variable_types(&space, &atom("(nat (str $x))")), Vec::<Bindings>::new());
+    }
+
+    #[test]
+    fn get_type_error_reports_variable_type_conflict() {
+        let space = metta_space("
+            (: let-var (-> $t $t $r $r))
+            (: str (-> String))
+            (: plus (-> Number Number Number))
+        ");
+
+        assert_eq!(get_type_error(&space, &atom("(let-var $n (str) (plus $n $n))")).map(|err| (err.atom, err.kind)),
+            Some((atom("(plus $n $n)"), TypeErrorKind::BadArgType{ position: 1, expected: atom("Number"), actual: vec![atom("String")] })));
+    }
 }
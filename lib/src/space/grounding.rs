@@ -4,13 +4,13 @@
 use crate::*;
 use super::*;
 use crate::atom::*;
-use crate::atom::matcher::{BindingsSet, MatchResultIter, match_atoms};
+use crate::atom::matcher::{BindingsSet, MatchResultIter, match_atoms, atoms_are_equivalent};
 use crate::atom::subexpr::split_expr;
 use crate::common::multitrie::{MultiTrie, TrieKey, TrieToken};
 
 use std::fmt::{Display, Debug};
 use std::collections::BTreeSet;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 // Grounding space
 
@@ -47,10 +47,10 @@ impl<'a> Iterator for GroundingSpaceIter<'a> {
     }
 }
 
-fn atom_to_trie_key(atom: &Atom) -> TrieKey<SymbolAtom> {
-    fn fill_key(atom: &Atom, tokens: &mut Vec<TrieToken<SymbolAtom>>) {
+fn atom_to_trie_key(atom: &Atom) -> TrieKey<SymbolId> {
+    fn fill_key(atom: &Atom, tokens: &mut Vec<TrieToken<SymbolId>>) {
         match atom {
-            Atom::Symbol(sym) => tokens.push(TrieToken::Exact(sym.clone())),
+            Atom::Symbol(sym) => tokens.push(TrieToken::Exact(sym.id())),
             Atom::Expression(expr) => {
                 tokens.push(TrieToken::LeftPar);
                 expr.children().iter().for_each(|child| fill_key(child, tokens));
@@ -75,15 +75,38 @@ fn atom_to_trie_key(atom: &Atom) -> TrieKey<SymbolAtom> {
     TrieKey::from(tokens)
 }
 
+/// Returns alpha-equivalent copy of the `atom` with variables renamed in the
+/// order of their first occurrence. Two atoms are alpha-equivalent if and
+/// only if their canonical forms are equal.
+fn canonical_atom(atom: &Atom) -> Atom {
+    let mut atom = atom.clone();
+    let mut vars: HashMap<VariableAtom, VariableAtom> = HashMap::new();
+    atom.iter_mut().filter_type::<&mut VariableAtom>().for_each(|var| {
+        let next_id = vars.len();
+        *var = vars.entry(var.clone())
+            .or_insert_with(|| VariableAtom::new_id("", next_id)).clone();
+    });
+    atom
+}
+
+/// Position of the atom in the space content and number of times it was
+/// added. Used when space has set semantics.
+#[derive(Clone, Debug)]
+struct UniqueAtom {
+    position: usize,
+    multiplicity: usize,
+}
+
 /// In-memory space which can contain grounded atoms.
 // TODO: Clone is required by C API
 #[derive(Clone)]
 pub struct GroundingSpace {
-    index: MultiTrie<SymbolAtom, usize>,
+    index: MultiTrie<SymbolId, usize>,
     content: Vec<Atom>,
     free: BTreeSet<usize>,
     common: SpaceCommon,
     name: Option<String>,
+    unique: Option<HashMap<Atom, UniqueAtom>>,
 }
 
 impl GroundingSpace {
@@ -96,22 +119,130 @@ impl GroundingSpace {
             free: BTreeSet::new(),
             common: SpaceCommon::default(),
             name: None,
+            unique: None,
         }
     }
 
+    /// Constructs new empty space with set semantics. Such space keeps a
+    /// single copy of the alpha-equivalent atoms: adding an atom which is
+    /// equivalent to the atom already in the space only increments its
+    /// multiplicity and removing it decrements the multiplicity. The atom
+    /// is removed from the space when its multiplicity becomes zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::{expr, bind_set};
+    /// use hyperon::space::Space;
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let mut space = GroundingSpace::new_set();
+    ///
+    /// space.add(expr!("=" ("f" x) x));
+    /// space.add(expr!("=" ("f" y) y));
+    ///
+    /// assert_eq!(space.atom_count(), Some(1));
+    /// assert_eq!(space.multiplicity(&expr!("=" ("f" z) z)), 2);
+    /// assert_eq!(space.query(&expr!("=" ("f" "a") r)), bind_set![{r: expr!("a")}]);
+    /// ```
+    pub fn new_set() -> Self {
+        let mut space = Self::new();
+        space.unique = Some(HashMap::new());
+        space
+    }
+
+    /// Returns true if space has set semantics, see [GroundingSpace::new_set].
+    pub fn is_set(&self) -> bool {
+        self.unique.is_some()
+    }
+
     /// Constructs space from vector of atoms.
     pub fn from_vec(atoms: Vec<Atom>) -> Self {
-        let mut index = MultiTrie::new();
-        for (i, atom) in atoms.iter().enumerate() {
-            index.insert(atom_to_trie_key(atom), i);
-        }
-        Self{
-            index,
-            content: atoms,
-            free: BTreeSet::new(),
-            common: SpaceCommon::default(),
-            name: None,
+        let mut space = Self::new();
+        space.add_bulk(atoms);
+        space
+    }
+
+    /// Adds `atoms` into space in a single batch. The index is built for the
+    /// whole batch at once and observers are not notified about the atoms
+    /// added, the batch is recorded by [SpaceCommon::mark_unobserved_change]
+    /// instead. Atoms are always appended to the end of the space content, so
+    /// they are returned by [Space::atom_iter] after all previously added atoms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::{expr, bind_set, sym};
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let mut space = GroundingSpace::new();
+    ///
+    /// space.add_bulk(vec![expr!("A" "B"), expr!("B" "C")]);
+    ///
+    /// assert_eq!(space.query(&expr!("A" x)), bind_set![{x: sym!("B")}]);
+    /// assert_eq!(space.query(&expr!(x "C")), bind_set![{x: sym!("B")}]);
+    /// ```
+    pub fn add_bulk<I: IntoIterator<Item=Atom>>(&mut self, atoms: I) {
+        self.add_bulk_positions(atoms);
+    }
+
+    /// Adds `atoms` the same way [GroundingSpace::add_bulk] does and returns
+    /// the positions of the added atoms in the space content. The addition
+    /// can be rolled back using [GroundingSpace::remove_bulk_added].
+    pub(crate) fn add_bulk_positions<I: IntoIterator<Item=Atom>>(&mut self, atoms: I) -> Vec<usize> {
+        let start = self.content.len();
+        let positions = match self.unique.as_mut() {
+            None => {
+                self.content.extend(atoms);
+                (start..self.content.len()).collect()
+            },
+            Some(unique) => {
+                let mut positions = Vec::new();
+                for atom in atoms {
+                    let position = self.content.len();
+                    let entry = unique.entry(canonical_atom(&atom))
+                        .or_insert(UniqueAtom{ position, multiplicity: 0 });
+                    entry.multiplicity += 1;
+                    positions.push(entry.position);
+                    if entry.position == position {
+                        self.content.push(atom);
+                    }
+                }
+                positions
+            },
+        };
+        let keys = self.content[start..].iter().enumerate()
+            .map(|(i, atom)| (atom_to_trie_key(atom), start + i));
+        self.index.insert_batch(keys);
+        self.common.mark_unobserved_change();
+        positions
+    }
+
+    /// Returns the atom added by [GroundingSpace::add_bulk_positions] at
+    /// the `position`.
+    pub(crate) fn bulk_added(&self, position: usize) -> &Atom {
+        &self.content[position]
+    }
+
+    /// Removes the atom added by [GroundingSpace::add_bulk_positions] at
+    /// the `position`. Unlike [GroundingSpace::remove] it keeps other atoms
+    /// equal to the removed one in the space and doesn't notify observers.
+    pub(crate) fn remove_bulk_added(&mut self, position: usize) {
+        let atom = &self.content[position];
+        if let Some(unique) = self.unique.as_mut() {
+            let key = canonical_atom(atom);
+            match unique.get_mut(&key) {
+                Some(entry) if entry.multiplicity > 1 => {
+                    entry.multiplicity -= 1;
+                    return;
+                },
+                Some(_) => { unique.remove(&key); },
+                None => panic!("Atom added in bulk is not found: {}", atom),
+            }
         }
+        self.index.remove(&atom_to_trie_key(&self.content[position]), &position);
+        self.free.insert(position);
+        self.common.mark_unobserved_change();
     }
 
     /// Adds `atom` into space.
@@ -133,20 +264,80 @@ impl GroundingSpace {
     /// ```
     pub fn add(&mut self, atom: Atom) {
         //log::debug!("GroundingSpace::add(): self: {:?}, atom: {:?}", self as *const GroundingSpace, atom);
+        if self.add_reference(&atom) {
+            return;
+        }
         self.add_internal(atom.clone());
         self.common.notify_all_observers(&SpaceEvent::Add(atom));
     }
 
+    /// Increments multiplicity of the `atom` when space has set semantics
+    /// and the equivalent atom is already in the space. Returns true if
+    /// multiplicity was incremented.
+    fn add_reference(&mut self, atom: &Atom) -> bool {
+        match self.unique.as_mut().and_then(|unique| unique.get_mut(&canonical_atom(atom))) {
+            Some(entry) => {
+                entry.multiplicity += 1;
+                true
+            },
+            None => false,
+        }
+    }
+
     fn add_internal(&mut self, atom: Atom) {
-        if self.free.is_empty() {
-            let pos = self.content.len();
-            self.index.insert(atom_to_trie_key(&atom), pos);
-            self.content.push(atom);
+        let pos = match self.free.iter().next().cloned() {
+            None => {
+                self.content.push(atom);
+                self.content.len() - 1
+            },
+            Some(pos) => {
+                self.free.remove(&pos);
+                self.content[pos] = atom;
+                pos
+            },
+        };
+        let atom = &self.content[pos];
+        self.index.insert(atom_to_trie_key(atom), pos);
+        if let Some(unique) = self.unique.as_mut() {
+            unique.insert(canonical_atom(atom), UniqueAtom{ position: pos, multiplicity: 1 });
+        }
+    }
+
+    /// Adds `atom` into space only if there is no alpha-equivalent atom in
+    /// the space yet. Returns true if atom was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::expr;
+    /// use hyperon::space::Space;
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let mut space = GroundingSpace::new();
+    ///
+    /// assert!(space.add_unique(expr!("=" ("f" x) x)));
+    /// assert!(!space.add_unique(expr!("=" ("f" y) y)));
+    /// assert_eq!(space.atom_count(), Some(1));
+    /// ```
+    pub fn add_unique(&mut self, atom: Atom) -> bool {
+        if self.multiplicity(&atom) > 0 {
+            false
         } else {
-            let pos = *self.free.iter().next().unwrap();
-            self.free.remove(&pos);
-            self.index.insert(atom_to_trie_key(&atom), pos);
-            self.content[pos] = atom;
+            self.add(atom);
+            true
+        }
+    }
+
+    /// Returns number of atoms in the space which are alpha-equivalent to
+    /// the `atom`. When space has set semantics returns multiplicity of
+    /// the atom.
+    pub fn multiplicity(&self, atom: &Atom) -> usize {
+        match self.unique.as_ref() {
+            Some(unique) => unique.get(&canonical_atom(atom))
+                .map_or(0, |entry| entry.multiplicity),
+            None => self.index.get(&atom_to_trie_key(atom))
+                .filter(|i| atoms_are_equivalent(&self.content[**i], atom))
+                .count(),
         }
     }
 
@@ -168,6 +359,9 @@ impl GroundingSpace {
     /// ```
     pub fn remove(&mut self, atom: &Atom) -> bool {
         //log::debug!("GroundingSpace::remove(): self: {:?}, atom: {:?}", self as *const GroundingSpace, atom);
+        if self.remove_reference(atom) {
+            return true;
+        }
         let is_removed = self.remove_internal(atom);
         if is_removed {
             self.common.notify_all_observers(&SpaceEvent::Remove(atom.clone()));
@@ -175,7 +369,31 @@ impl GroundingSpace {
         is_removed
     }
 
+    /// Decrements multiplicity of the `atom` when space has set semantics
+    /// and the atom is referenced more than once. Returns true if
+    /// multiplicity was decremented.
+    fn remove_reference(&mut self, atom: &Atom) -> bool {
+        match self.unique.as_mut().and_then(|unique| unique.get_mut(&canonical_atom(atom))) {
+            Some(entry) if entry.multiplicity > 1 => {
+                entry.multiplicity -= 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
     fn remove_internal(&mut self, atom: &Atom) -> bool {
+        if let Some(unique) = self.unique.as_mut() {
+            return match unique.remove(&canonical_atom(atom)) {
+                Some(UniqueAtom{ position, .. }) => {
+                    let index_key = atom_to_trie_key(&self.content[position]);
+                    self.index.remove(&index_key, &position);
+                    self.free.insert(position);
+                    true
+                },
+                None => false,
+            }
+        }
         let index_key = atom_to_trie_key(atom);
         let indexes: Vec<usize> = self.index.get(&index_key).map(|i| *i).collect();
         let mut indexes: Vec<usize> = indexes.into_iter()
@@ -208,6 +426,15 @@ impl GroundingSpace {
     /// assert_eq!(space.query(&sym!("B")), BindingsSet::single());
     /// ```
     pub fn replace(&mut self, from: &Atom, to: Atom) -> bool {
+        if self.is_set() {
+            // Replacing an atom in a set can either change the multiplicity
+            // or the content of the space, thus it is split on two operations
+            let is_replaced = self.remove(from);
+            if is_replaced {
+                self.add(to);
+            }
+            return is_replaced;
+        }
         let is_replaced = self.replace_internal(from, to.clone());
         if is_replaced {
             self.common.notify_all_observers(&SpaceEvent::Replace(from.clone(), to));
@@ -316,6 +543,12 @@ impl Space for GroundingSpace {
     fn atom_iter(&self) -> Option<SpaceIter> {
         Some(self.iter())
     }
+    fn multiplicity(&self, atom: &Atom) -> Option<usize> {
+        Some(GroundingSpace::multiplicity(self, atom))
+    }
+    fn notifies_observers(&self) -> bool {
+        true
+    }
     fn as_any(&self) -> Option<&dyn std::any::Any> {
         Some(self)
     }
@@ -334,6 +567,9 @@ impl SpaceMut for GroundingSpace {
     fn replace(&mut self, from: &Atom, to: Atom) -> bool {
         GroundingSpace::replace(self, from, to)
     }
+    fn add_unique(&mut self, atom: Atom) -> bool {
+        GroundingSpace::add_unique(self, atom)
+    }
     fn as_space(&self) -> &dyn Space {
         self
     }
@@ -455,6 +691,18 @@ mod test {
             SpaceEvent::Replace(sym!("b"), sym!("d"))]);
     }
 
+    #[test]
+    fn add_bulk_does_not_notify_observers() {
+        let mut space = GroundingSpace::new();
+        let observer = space.common.register_observer(SpaceEventCollector::new());
+
+        space.add_bulk(vec![expr!("a"), expr!("b")]);
+
+        assert_eq_no_order!(space, vec![expr!("a"), expr!("b")]);
+        assert_eq!(observer.borrow().events, vec![]);
+        assert_eq!(space.common.unobserved_changes(), 1);
+    }
+
     #[test]
     fn replace_atom_not_found() {
         let mut space = GroundingSpace::new();
@@ -524,6 +772,65 @@ mod test {
         assert_eq_no_order!(second, vec![expr!("d")]);
     }
 
+    #[test]
+    fn set_space_keeps_single_copy_of_equivalent_atoms() {
+        let mut space = GroundingSpace::new_set();
+        let observer = space.common.register_observer(SpaceEventCollector::new());
+
+        space.add(expr!("=" ("f" x) x));
+        space.add(expr!("=" ("f" y) y));
+        space.add(expr!("=" ("f" y) "y"));
+
+        assert_eq_no_order!(space, vec![expr!("=" ("f" x) x), expr!("=" ("f" y) "y")]);
+        assert_eq!(space.multiplicity(&expr!("=" ("f" z) z)), 2);
+        assert_eq!(space.multiplicity(&expr!("=" ("f" z) "z")), 0);
+        assert_eq!(observer.borrow().events, vec![
+            SpaceEvent::Add(expr!("=" ("f" x) x)),
+            SpaceEvent::Add(expr!("=" ("f" y) "y"))]);
+    }
+
+    #[test]
+    fn set_space_remove_decrements_multiplicity() {
+        let mut space = GroundingSpace::new_set();
+        let observer = space.common.register_observer(SpaceEventCollector::new());
+
+        space.add(expr!("a" x));
+        space.add(expr!("a" y));
+        assert_eq!(space.remove(&expr!("a" z)), true);
+        assert_eq_no_order!(space, vec![expr!("a" x)]);
+        assert_eq!(space.remove(&expr!("a" z)), true);
+        assert_eq_no_order!(space, Vec::<Atom>::new());
+        assert_eq!(space.remove(&expr!("a" z)), false);
+
+        assert_eq!(observer.borrow().events, vec![SpaceEvent::Add(expr!("a" x)),
+            SpaceEvent::Remove(expr!("a" z))]);
+        space.add(expr!("a" "b"));
+        assert_eq!(space.query(&expr!("a" v)), bind_set![{v: expr!("b")}]);
+    }
+
+    #[test]
+    fn set_space_add_bulk_removes_duplicates() {
+        let mut space = GroundingSpace::new_set();
+        space.add_bulk(vec![expr!("a" x), expr!("b"), expr!("a" y)]);
+
+        assert_eq_no_order!(space, vec![expr!("a" x), expr!("b")]);
+        assert_eq!(space.multiplicity(&expr!("a" z)), 2);
+        assert_eq!(space.query(&expr!("a" "c")), BindingsSet::single());
+    }
+
+    #[test]
+    fn add_unique_rejects_equivalent_atom() {
+        let mut space = GroundingSpace::new();
+        space.add(expr!("=" ("f" x) x));
+
+        assert_eq!(space.add_unique(expr!("=" ("f" y) y)), false);
+        assert_eq!(space.add_unique(expr!("=" ("f" y) "y")), true);
+        assert_eq_no_order!(space, vec![expr!("=" ("f" x) x), expr!("=" ("f" y) "y")]);
+
+        space.add(expr!("=" ("f" z) z));
+        assert_eq!(space.multiplicity(&expr!("=" ("f" v) v)), 2);
+    }
+
     #[test]
     fn test_match_symbol() {
         let mut space = GroundingSpace::new();
@@ -676,15 +983,19 @@ mod test {
         assert_eq!(result, bind_set![{x: sym!("a")}]);
     }
 
+    fn symbol_id(name: &str) -> SymbolId {
+        <&SymbolAtom>::try_from(&Atom::sym(name)).unwrap().id()
+    }
+
     #[test]
     fn index_atom_to_key() {
-        assert_eq!(atom_to_trie_key(&Atom::sym("A")), TrieKey::from([TrieToken::Exact(SymbolAtom::new("A".into()))]));
+        assert_eq!(atom_to_trie_key(&Atom::sym("A")), TrieKey::from([TrieToken::Exact(symbol_id("A"))]));
         assert_eq!(atom_to_trie_key(&Atom::value(1)), TrieKey::from([TrieToken::Wildcard]));
         assert_eq!(atom_to_trie_key(&Atom::var("a")), TrieKey::from([TrieToken::Wildcard]));
         assert_eq!(atom_to_trie_key(&expr!("A" "B")), TrieKey::from([
                 TrieToken::LeftPar,
-                TrieToken::Exact(SymbolAtom::new("A".into())),
-                TrieToken::Exact(SymbolAtom::new("B".into())),
+                TrieToken::Exact(symbol_id("A")),
+                TrieToken::Exact(symbol_id("B")),
                 TrieToken::RightPar
         ]));
     }
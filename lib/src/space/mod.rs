@@ -5,11 +5,13 @@ pub mod grounding;
 
 use std::fmt::Display;
 use std::rc::{Rc, Weak};
-use std::cell::{RefCell, Ref, RefMut};
+use std::cell::{Cell, RefCell, Ref, RefMut};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 
 use crate::common::FlexRef;
 use crate::atom::*;
-use crate::atom::matcher::{BindingsSet, apply_bindings_to_atom};
+use crate::atom::matcher::{BindingsSet, apply_bindings_to_atom, atoms_are_equivalent};
 
 /// Contains information about space modification event.
 #[derive(Clone, Debug, PartialEq)]
@@ -110,6 +112,8 @@ impl<'a> Iterator for SpaceIter<'a> {
 #[derive(Default)]
 pub struct SpaceCommon {
     observers: RefCell<Vec<Weak<RefCell<dyn SpaceObserver>>>>,
+    owned_observers: RefCell<HashMap<TypeId, Rc<dyn Any>>>,
+    unobserved_changes: Cell<usize>,
 }
 impl SpaceCommon {
     /// Registers space modifications `observer`. Observer is automatically deregistered when
@@ -122,6 +126,60 @@ impl SpaceCommon {
         SpaceObserverRef(observer_ref)
     }
 
+    /// Returns the observer of type `T` owned by the space. On the first call
+    /// the observer is created by `init` and registered, next calls return
+    /// the same instance. Owned observer lives as long as the space does,
+    /// thus it can be used to keep the data derived from the space content
+    /// (for instance a cache) in sync with the space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::sym;
+    /// use hyperon::space::*;
+    /// use hyperon::space::grounding::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Counter(usize);
+    ///
+    /// impl SpaceObserver for Counter {
+    ///     fn notify(&mut self, _event: &SpaceEvent) {
+    ///         self.0 += 1;
+    ///     }
+    /// }
+    ///
+    /// let mut space = GroundingSpace::new();
+    /// space.common().owned_observer(Counter::default);
+    ///
+    /// space.add(sym!("A"));
+    /// space.add(sym!("B"));
+    ///
+    /// assert_eq!(space.common().owned_observer(Counter::default).borrow().0, 2);
+    /// ```
+    pub fn owned_observer<T: SpaceObserver + 'static, F: FnOnce() -> T>(&self, init: F) -> SpaceObserverRef<T> {
+        let observer = Rc::clone(self.owned_observers.borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| self.register_observer(init()).into_inner() as Rc<dyn Any>));
+        match observer.downcast::<RefCell<T>>() {
+            Ok(observer) => SpaceObserverRef(observer),
+            Err(_) => unreachable!("Owned observers are indexed by type"),
+        }
+    }
+
+    /// Records the space modification which observers are not notified
+    /// about, for instance adding atoms in bulk.
+    pub fn mark_unobserved_change(&self) {
+        self.unobserved_changes.set(self.unobserved_changes.get() + 1);
+    }
+
+    /// Returns the number of modifications recorded by
+    /// [SpaceCommon::mark_unobserved_change]. Owned observers which keep the
+    /// data derived from the space content compare it with the value seen
+    /// when the data was built to find out that the data should be rebuilt.
+    pub fn unobserved_changes(&self) -> usize {
+        self.unobserved_changes.get()
+    }
+
     /// Notifies all registered observers about space modification `event`.
     pub fn notify_all_observers(&self, event: &SpaceEvent) {
         let mut cleanup = false;
@@ -144,6 +202,8 @@ impl Clone for SpaceCommon {
             //We don't want to clone observers when a space is cloned, as that leads to a situation
             // where an observer can't know which space an event pertains to
             observers: RefCell::new(vec![]),
+            owned_observers: RefCell::new(HashMap::new()),
+            unobserved_changes: Cell::new(0),
         }
     }
 }
@@ -207,6 +267,20 @@ pub trait Space: std::fmt::Debug + std::fmt::Display {
         None
     }
 
+    /// Returns the number of atoms in the space which are alpha-equivalent
+    /// to the `atom`, or None if this can't be determined. Spaces with set
+    /// semantics return the number of times the atom was added.
+    fn multiplicity(&self, atom: &Atom) -> Option<usize> {
+        self.atom_iter().map(|iter| iter.filter(|a| atoms_are_equivalent(a, atom)).count())
+    }
+
+    /// Returns true if the space notifies observers about its modifications
+    /// or records them using [SpaceCommon::mark_unobserved_change]. Only the
+    /// data derived from the content of such spaces can be cached.
+    fn notifies_observers(&self) -> bool {
+        false
+    }
+
     /// Returns an &dyn [Any] for spaces where this is possible
     fn as_any(&self) -> Option<&dyn std::any::Any>;
 
@@ -273,6 +347,32 @@ pub trait SpaceMut: Space {
     /// ```
     fn replace(&mut self, from: &Atom, to: Atom) -> bool;
 
+    /// Adds `atom` into space only if space doesn't contain an atom which is
+    /// alpha-equivalent to it. Returns true if atom was added, and false
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hyperon::expr;
+    /// use hyperon::space::{Space, SpaceMut};
+    /// use hyperon::space::grounding::GroundingSpace;
+    ///
+    /// let mut space = GroundingSpace::from_vec(vec![expr!("A" x)]);
+    ///
+    /// assert!(!SpaceMut::add_unique(&mut space, expr!("A" y)));
+    /// assert!(SpaceMut::add_unique(&mut space, expr!("A" "B")));
+    /// assert_eq!(space.atom_count(), Some(2));
+    /// ```
+    fn add_unique(&mut self, atom: Atom) -> bool {
+        if self.as_space().multiplicity(&atom).unwrap_or(0) > 0 {
+            false
+        } else {
+            self.add(atom);
+            true
+        }
+    }
+
     /// Turn a &dyn SpaceMut into an &dyn Space.  Obsolete when Trait Upcasting is stabilized.
     /// https://github.com/rust-lang/rust/issues/65991  Any month now.
     fn as_space(&self) -> &dyn Space;
@@ -320,6 +420,9 @@ impl SpaceMut for DynSpace {
     fn replace(&mut self, from: &Atom, to: Atom) -> bool {
         self.0.borrow_mut().replace(from, to)
     }
+    fn add_unique(&mut self, atom: Atom) -> bool {
+        self.0.borrow_mut().add_unique(atom)
+    }
     fn as_space(&self) -> &dyn Space {
         self
     }
@@ -338,6 +441,12 @@ impl Space for DynSpace {
     fn atom_count(&self) -> Option<usize> {
         self.0.borrow().atom_count()
     }
+    fn multiplicity(&self, atom: &Atom) -> Option<usize> {
+        self.0.borrow().multiplicity(atom)
+    }
+    fn notifies_observers(&self) -> bool {
+        self.0.borrow().notifies_observers()
+    }
     fn atom_iter(&self) -> Option<SpaceIter> {
         None
     }
@@ -367,6 +476,10 @@ impl crate::atom::Grounded for DynSpace {
     fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
         execute_not_executable(self)
     }
+
+    fn hash_gnd(&self, state: &mut dyn std::hash::Hasher) {
+        state.write_usize(RefCell::as_ptr(&self.0) as *const () as usize);
+    }
 }
 
 impl<T: Space> Space for &T {
@@ -385,6 +498,12 @@ impl<T: Space> Space for &T {
     fn atom_iter(&self) -> Option<SpaceIter> {
         T::atom_iter(*self)
     }
+    fn multiplicity(&self, atom: &Atom) -> Option<usize> {
+        T::multiplicity(*self, atom)
+    }
+    fn notifies_observers(&self) -> bool {
+        T::notifies_observers(*self)
+    }
     fn as_any(&self) -> Option<&dyn std::any::Any> {
         None
     }
@@ -482,6 +482,14 @@ def validate_atom(gnd_space, atom):
     """
     return hp.validate_atom(gnd_space.cspace, atom.catom)
 
+def get_type_error(gnd_space, atom):
+    """
+    Explains why the given Atom is not correctly typed. Returns None if the
+    Atom is correctly typed.
+    """
+    raw_atom = hp.get_type_error(gnd_space.cspace, atom.catom)
+    return None if raw_atom is None else Atom._from_catom(raw_atom)
+
 def get_atom_types(gnd_space, atom):
     """Provides all types for the given Atom in the context of the given Space."""
     result = hp.get_atom_types(gnd_space.cspace, atom.catom)
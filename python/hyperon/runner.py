@@ -207,16 +207,16 @@ class Environment:
         else:
             return None
 
-    def init_common_env(working_dir = None, config_dir = None, create_config = False, disable_config = False, is_test = False, include_paths = []):
+    def init_common_env(working_dir = None, config_dir = None, create_config = False, disable_config = False, is_test = False, include_paths = [], random_seed = None, allowed_paths = [], allowed_env_vars = []):
         """Initialize the common environment with the supplied args"""
-        builder = Environment.custom_env(working_dir, config_dir, create_config, disable_config, is_test, include_paths)
+        builder = Environment.custom_env(working_dir, config_dir, create_config, disable_config, is_test, include_paths, random_seed, allowed_paths, allowed_env_vars)
         return hp.env_builder_init_common_env(builder)
 
     def test_env():
         """Returns an EnvBuilder object specifying a unit-test environment, that can be used to init a MeTTa runner"""
         return hp.env_builder_use_test_env()
 
-    def custom_env(working_dir = None, config_dir = None, create_config = False, disable_config = False, is_test = False, include_paths = []):
+    def custom_env(working_dir = None, config_dir = None, create_config = False, disable_config = False, is_test = False, include_paths = [], random_seed = None, allowed_paths = [], allowed_env_vars = []):
         """Returns an EnvBuilder object that can be used to init a MeTTa runner, if you need multiple environments to coexist in the same process"""
         builder = hp.env_builder_start()
         if (working_dir is not None):
@@ -231,4 +231,10 @@ class Environment:
             hp.env_builder_set_is_test(True)
         for path in reversed(include_paths):
             hp.env_builder_add_include_path(builder, path)
+        if (random_seed is not None):
+            hp.env_builder_set_random_seed(builder, random_seed)
+        for path in allowed_paths:
+            hp.env_builder_add_allowed_path(builder, path)
+        for name in allowed_env_vars:
+            hp.env_builder_add_allowed_env_var(builder, name)
         return builder
@@ -780,6 +780,10 @@ PYBIND11_MODULE(hyperonpy, m) {
     m.def("validate_atom", [](CSpace space, CAtom& atom) {
             return validate_atom(space.ptr(), atom.ptr());
         }, "Validate expression arguments correspond to the operation type");
+    m.def("get_type_error", [](CSpace space, CAtom& atom) -> nonstd::optional<CAtom> {
+            auto const res = get_type_error(space.ptr(), atom.ptr());
+            return atom_is_null(&res) ? nonstd::nullopt : nonstd::optional<CAtom>(CAtom(res));
+        }, "Explain why expression is not typed correctly");
     m.def("get_atom_types", [](CSpace space, CAtom& atom) {
             py::list atoms;
             get_atom_types(space.ptr(), atom.ptr(), copy_atoms, &atoms);
@@ -861,6 +865,9 @@ PYBIND11_MODULE(hyperonpy, m) {
     m.def("env_builder_create_config_dir", [](EnvBuilder& builder) { env_builder_create_config_dir(builder.ptr()); }, "Creates the config dir if it doesn't exist");
     m.def("env_builder_disable_config_dir", [](EnvBuilder& builder) { env_builder_disable_config_dir(builder.ptr()); }, "Disables the config dir in the environment");
     m.def("env_builder_set_is_test", [](EnvBuilder& builder, bool is_test) { env_builder_set_is_test(builder.ptr(), is_test); }, "Disables the config dir in the environment");
+    m.def("env_builder_add_allowed_path", [](EnvBuilder& builder, std::string path) { env_builder_add_allowed_path(builder.ptr(), path.c_str()); }, "Adds a directory which MeTTa code is allowed to access");
+    m.def("env_builder_add_allowed_env_var", [](EnvBuilder& builder, std::string name) { env_builder_add_allowed_env_var(builder.ptr(), name.c_str()); }, "Adds an environment variable which MeTTa code is allowed to read");
+    m.def("env_builder_set_random_seed", [](EnvBuilder& builder, uint64_t seed) { env_builder_set_random_seed(builder.ptr(), seed); }, "Sets the seed of the random generators in the environment");
     m.def("env_builder_add_include_path", [](EnvBuilder& builder, std::string path) { env_builder_add_include_path(builder.ptr(), path.c_str()); }, "Adds an include path to the environment");
 
     m.def("gnd_get_int", [](CAtom atom) -> py::object {
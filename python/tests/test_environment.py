@@ -1,6 +1,6 @@
 import unittest
 
-from hyperon import Environment
+from hyperon import Environment, MeTTa
 
 class HyperonTestCase(unittest.TestCase):
 
@@ -12,3 +12,10 @@ class HyperonTestCase(unittest.TestCase):
         self.assertEqual(Environment.config_dir(), "/tmp/test_dir")
 
         self.assertFalse(Environment.init_common_env(disable_config = True))
+
+    def testRandomSeed(self):
+        def random_ints():
+            metta = MeTTa(env_builder = Environment.custom_env(disable_config = True, random_seed = 42))
+            return metta.run("!(random-int 0 99) !(random-int 0 99) !(random-int 0 99)")
+
+        self.assertEqual(random_ints(), random_ints())
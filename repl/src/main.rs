@@ -31,6 +31,11 @@ struct CliArgs {
     /// Additional include directory paths
     #[arg(short, long)]
     include_paths: Vec<PathBuf>,
+
+    /// Load the supplied files into the space using the bulk loader, without executing them,
+    /// and then enter interactive mode
+    #[arg(long)]
+    bulk: bool,
 }
 
 fn main() -> Result<()> {
@@ -82,6 +87,14 @@ fn main() -> Result<()> {
         }
     });
 
+    //If we are asked to bulk load the files, load all of them and then go interactive
+    if cli_args.bulk {
+        for kb_file in cli_args.files.iter() {
+            metta.load_bulk(kb_file.clone());
+        }
+        return start_interactive_mode(repl_params, metta).map_err(|err| err.into());
+    }
+
     //If we have .metta files to run, then run them
     if let Some(metta_file) = primary_metta_file {
 
@@ -120,6 +120,12 @@ pub mod metta_interface_mod {
             }).unwrap();
         }
 
+        pub fn load_bulk(&mut self, path: PathBuf) {
+            //TODO: HyperonPy doesn't expose the bulk loader yet, so the file is imported as a regular module
+            eprintln!("Bulk loading is not supported by the Python-based repl, importing {} as a module", path.display());
+            self.load_metta_module(path);
+        }
+
         pub fn exec(&mut self, line: &str) {
 
             //Initialize the runner state
@@ -324,7 +330,7 @@ pub mod metta_interface_mod {
     use hyperon::atom::{Grounded, ExecError, match_by_equality};
     use hyperon::matcher::MatchResultIter;
     use hyperon::metta::*;
-    use hyperon::metta::text::SExprParser;
+    use hyperon::metta::text::{SExprParser, StreamParser};
     use hyperon::ExpressionAtom;
     use hyperon::Atom;
     use hyperon::metta::runner::{Metta, RunnerState, Environment, EnvBuilder};
@@ -372,6 +378,26 @@ pub mod metta_interface_mod {
             self.metta.load_module(module).unwrap();
         }
 
+        pub fn load_bulk(&mut self, path: PathBuf) {
+            let file = match std::fs::File::open(&path) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("Could not read file, path: {}, error: {}", path.display(), err);
+                    return;
+                }
+            };
+            let parser = StreamParser::new(std::io::BufReader::new(file));
+            match self.metta.load_bulk(parser, self.metta.is_type_check_enabled()) {
+                Ok(stats) => {
+                    for error in stats.rejected.iter() {
+                        println!("{error}");
+                    }
+                    println!("{}: {stats}", path.display());
+                },
+                Err(err) => eprintln!("Bulk loading failed, path: {}, error: {}", path.display(), err),
+            }
+        }
+
         pub fn parse_and_unroll_syntax_tree(&self, line: &str) -> Vec<(SyntaxNodeType, std::ops::Range<usize>)> {
 
             let mut nodes = vec![];